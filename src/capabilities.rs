@@ -0,0 +1,126 @@
+/// Oldest RPC version whose torrent/session fields this client relies on.
+pub const MIN_SUPPORTED_RPC_VERSION: i64 = 15;
+/// Newest RPC version this client speaks natively (Transmission 4.1, JSON-RPC 2.0).
+pub const MAX_KNOWN_RPC_VERSION: i64 = 18;
+
+/// Feature set negotiated from `rpc_version`, `rpc_version_minimum` and
+/// `rpc_version_semver` during the startup handshake.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    pub rpc_version: i64,
+    pub rpc_version_minimum: i64,
+    pub rpc_version_semver: Option<String>,
+    pub json_rpc: bool,
+    pub labels: bool,
+    pub table_format: bool,
+    pub bandwidth_groups: bool,
+    pub torrent_reannounce: bool,
+    pub file_count: bool,
+}
+
+impl Capabilities {
+    pub fn from_versions(
+        rpc_version: i64,
+        rpc_version_minimum: i64,
+        rpc_version_semver: Option<String>,
+    ) -> Self {
+        let semver_major = rpc_version_semver
+            .as_deref()
+            .and_then(|value| value.split('.').next())
+            .and_then(|major| major.parse::<u64>().ok());
+        Self {
+            json_rpc: rpc_version >= 18 || semver_major.is_some_and(|major| major >= 6),
+            labels: rpc_version >= 16,
+            table_format: rpc_version >= 16,
+            bandwidth_groups: rpc_version >= 17,
+            torrent_reannounce: rpc_version >= 5,
+            file_count: rpc_version >= 17,
+            rpc_version,
+            rpc_version_minimum,
+            rpc_version_semver,
+        }
+    }
+
+    /// Short version string for the session header, e.g. `rpc 18 (6.0.0)`.
+    pub fn label(&self) -> String {
+        match &self.rpc_version_semver {
+            Some(semver) => format!("rpc {} ({semver})", self.rpc_version),
+            None => format!("rpc {}", self.rpc_version),
+        }
+    }
+
+    /// Warning to surface when the daemon and this client cannot fully agree on
+    /// a protocol version.
+    pub fn compatibility_warning(&self) -> Option<String> {
+        if self.rpc_version < MIN_SUPPORTED_RPC_VERSION {
+            Some(format!(
+                "Daemon RPC version {} is older than the supported minimum {}",
+                self.rpc_version, MIN_SUPPORTED_RPC_VERSION
+            ))
+        } else if self.rpc_version_minimum > MAX_KNOWN_RPC_VERSION {
+            Some(format!(
+                "Daemon requires RPC version {} or newer; this client speaks {}",
+                self.rpc_version_minimum, MAX_KNOWN_RPC_VERSION
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn features_follow_rpc_version_thresholds() {
+        let old = Capabilities::from_versions(15, 1, None);
+        assert!(!old.json_rpc && !old.labels && !old.table_format && !old.file_count);
+        assert!(!old.bandwidth_groups);
+        assert!(old.torrent_reannounce);
+
+        let v16 = Capabilities::from_versions(16, 1, None);
+        assert!(v16.labels && v16.table_format && !v16.file_count && !v16.json_rpc);
+        assert!(!v16.bandwidth_groups);
+
+        let v17 = Capabilities::from_versions(17, 14, Some("5.4.0".to_string()));
+        assert!(v17.file_count && v17.bandwidth_groups && !v17.json_rpc);
+
+        let v18 = Capabilities::from_versions(18, 14, None);
+        assert!(v18.json_rpc);
+
+        assert!(!Capabilities::from_versions(4, 1, None).torrent_reannounce);
+    }
+
+    #[test]
+    fn semver_major_six_enables_json_rpc_on_its_own() {
+        assert!(Capabilities::from_versions(17, 14, Some("6.0.0".to_string())).json_rpc);
+        // An older semver does not turn JSON-RPC off for rpc_version 18.
+        assert!(Capabilities::from_versions(18, 14, Some("5.4.0".to_string())).json_rpc);
+        // An unparsable semver leaves the decision to rpc_version.
+        assert!(!Capabilities::from_versions(17, 14, Some("next".to_string())).json_rpc);
+    }
+
+    #[test]
+    fn warns_outside_the_supported_range() {
+        assert!(Capabilities::from_versions(14, 1, None)
+            .compatibility_warning()
+            .is_some_and(|warning| warning.contains("older than the supported minimum 15")));
+        assert!(Capabilities::from_versions(20, 19, None)
+            .compatibility_warning()
+            .is_some_and(|warning| warning.contains("requires RPC version 19")));
+        assert_eq!(
+            Capabilities::from_versions(18, 18, None).compatibility_warning(),
+            None
+        );
+    }
+
+    #[test]
+    fn label_includes_semver_when_known() {
+        assert_eq!(
+            Capabilities::from_versions(18, 14, Some("6.0.0".to_string())).label(),
+            "rpc 18 (6.0.0)"
+        );
+        assert_eq!(Capabilities::from_versions(17, 14, None).label(), "rpc 17");
+    }
+}
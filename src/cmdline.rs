@@ -0,0 +1,629 @@
+use std::fs;
+
+use anyhow::{anyhow, bail, Context as _, Result};
+
+use crate::{
+    columns::Column,
+    fuzzy::{fuzzy_match, Match},
+};
+
+/// A `:` command line entry with its words resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Pause,
+    Resume,
+    Reannounce,
+    Refresh,
+    /// Moves the selected torrent's data to a directory on the daemon.
+    Move(String),
+    /// Per-torrent limit in KiB/s; `None` lifts it.
+    Limit {
+        direction: LimitDirection,
+        limit: Option<i64>,
+    },
+    Label(LabelOp, Vec<String>),
+    /// `None` goes back to the order the daemon reports.
+    Sort(Option<(Column, SortOrder)>),
+    /// A profile name, or `all` for the merged view.
+    Profile(String),
+    Filter(String),
+    /// Applies the saved view with this name.
+    View(String),
+    /// Saves the current filter, sort and columns as a view.
+    SaveView(String),
+    Quit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitDirection {
+    Down,
+    Up,
+    Both,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelOp {
+    Add,
+    Remove,
+    Set,
+    Clear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub description: &'static str,
+}
+
+/// Every command, in the order the command line suggests them.
+pub const COMMANDS: [CommandSpec; 12] = [
+    CommandSpec {
+        name: "move",
+        usage: "move <path>",
+        description: "Move the selected torrent's data",
+    },
+    CommandSpec {
+        name: "limit",
+        usage: "limit down|up|both <rate|off>",
+        description: "Limit the selected torrent's speed",
+    },
+    CommandSpec {
+        name: "label",
+        usage: "label add|remove|set <labels…> or label clear",
+        description: "Change the selected torrent's labels",
+    },
+    CommandSpec {
+        name: "sort",
+        usage: "sort <column> [asc|desc] or sort off",
+        description: "Sort the torrent list",
+    },
+    CommandSpec {
+        name: "profile",
+        usage: "profile <name|all>",
+        description: "Switch daemon profile",
+    },
+    CommandSpec {
+        name: "filter",
+        usage: "filter [text]",
+        description: "Filter torrents by name",
+    },
+    CommandSpec {
+        name: "view",
+        usage: "view <name> or view save <name>",
+        description: "Apply or save a saved view",
+    },
+    CommandSpec {
+        name: "pause",
+        usage: "pause",
+        description: "Pause the selected torrent",
+    },
+    CommandSpec {
+        name: "resume",
+        usage: "resume",
+        description: "Resume the selected torrent",
+    },
+    CommandSpec {
+        name: "reannounce",
+        usage: "reannounce",
+        description: "Ask trackers for more peers",
+    },
+    CommandSpec {
+        name: "refresh",
+        usage: "refresh",
+        description: "Refresh now",
+    },
+    CommandSpec {
+        name: "quit",
+        usage: "quit",
+        description: "Quit",
+    },
+];
+
+const DIRECTIONS: [&str; 3] = ["down", "up", "both"];
+const LABEL_OPS: [&str; 4] = ["add", "remove", "set", "clear"];
+const SORT_ORDERS: [&str; 2] = ["asc", "desc"];
+
+/// What arguments can be matched against besides the fixed keywords.
+#[derive(Debug, Default)]
+pub struct Context {
+    /// Profile names, plus `all` when the merged view is available.
+    pub profiles: Vec<String>,
+    /// Every label on a known torrent.
+    pub labels: Vec<String>,
+    /// Labels of the selected torrent.
+    pub selected_labels: Vec<String>,
+    /// Saved view names.
+    pub views: Vec<String>,
+    /// Download directories the selected torrent's daemon reports.
+    pub download_dirs: Vec<String>,
+    /// The daemon shares this machine's filesystem, so local directories
+    /// are worth offering for `move`.
+    pub local_paths: bool,
+}
+
+/// Parses a command line. The command and keyword arguments may be
+/// abbreviated or fuzzy-typed as long as one candidate matches best.
+pub fn parse(input: &str, context: &Context) -> Result<Command> {
+    let (word, rest) = split_word(input.trim());
+    if word.is_empty() {
+        bail!("empty command");
+    }
+    let name = resolve(word, &command_names(), "command")?;
+    let args: Vec<&str> = rest.split_whitespace().collect();
+    let usage = || {
+        let spec = COMMANDS.iter().find(|spec| spec.name == name);
+        anyhow!("usage: {}", spec.map_or(name, |spec| spec.usage))
+    };
+    let command = match name {
+        "move" if !rest.is_empty() => Command::Move(rest.to_string()),
+        "limit" => {
+            let [direction, rate] = args[..] else {
+                return Err(usage());
+            };
+            let direction = match resolve(direction, &DIRECTIONS, "direction")? {
+                "down" => LimitDirection::Down,
+                "up" => LimitDirection::Up,
+                _ => LimitDirection::Both,
+            };
+            Command::Limit {
+                direction,
+                limit: parse_rate(rate)?,
+            }
+        }
+        "label" => {
+            let Some((op, words)) = args.split_first() else {
+                return Err(usage());
+            };
+            let labels: Vec<String> = words
+                .iter()
+                .flat_map(|word| word.split(','))
+                .filter(|label| !label.is_empty())
+                .map(str::to_string)
+                .collect();
+            match resolve(op, &LABEL_OPS, "label operation")? {
+                "clear" if labels.is_empty() => Command::Label(LabelOp::Clear, labels),
+                "add" if !labels.is_empty() => Command::Label(LabelOp::Add, labels),
+                "set" if !labels.is_empty() => Command::Label(LabelOp::Set, labels),
+                "remove" if !labels.is_empty() => {
+                    if context.selected_labels.is_empty() {
+                        bail!("the selected torrent has no labels");
+                    }
+                    let current: Vec<&str> =
+                        context.selected_labels.iter().map(String::as_str).collect();
+                    let labels = labels
+                        .iter()
+                        .map(|label| resolve(label, &current, "label").map(str::to_string))
+                        .collect::<Result<_>>()?;
+                    Command::Label(LabelOp::Remove, labels)
+                }
+                _ => return Err(usage()),
+            }
+        }
+        "sort" => {
+            let (column, order) = match args[..] {
+                [column] => (column, None),
+                [column, order] => (column, Some(order)),
+                _ => return Err(usage()),
+            };
+            let mut names = vec!["off"];
+            names.extend(Column::ALL.iter().map(|column| column.name()));
+            let column = resolve(column, &names, "column")?;
+            if column == "off" {
+                if order.is_some() {
+                    return Err(usage());
+                }
+                Command::Sort(None)
+            } else {
+                let column = Column::ALL
+                    .into_iter()
+                    .find(|candidate| candidate.name() == column)
+                    .expect("resolved from Column::ALL");
+                let order = match order.map(|order| resolve(order, &SORT_ORDERS, "sort order")) {
+                    Some(Ok("desc")) => SortOrder::Descending,
+                    Some(Err(err)) => return Err(err),
+                    _ => SortOrder::Ascending,
+                };
+                Command::Sort(Some((column, order)))
+            }
+        }
+        "profile" => {
+            let [profile] = args[..] else {
+                return Err(usage());
+            };
+            let profiles: Vec<&str> = context.profiles.iter().map(String::as_str).collect();
+            Command::Profile(resolve(profile, &profiles, "profile")?.to_string())
+        }
+        "filter" => Command::Filter(rest.to_string()),
+        "view" => {
+            let views: Vec<&str> = context.views.iter().map(String::as_str).collect();
+            let (first, name) = split_word(rest);
+            if let Some(exact) = views.iter().find(|view| view.eq_ignore_ascii_case(rest)) {
+                Command::View(exact.to_string())
+            } else if first == "save" && !name.is_empty() {
+                Command::SaveView(name.trim_end().to_string())
+            } else if rest.is_empty() {
+                return Err(usage());
+            } else {
+                Command::View(resolve(rest, &views, "view")?.to_string())
+            }
+        }
+        "pause" | "resume" | "reannounce" | "refresh" | "quit" if args.is_empty() => match name {
+            "pause" => Command::Pause,
+            "resume" => Command::Resume,
+            "reannounce" => Command::Reannounce,
+            "refresh" => Command::Refresh,
+            _ => Command::Quit,
+        },
+        _ => return Err(usage()),
+    };
+    Ok(command)
+}
+
+/// `500K`, `1.5M`, `2G` or a plain number, all in KiB/s; `off` lifts the
+/// limit.
+fn parse_rate(text: &str) -> Result<Option<i64>> {
+    if ["off", "none", "unlimited"]
+        .iter()
+        .any(|word| text.eq_ignore_ascii_case(word))
+    {
+        return Ok(None);
+    }
+    let lower = text.to_ascii_lowercase();
+    let lower = lower.strip_suffix("/s").unwrap_or(&lower);
+    let split = lower
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(lower.len());
+    let (number, unit) = lower.split_at(split);
+    let factor = match unit {
+        "" | "k" | "kb" | "kib" => 1.0,
+        "m" | "mb" | "mib" => 1024.0,
+        "g" | "gb" | "gib" => 1024.0 * 1024.0,
+        _ => bail!("unknown rate unit in `{text}` (use K, M or G)"),
+    };
+    let value: f64 = number
+        .parse()
+        .with_context(|| format!("invalid rate `{text}`"))?;
+    Ok(Some((value * factor).round() as i64))
+}
+
+/// Candidates for the word being typed at the end of a command line.
+#[derive(Debug, Default)]
+pub struct Completion {
+    /// Byte offset in the line where the word starts.
+    pub start: usize,
+    /// Best match first.
+    pub candidates: Vec<Candidate>,
+}
+
+#[derive(Debug)]
+pub struct Candidate {
+    pub text: String,
+    /// Shown next to the candidate; empty for arguments.
+    pub detail: &'static str,
+    /// Char indexes of `text` that matched what was typed.
+    pub positions: Vec<usize>,
+}
+
+/// Completes commands, keywords, sort columns, profiles and labels by fuzzy
+/// match, and `move` paths from the local filesystem.
+pub fn complete<'a>(input: &str, context: &'a Context) -> Completion {
+    let lead = input.len() - input.trim_start().len();
+    let line = &input[lead..];
+    let Some(split) = line.find(char::is_whitespace) else {
+        let candidates = rank(line, &command_names())
+            .into_iter()
+            .map(|(name, found)| Candidate {
+                text: name.to_string(),
+                detail: COMMANDS
+                    .iter()
+                    .find(|spec| spec.name == name)
+                    .map_or("", |spec| spec.description),
+                positions: found.positions,
+            })
+            .collect();
+        return Completion {
+            start: lead,
+            candidates,
+        };
+    };
+    let Ok(name) = resolve(&line[..split], &command_names(), "command") else {
+        return Completion::default();
+    };
+    let rest_start = input.len() - line[split..].trim_start().len();
+    let rest = &input[rest_start..];
+    if name == "move" {
+        return Completion {
+            start: rest_start,
+            candidates: complete_move(rest, context),
+        };
+    }
+    if name == "view" {
+        // A new name has nothing to complete against.
+        if split_word(rest).0 == "save" && rest.len() > "save".len() {
+            return Completion::default();
+        }
+        // View names may contain spaces, so the whole rest is one word.
+        let mut options = vec!["save"];
+        options.extend(context.views.iter().map(String::as_str));
+        let candidates = rank(rest, &options)
+            .into_iter()
+            .map(|(text, found)| Candidate {
+                text: text.to_string(),
+                detail: "",
+                positions: found.positions,
+            })
+            .collect();
+        return Completion {
+            start: rest_start,
+            candidates,
+        };
+    }
+    let word_start = rest
+        .char_indices()
+        .rev()
+        .find(|(_, c)| c.is_whitespace())
+        .map_or(0, |(idx, c)| idx + c.len_utf8());
+    let done: Vec<&str> = rest[..word_start].split_whitespace().collect();
+    let labels = |labels: &'a [String]| -> Vec<&'a str> {
+        labels
+            .iter()
+            .map(String::as_str)
+            .filter(|label| !done.contains(label))
+            .collect()
+    };
+    let options: Vec<&str> = match (name, done.as_slice()) {
+        ("limit", []) => DIRECTIONS.to_vec(),
+        ("limit", [_]) => vec!["off"],
+        ("label", []) => LABEL_OPS.to_vec(),
+        ("label", [op, ..]) => match resolve(op, &LABEL_OPS, "label operation") {
+            Ok("add") => labels(&context.labels)
+                .into_iter()
+                .filter(|label| !context.selected_labels.iter().any(|own| own == label))
+                .collect(),
+            Ok("set") => labels(&context.labels),
+            Ok("remove") => labels(&context.selected_labels),
+            _ => Vec::new(),
+        },
+        ("sort", []) => {
+            let mut names = vec!["off"];
+            names.extend(Column::ALL.iter().map(|column| column.name()));
+            names
+        }
+        ("sort", [column]) if !column.eq_ignore_ascii_case("off") => SORT_ORDERS.to_vec(),
+        ("profile", []) => context.profiles.iter().map(String::as_str).collect(),
+        _ => Vec::new(),
+    };
+    let candidates = rank(&rest[word_start..], &options)
+        .into_iter()
+        .map(|(text, found)| Candidate {
+            text: text.to_string(),
+            detail: "",
+            positions: found.positions,
+        })
+        .collect();
+    Completion {
+        start: rest_start + word_start,
+        candidates,
+    }
+}
+
+/// Download directories the daemon already uses that start with `typed`,
+/// then local directories when the daemon runs on this machine.
+fn complete_move(typed: &str, context: &Context) -> Vec<Candidate> {
+    let matched: Vec<usize> = (0..typed.chars().count()).collect();
+    let mut candidates: Vec<Candidate> = context
+        .download_dirs
+        .iter()
+        .filter(|dir| dir.starts_with(typed))
+        .map(|dir| Candidate {
+            text: dir.clone(),
+            detail: "",
+            positions: matched.clone(),
+        })
+        .collect();
+    if context.local_paths {
+        for candidate in complete_path(typed) {
+            let known = candidates.iter().any(|known| {
+                known.text.trim_end_matches('/') == candidate.text.trim_end_matches('/')
+            });
+            if !known {
+                candidates.push(candidate);
+            }
+        }
+    }
+    candidates
+}
+
+/// Directories next to `typed` whose names start with its last component.
+/// Hidden ones are only offered once that component starts with `.`.
+fn complete_path(typed: &str) -> Vec<Candidate> {
+    let (dir, prefix) = match typed.rfind('/') {
+        Some(idx) => typed.split_at(idx + 1),
+        None => ("", typed),
+    };
+    let Ok(entries) = fs::read_dir(if dir.is_empty() { "." } else { dir }) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| {
+            name.starts_with(prefix) && (prefix.starts_with('.') || !name.starts_with('.'))
+        })
+        .collect();
+    names.sort();
+    let start = dir.chars().count();
+    let matched: Vec<usize> = (start..start + prefix.chars().count()).collect();
+    names
+        .into_iter()
+        .map(|name| Candidate {
+            text: format!("{dir}{name}/"),
+            detail: "",
+            positions: matched.clone(),
+        })
+        .collect()
+}
+
+fn command_names() -> Vec<&'static str> {
+    COMMANDS.iter().map(|spec| spec.name).collect()
+}
+
+fn split_word(input: &str) -> (&str, &str) {
+    input
+        .split_once(char::is_whitespace)
+        .map_or((input, ""), |(word, rest)| (word, rest.trim_start()))
+}
+
+/// Picks the candidate `word` names: an exact match ignoring case, or else
+/// the one fuzzy match that scores best.
+fn resolve<'a>(word: &str, candidates: &[&'a str], what: &str) -> Result<&'a str> {
+    if let Some(exact) = candidates
+        .iter()
+        .find(|candidate| candidate.eq_ignore_ascii_case(word))
+    {
+        return Ok(exact);
+    }
+    let ranked = rank(word, candidates);
+    match ranked.as_slice() {
+        [] if candidates.is_empty() => bail!("unknown {what} `{word}`"),
+        [] => bail!(
+            "unknown {what} `{word}` (expected one of {})",
+            candidates.join(", ")
+        ),
+        [(only, _)] => Ok(only),
+        [(first, best), (_, second), ..] if best.score > second.score => Ok(first),
+        [(_, best), ..] => {
+            let tied: Vec<&str> = ranked
+                .iter()
+                .take_while(|(_, found)| found.score == best.score)
+                .map(|(candidate, _)| *candidate)
+                .collect();
+            bail!("ambiguous {what} `{word}`: {}", tied.join(", "))
+        }
+    }
+}
+
+/// The candidates `word` fuzzy-matches, best first; ties keep their order.
+fn rank<'a>(word: &str, candidates: &[&'a str]) -> Vec<(&'a str, Match)> {
+    let mut ranked: Vec<(&str, Match)> = candidates
+        .iter()
+        .filter_map(|&candidate| fuzzy_match(word, candidate).map(|found| (candidate, found)))
+        .collect();
+    ranked.sort_by_key(|(_, found)| std::cmp::Reverse(found.score));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> Context {
+        Context {
+            profiles: vec!["default".into(), "seedbox".into(), "all".into()],
+            labels: vec!["linux".into(), "iso".into(), "music".into()],
+            selected_labels: vec!["linux".into(), "iso".into()],
+            views: vec!["seeding".into(), "big isos".into()],
+            download_dirs: vec!["/data".into(), "/mnt/media".into()],
+            local_paths: false,
+        }
+    }
+
+    fn parsed(input: &str) -> Command {
+        parse(input, &context()).expect(input)
+    }
+
+    #[test]
+    fn parses_the_documented_examples() {
+        assert_eq!(parsed("move /mnt/new"), Command::Move("/mnt/new".into()));
+        assert_eq!(
+            parsed("limit down 500K"),
+            Command::Limit {
+                direction: LimitDirection::Down,
+                limit: Some(500),
+            }
+        );
+        assert_eq!(
+            parsed("label add foo"),
+            Command::Label(LabelOp::Add, vec!["foo".into()])
+        );
+        assert_eq!(
+            parsed("sort ratio desc"),
+            Command::Sort(Some((Column::Ratio, SortOrder::Descending)))
+        );
+        assert_eq!(
+            parsed("profile seedbox"),
+            Command::Profile("seedbox".into())
+        );
+    }
+
+    #[test]
+    fn resolves_abbreviations_and_rejects_ties() {
+        assert_eq!(
+            parsed("lim u 1.5M"),
+            Command::Limit {
+                direction: LimitDirection::Up,
+                limit: Some(1536),
+            }
+        );
+        assert_eq!(parsed("lim both off"), parsed("limit both none"));
+        assert_eq!(
+            parsed("so dl_dir"),
+            Command::Sort(Some((Column::DownloadDir, SortOrder::Ascending)))
+        );
+        assert_eq!(parsed("pro sb"), Command::Profile("seedbox".into()));
+        assert_eq!(
+            parsed("label rm lin"),
+            Command::Label(LabelOp::Remove, vec!["linux".into()])
+        );
+        let err = parse("re", &context()).unwrap_err().to_string();
+        assert!(err.starts_with("ambiguous command `re`"), "{err}");
+        assert!(parse("sort bogus", &context()).is_err());
+        assert!(parse("limit down 5X", &context()).is_err());
+        assert!(parse("pause now", &context()).is_err());
+    }
+
+    #[test]
+    fn applies_and_saves_views() {
+        assert_eq!(parsed("view Big ISOs"), Command::View("big isos".into()));
+        assert_eq!(parsed("vi seed"), Command::View("seeding".into()));
+        assert_eq!(
+            parsed("view save done today"),
+            Command::SaveView("done today".into())
+        );
+        assert!(parse("view", &context()).is_err());
+        assert!(parse("view save", &context()).is_err());
+        assert!(parse("view music", &context()).is_err());
+    }
+
+    #[test]
+    fn completes_commands_and_arguments() {
+        let texts = |input: &str| -> Vec<String> {
+            complete(input, &context())
+                .candidates
+                .into_iter()
+                .map(|candidate| candidate.text)
+                .collect()
+        };
+        assert_eq!(texts("so")[0], "sort");
+        assert_eq!(texts("").len(), COMMANDS.len());
+        assert_eq!(texts("sort rat")[0], "ratio");
+        assert_eq!(texts("sort ratio "), ["asc", "desc"]);
+        assert_eq!(texts("label remove "), ["linux", "iso"]);
+        assert_eq!(texts("label add "), ["music"]);
+        assert_eq!(texts("label set linux "), ["iso", "music"]);
+        assert_eq!(texts("profile s"), ["seedbox"]);
+        assert_eq!(texts("view big i"), ["big isos"]);
+        assert_eq!(complete("view big i", &context()).start, 5);
+        assert!(texts("view save ").is_empty());
+        assert_eq!(texts("move /m"), ["/mnt/media"]);
+        assert_eq!(texts("move "), ["/data", "/mnt/media"]);
+        assert_eq!(complete("limit  do", &context()).start, 7);
+    }
+}
@@ -0,0 +1,396 @@
+use std::cmp::Ordering;
+
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::model::{format_bytes, format_eta, format_progress, format_speed, TorrentSummary};
+
+/// A torrent list column. Config names are the snake_case variant names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+    Name,
+    Daemon,
+    Status,
+    Download,
+    Upload,
+    Progress,
+    Eta,
+    Ratio,
+    Size,
+    Downloaded,
+    Uploaded,
+    Added,
+    Done,
+    Queue,
+    Peers,
+    Seeds,
+    Labels,
+    Tracker,
+    DownloadDir,
+    Hash,
+    Error,
+}
+
+/// Shown when config does not set `columns`.
+pub const DEFAULT_COLUMNS: [Column; 7] = [
+    Column::Name,
+    Column::Status,
+    Column::Download,
+    Column::Upload,
+    Column::Progress,
+    Column::Eta,
+    Column::Ratio,
+];
+
+impl Column {
+    /// Every column, in the order the picker offers hidden ones.
+    pub const ALL: [Column; 21] = [
+        Column::Name,
+        Column::Daemon,
+        Column::Status,
+        Column::Download,
+        Column::Upload,
+        Column::Progress,
+        Column::Eta,
+        Column::Ratio,
+        Column::Size,
+        Column::Downloaded,
+        Column::Uploaded,
+        Column::Added,
+        Column::Done,
+        Column::Queue,
+        Column::Peers,
+        Column::Seeds,
+        Column::Labels,
+        Column::Tracker,
+        Column::DownloadDir,
+        Column::Hash,
+        Column::Error,
+    ];
+
+    /// The name used in config and commands.
+    pub fn name(self) -> &'static str {
+        match self {
+            Column::Name => "name",
+            Column::Daemon => "daemon",
+            Column::Status => "status",
+            Column::Download => "download",
+            Column::Upload => "upload",
+            Column::Progress => "progress",
+            Column::Eta => "eta",
+            Column::Ratio => "ratio",
+            Column::Size => "size",
+            Column::Downloaded => "downloaded",
+            Column::Uploaded => "uploaded",
+            Column::Added => "added",
+            Column::Done => "done",
+            Column::Queue => "queue",
+            Column::Peers => "peers",
+            Column::Seeds => "seeds",
+            Column::Labels => "labels",
+            Column::Tracker => "tracker",
+            Column::DownloadDir => "download_dir",
+            Column::Hash => "hash",
+            Column::Error => "error",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Column::Name => "Name",
+            Column::Daemon => "Daemon",
+            Column::Status => "Status",
+            Column::Download => "DL",
+            Column::Upload => "UL",
+            Column::Progress => "Progress",
+            Column::Eta => "ETA",
+            Column::Ratio => "Ratio",
+            Column::Size => "Size",
+            Column::Downloaded => "Downloaded",
+            Column::Uploaded => "Uploaded",
+            Column::Added => "Added",
+            Column::Done => "Done",
+            Column::Queue => "Queue",
+            Column::Peers => "Peers",
+            Column::Seeds => "Seeds",
+            Column::Labels => "Labels",
+            Column::Tracker => "Tracker",
+            Column::DownloadDir => "Location",
+            Column::Hash => "Hash",
+            Column::Error => "Error",
+        }
+    }
+
+    /// What the picker says the column shows.
+    pub fn description(self) -> &'static str {
+        match self {
+            Column::Name => "Torrent name (always shown)",
+            Column::Daemon => "Source profile (all profiles view only)",
+            Column::Status => "Transfer status",
+            Column::Download => "Download rate",
+            Column::Upload => "Upload rate",
+            Column::Progress => "Percent of wanted data downloaded",
+            Column::Eta => "Time left",
+            Column::Ratio => "Upload ratio",
+            Column::Size => "Size of wanted data",
+            Column::Downloaded => "Bytes downloaded ever",
+            Column::Uploaded => "Bytes uploaded ever",
+            Column::Added => "Date added (UTC)",
+            Column::Done => "Date completed (UTC)",
+            Column::Queue => "Queue position",
+            Column::Peers => "Connected peers",
+            Column::Seeds => "Connected peers that are seeds",
+            Column::Labels => "Labels",
+            Column::Tracker => "Host of the first tracker",
+            Column::DownloadDir => "Download directory",
+            Column::Hash => "Info hash",
+            Column::Error => "Error reported by the daemon",
+        }
+    }
+
+    /// The narrowest width the column is drawn at.
+    fn min_width(self) -> u16 {
+        match self {
+            Column::Name => 20,
+            Column::Daemon | Column::Status | Column::Download | Column::Upload => 12,
+            Column::Labels => 12,
+            Column::Progress => 9,
+            Column::Eta | Column::Size | Column::Downloaded | Column::Uploaded => 10,
+            Column::Added | Column::Done => 10,
+            Column::Ratio => 8,
+            Column::Queue | Column::Peers | Column::Seeds => 5,
+            Column::Tracker | Column::DownloadDir | Column::Error => 16,
+            Column::Hash => 40,
+        }
+    }
+
+    /// Share of the spare width a column grows by; fixed columns get none.
+    fn weight(self) -> u16 {
+        match self {
+            Column::Name => 3,
+            Column::Labels | Column::Tracker | Column::DownloadDir | Column::Error => 1,
+            _ => 0,
+        }
+    }
+
+    /// When the terminal is too narrow, columns are hidden lowest first.
+    fn priority(self) -> u8 {
+        match self {
+            Column::Name => u8::MAX,
+            Column::Status => 100,
+            Column::Progress => 95,
+            Column::Daemon => 90,
+            Column::Error => 85,
+            Column::Download => 80,
+            Column::Upload => 70,
+            Column::Size => 65,
+            Column::Eta => 60,
+            Column::Labels => 55,
+            Column::Ratio => 50,
+            Column::Peers | Column::Tracker => 45,
+            Column::Downloaded | Column::Uploaded => 40,
+            Column::Seeds => 35,
+            Column::Added | Column::Done => 30,
+            Column::DownloadDir => 25,
+            Column::Queue => 20,
+            Column::Hash => 10,
+        }
+    }
+
+    pub fn is_numeric(self) -> bool {
+        matches!(
+            self,
+            Column::Download
+                | Column::Upload
+                | Column::Progress
+                | Column::Eta
+                | Column::Ratio
+                | Column::Size
+                | Column::Downloaded
+                | Column::Uploaded
+                | Column::Queue
+                | Column::Peers
+                | Column::Seeds
+        )
+    }
+
+    pub fn text(self, torrent: &TorrentSummary) -> String {
+        match self {
+            Column::Name => torrent.name.clone(),
+            Column::Daemon => torrent.daemon.clone(),
+            Column::Status => torrent.status.clone(),
+            Column::Download => format_speed(torrent.rate_download),
+            Column::Upload => format_speed(torrent.rate_upload),
+            Column::Progress => format_progress(torrent.percent_done),
+            Column::Eta => format_eta(torrent.eta),
+            Column::Ratio => format!("{:.2}", torrent.upload_ratio),
+            Column::Size => format_bytes(torrent.size_when_done),
+            Column::Downloaded => format_bytes(torrent.downloaded_ever),
+            Column::Uploaded => format_bytes(torrent.uploaded_ever),
+            Column::Added => format_date(torrent.added_date),
+            Column::Done => format_date(torrent.done_date),
+            Column::Queue => torrent.queue_position.to_string(),
+            Column::Peers => torrent.peers_connected.to_string(),
+            Column::Seeds => torrent.seeds().to_string(),
+            Column::Labels => torrent.labels.join(", "),
+            Column::Tracker => torrent
+                .trackers
+                .first()
+                .map(|url| tracker_host(url).to_string())
+                .unwrap_or_default(),
+            Column::DownloadDir => torrent.download_dir.clone(),
+            Column::Hash => torrent.hash.clone(),
+            Column::Error => torrent.error.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Orders two torrents by this column, ascending. Numbers compare by
+    /// value and unknown ETAs sort last; text compares case-insensitively.
+    pub fn compare(self, a: &TorrentSummary, b: &TorrentSummary) -> Ordering {
+        match self {
+            Column::Download => a.rate_download.cmp(&b.rate_download),
+            Column::Upload => a.rate_upload.cmp(&b.rate_upload),
+            Column::Progress => a.percent_done.total_cmp(&b.percent_done),
+            Column::Eta => {
+                let eta = |t: &TorrentSummary| t.eta.filter(|&eta| eta >= 0).unwrap_or(i64::MAX);
+                eta(a).cmp(&eta(b))
+            }
+            Column::Ratio => a.upload_ratio.total_cmp(&b.upload_ratio),
+            Column::Size => a.size_when_done.cmp(&b.size_when_done),
+            Column::Downloaded => a.downloaded_ever.cmp(&b.downloaded_ever),
+            Column::Uploaded => a.uploaded_ever.cmp(&b.uploaded_ever),
+            Column::Added => a.added_date.cmp(&b.added_date),
+            Column::Done => a.done_date.cmp(&b.done_date),
+            Column::Queue => a.queue_position.cmp(&b.queue_position),
+            Column::Peers => a.peers_connected.cmp(&b.peers_connected),
+            Column::Seeds => a.seeds().cmp(&b.seeds()),
+            _ => self
+                .text(a)
+                .to_lowercase()
+                .cmp(&self.text(b).to_lowercase()),
+        }
+    }
+}
+
+/// Fits `columns` into `width` cells with `spacing` between them. Columns
+/// that do not fit at their minimum width are dropped by priority (Name is
+/// always kept); spare width goes to the flexible columns by weight.
+pub fn layout(columns: &[Column], width: u16, spacing: u16) -> Vec<(Column, u16)> {
+    let mut shown = columns.to_vec();
+    let needed = |shown: &[Column]| {
+        let gaps = spacing * shown.len().saturating_sub(1) as u16;
+        shown.iter().map(|column| column.min_width()).sum::<u16>() + gaps
+    };
+    while shown.len() > 1 && needed(&shown) > width {
+        let Some(drop) = shown
+            .iter()
+            .enumerate()
+            .filter(|(_, &column)| column != Column::Name)
+            .min_by_key(|&(idx, column)| (column.priority(), std::cmp::Reverse(idx)))
+            .map(|(idx, _)| idx)
+        else {
+            break;
+        };
+        shown.remove(drop);
+    }
+    let mut spare = width.saturating_sub(needed(&shown));
+    let mut weights: u16 = shown.iter().map(|column| column.weight()).sum();
+    shown
+        .into_iter()
+        .map(|column| {
+            let weight = column.weight();
+            let extra = if weights == 0 {
+                0
+            } else {
+                (u32::from(spare) * u32::from(weight) / u32::from(weights)) as u16
+            };
+            spare -= extra;
+            weights -= weight;
+            (column, column.min_width() + extra)
+        })
+        .collect()
+}
+
+/// `YYYY-MM-DD` in UTC, or `-` for an unset (zero) timestamp.
+fn format_date(unix: i64) -> String {
+    match OffsetDateTime::from_unix_timestamp(unix) {
+        Ok(at) if unix > 0 => format!(
+            "{:04}-{:02}-{:02}",
+            at.year(),
+            u8::from(at.month()),
+            at.day()
+        ),
+        _ => "-".to_string(),
+    }
+}
+
+/// `tracker.example.org` from `https://tracker.example.org:443/announce`.
+fn tracker_host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?']).next().unwrap_or(rest);
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    if host.starts_with('[') {
+        return host.split_inclusive(']').next().unwrap_or(host);
+    }
+    host.split(':').next().unwrap_or(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(widths: &[(Column, u16)], spacing: u16) -> u16 {
+        let gaps = spacing * widths.len().saturating_sub(1) as u16;
+        widths.iter().map(|&(_, width)| width).sum::<u16>() + gaps
+    }
+
+    #[test]
+    fn narrow_terminals_drop_low_priority_columns() {
+        let widths = layout(&DEFAULT_COLUMNS, 60, 1);
+        assert_eq!(
+            widths,
+            [
+                (Column::Name, 24),
+                (Column::Status, 12),
+                (Column::Download, 12),
+                (Column::Progress, 9),
+            ]
+        );
+        assert_eq!(layout(&DEFAULT_COLUMNS, 10, 1), [(Column::Name, 20)]);
+        // Without Name the last column standing stays, and fixed columns never grow.
+        assert_eq!(
+            layout(&[Column::Hash, Column::Ratio], 30, 1),
+            [(Column::Ratio, 8)]
+        );
+        assert_eq!(layout(&[], 30, 1), []);
+    }
+
+    #[test]
+    fn flexible_columns_fill_the_width() {
+        let sets: [&[Column]; 3] = [
+            &DEFAULT_COLUMNS,
+            &[Column::Name, Column::Tracker, Column::Labels, Column::Hash],
+            &Column::ALL,
+        ];
+        for columns in sets {
+            for spacing in [0, 1, 2] {
+                for width in 20..=300 {
+                    let widths = layout(columns, width, spacing);
+                    assert!(widths.iter().any(|&(column, _)| column == Column::Name));
+                    assert_eq!(total(&widths, spacing), width, "{columns:?} at {width}");
+                    for &(column, shown) in &widths {
+                        assert!(shown >= column.min_width());
+                    }
+                }
+            }
+        }
+        let widths = layout(&[Column::Name, Column::Tracker], 100, 1);
+        assert_eq!(
+            widths,
+            [(Column::Name, 20 + 47), (Column::Tracker, 16 + 16)]
+        );
+    }
+}
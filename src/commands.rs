@@ -0,0 +1,541 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    net::SocketAddr,
+    path::PathBuf,
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::{
+    config::{AppConfig, RpcConfig},
+    export::{self, ExportFormat},
+    metrics::{self, MetricsOptions},
+    model::{format_bytes, format_eta, format_progress, format_speed, Snapshot, TorrentSummary},
+    preferences::PREFERENCE_FIELDS,
+    rpc::{AddOptions, AddTorrentOutcome, TransmissionClient, TransmissionError},
+    tunnel::{free_local_port, SshTunnel},
+    watch,
+};
+
+/// Exit status for failures not covered below (bad config, RPC errors, …).
+/// clap itself exits with 2 on usage errors.
+pub const EXIT_FAILURE: i32 = 1;
+/// The daemon could not be reached: network, TLS or session negotiation.
+pub const EXIT_UNREACHABLE: i32 = 3;
+pub const EXIT_AUTH: i32 = 4;
+/// A selector or torrent id matched nothing.
+pub const EXIT_NO_MATCH: i32 = 5;
+
+/// How long a command waits for a profile's SSH tunnel before giving up.
+const TUNNEL_STARTUP: Duration = Duration::from_secs(10);
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    #[command(flatten)]
+    OneShot(OneShotCommand),
+    /// Serve OpenMetrics for Prometheus, polling the daemon (every profile
+    /// with --aggregate) at the poll interval
+    ServeMetrics {
+        #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:9190")]
+        listen: SocketAddr,
+        #[command(flatten)]
+        options: MetricsOptions,
+    },
+    /// Add .torrent and .magnet files dropped into the [[watch]] folders,
+    /// scanning at the poll interval
+    Watch {
+        /// Scan each folder once and exit
+        #[arg(long)]
+        once: bool,
+    },
+}
+
+/// Subcommands that make their requests, print the result and exit.
+#[derive(Subcommand, Debug)]
+pub enum OneShotCommand {
+    /// List torrents, optionally narrowed by selectors
+    List {
+        /// Torrent ids, ranges (3-7), `all`, `status:<status>`, `label:<label>` or
+        /// a case-insensitive name fragment; a torrent matching any is listed
+        selectors: Vec<String>,
+        /// Export with raw values instead of printing a table (`--json` implies json)
+        #[arg(long, value_enum)]
+        format: Option<ExportFormat>,
+        /// Comma-separated export columns (default: all but peers)
+        #[arg(long, value_delimiter = ',')]
+        columns: Vec<String>,
+        /// Write the export to FILE instead of stdout
+        #[arg(long, short, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Add magnet links, torrent URLs or local .torrent files
+    Add {
+        #[arg(required = true, value_name = "MAGNET|FILE")]
+        sources: Vec<String>,
+    },
+    /// Start (resume) the selected torrents
+    Start {
+        #[arg(required = true, value_name = "SELECTOR")]
+        selectors: Vec<String>,
+    },
+    /// Stop (pause) the selected torrents
+    Stop {
+        #[arg(required = true, value_name = "SELECTOR")]
+        selectors: Vec<String>,
+    },
+    /// Remove the selected torrents
+    Remove {
+        #[arg(required = true, value_name = "SELECTOR")]
+        selectors: Vec<String>,
+        /// Also delete downloaded data
+        #[arg(long)]
+        delete_data: bool,
+    },
+    /// Show details for one torrent
+    Info { id: i64 },
+    /// Read or change daemon preferences
+    Prefs {
+        #[command(subcommand)]
+        action: PrefsCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PrefsCommand {
+    /// Print every preference, or just KEY
+    Get { key: Option<String> },
+    /// Change one preference on the daemon
+    Set { key: String, value: String },
+}
+
+#[derive(Debug, Error)]
+#[error("no torrent matches {0}")]
+pub struct NoMatch(String);
+
+/// Maps a failed command to its process exit status.
+pub fn exit_code(err: &anyhow::Error) -> i32 {
+    for cause in err.chain() {
+        if cause.downcast_ref::<NoMatch>().is_some() {
+            return EXIT_NO_MATCH;
+        }
+        if let Some(err) = cause.downcast_ref::<TransmissionError>() {
+            return match err {
+                TransmissionError::Authentication => EXIT_AUTH,
+                err if err.is_connection_failure() => EXIT_UNREACHABLE,
+                _ => EXIT_FAILURE,
+            };
+        }
+    }
+    EXIT_FAILURE
+}
+
+/// Runs one subcommand against the startup profile and prints its result,
+/// as JSON when `json` is set.
+pub fn run(command: Command, config: &AppConfig, json: bool) -> Result<()> {
+    let command = match command {
+        Command::OneShot(command) => command,
+        Command::ServeMetrics { listen, options } => {
+            return metrics::serve(config, listen, options)
+        }
+        Command::Watch { once } => return watch::run(config, once, json),
+    };
+    let mut out = io::stdout().lock();
+    match execute(command, config, json, &mut out).and_then(|()| Ok(out.flush()?)) {
+        // `list | head` closing the pipe early is not a failure.
+        Err(err)
+            if err
+                .downcast_ref::<io::Error>()
+                .is_some_and(|err| err.kind() == io::ErrorKind::BrokenPipe) =>
+        {
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+fn execute(
+    command: OneShotCommand,
+    config: &AppConfig,
+    json: bool,
+    out: &mut impl Write,
+) -> Result<()> {
+    let profile = &config.profiles[config.active_profile];
+    let (client, _tunnel) = connect(&profile.rpc)?;
+    client
+        .handshake()
+        .with_context(|| format!("cannot reach profile '{}'", profile.name))?;
+    match command {
+        OneShotCommand::List {
+            selectors,
+            format,
+            columns,
+            output,
+        } => {
+            let snapshot = fetch_snapshot(&client, &profile.name)?;
+            let torrents: Vec<&TorrentSummary> = if selectors.is_empty() {
+                snapshot.torrents.iter().collect()
+            } else {
+                select(&snapshot.torrents, &selectors)
+            };
+            let format = format.or(json.then_some(ExportFormat::Json));
+            if format.is_none() && !(columns.is_empty() && output.is_none()) {
+                bail!("--columns and --output need --format or --json");
+            }
+            match (format, output) {
+                (Some(format), Some(path)) => {
+                    let columns = export::parse_columns(&columns, format)?;
+                    let mut file = BufWriter::new(
+                        File::create(&path)
+                            .with_context(|| format!("failed to create {}", path.display()))?,
+                    );
+                    export::write_torrents(&mut file, &torrents, format, &columns)?;
+                    file.flush()
+                        .with_context(|| format!("failed to write {}", path.display()))?;
+                }
+                (Some(format), None) => {
+                    let columns = export::parse_columns(&columns, format)?;
+                    export::write_torrents(out, &torrents, format, &columns)?;
+                }
+                (None, _) => print_table(out, &torrents)?,
+            }
+        }
+        OneShotCommand::Add { sources } => add(&client, &sources, json, out)?,
+        OneShotCommand::Start { selectors } => {
+            act(&client, &selectors, "start", json, out, |ids| {
+                client.start_torrents(ids)
+            })?
+        }
+        OneShotCommand::Stop { selectors } => act(&client, &selectors, "stop", json, out, |ids| {
+            client.stop_torrents(ids)
+        })?,
+        OneShotCommand::Remove {
+            selectors,
+            delete_data,
+        } => act(&client, &selectors, "remove", json, out, |ids| {
+            client.remove_torrents(ids, delete_data)
+        })?,
+        OneShotCommand::Info { id } => {
+            let snapshot = fetch_snapshot(&client, &profile.name)?;
+            let torrent = snapshot
+                .torrents
+                .iter()
+                .find(|t| t.torrent_id == id)
+                .ok_or_else(|| NoMatch(format!("id {id}")))?;
+            if json {
+                print_json(out, &serde_json::to_value(torrent)?)?;
+            } else {
+                print_info(out, torrent)?;
+            }
+        }
+        OneShotCommand::Prefs { action } => prefs(&client, action, json, out)?,
+    }
+    Ok(())
+}
+
+/// Fetches the torrent list, tagging each torrent with the profile name as
+/// the TUI does.
+fn fetch_snapshot(client: &TransmissionClient, profile: &str) -> Result<Snapshot> {
+    let mut snapshot = client.fetch_snapshot()?;
+    for torrent in &mut snapshot.torrents {
+        torrent.daemon = profile.to_string();
+    }
+    Ok(snapshot)
+}
+
+/// Builds a client for one profile, bringing up its SSH tunnel first when it
+/// has one. The tunnel must outlive the client.
+pub fn connect(rpc: &RpcConfig) -> Result<(TransmissionClient, Option<SshTunnel>)> {
+    let Some(ssh) = &rpc.ssh else {
+        let client = TransmissionClient::new(rpc.clone())
+            .context("failed to construct Transmission RPC client")?;
+        return Ok((client, None));
+    };
+    let port = free_local_port().context("no free local port for the ssh tunnel")?;
+    let tunnel = SshTunnel::start(ssh.clone(), port);
+    if !tunnel.wait_ready(TUNNEL_STARTUP) {
+        let reason = tunnel
+            .last_error()
+            .unwrap_or_else(|| "timed out".to_string());
+        return Err(
+            anyhow::Error::new(TransmissionError::Offline).context(format!(
+                "ssh tunnel to {} failed: {reason}",
+                ssh.destination
+            )),
+        );
+    }
+    let client = TransmissionClient::new(rpc.through_tunnel(port))
+        .context("failed to construct Transmission RPC client")?;
+    Ok((client, Some(tunnel)))
+}
+
+enum Selector {
+    All,
+    Id(i64),
+    Range(i64, i64),
+    Status(String),
+    Label(String),
+    Name(String),
+}
+
+impl Selector {
+    fn parse(raw: &str) -> Self {
+        if raw.eq_ignore_ascii_case("all") {
+            return Selector::All;
+        }
+        if let Ok(id) = raw.parse() {
+            return Selector::Id(id);
+        }
+        if let Some((from, to)) = raw.split_once('-') {
+            if let (Ok(from), Ok(to)) = (from.parse(), to.parse()) {
+                return Selector::Range(from, to);
+            }
+        }
+        if let Some(status) = raw.strip_prefix("status:") {
+            return Selector::Status(status.to_ascii_lowercase());
+        }
+        if let Some(label) = raw.strip_prefix("label:") {
+            return Selector::Label(label.to_string());
+        }
+        Selector::Name(raw.to_lowercase())
+    }
+
+    fn matches(&self, torrent: &TorrentSummary) -> bool {
+        match self {
+            Selector::All => true,
+            Selector::Id(id) => torrent.torrent_id == *id,
+            Selector::Range(from, to) => (*from..=*to).contains(&torrent.torrent_id),
+            Selector::Status(status) => torrent.status == *status,
+            Selector::Label(label) => torrent.labels.iter().any(|l| l == label),
+            Selector::Name(fragment) => torrent.name.to_lowercase().contains(fragment),
+        }
+    }
+}
+
+/// Torrents matching any of `selectors`, in daemon order.
+fn select<'a>(torrents: &'a [TorrentSummary], selectors: &[String]) -> Vec<&'a TorrentSummary> {
+    let selectors: Vec<Selector> = selectors.iter().map(|raw| Selector::parse(raw)).collect();
+    torrents
+        .iter()
+        .filter(|torrent| selectors.iter().any(|selector| selector.matches(torrent)))
+        .collect()
+}
+
+fn act(
+    client: &TransmissionClient,
+    selectors: &[String],
+    action: &str,
+    json: bool,
+    out: &mut impl Write,
+    send: impl FnOnce(&[i64]) -> Result<(), TransmissionError>,
+) -> Result<()> {
+    let snapshot = client.fetch_snapshot()?;
+    let torrents = select(&snapshot.torrents, selectors);
+    if torrents.is_empty() {
+        return Err(NoMatch(selectors.join(" ")).into());
+    }
+    let ids: Vec<i64> = torrents.iter().map(|t| t.torrent_id).collect();
+    send(&ids).with_context(|| format!("{action} failed"))?;
+    if json {
+        let value = json!({
+            "action": action,
+            "torrents": torrents
+                .iter()
+                .map(|t| json!({ "id": t.torrent_id, "name": t.name }))
+                .collect::<Vec<_>>(),
+        });
+        print_json(out, &value)?;
+    } else {
+        for torrent in torrents {
+            writeln!(
+                out,
+                "{action:<8}{:>5}  {}",
+                torrent.torrent_id, torrent.name
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Adds each source in turn; a failure is reported and the rest still run.
+fn add(
+    client: &TransmissionClient,
+    sources: &[String],
+    json: bool,
+    out: &mut impl Write,
+) -> Result<()> {
+    let mut results = Vec::new();
+    let mut failed = 0;
+    for source in sources {
+        match add_one(client, source) {
+            Ok(outcome) => {
+                let state = if outcome.duplicate {
+                    "duplicate"
+                } else {
+                    "added"
+                };
+                if json {
+                    results.push(json!({
+                        "source": source,
+                        "added": outcome.added,
+                        "duplicate": outcome.duplicate,
+                        "id": outcome.torrent_id,
+                        "name": outcome.name,
+                    }));
+                } else {
+                    writeln!(
+                        out,
+                        "{state:<10}{:>5}  {}",
+                        outcome
+                            .torrent_id
+                            .map(|id| id.to_string())
+                            .unwrap_or_default(),
+                        outcome.name.as_deref().unwrap_or(source)
+                    )?;
+                }
+            }
+            Err(err) => {
+                failed += 1;
+                eprintln!("error: {source}: {err:#}");
+                if json {
+                    results.push(json!({ "source": source, "error": format!("{err:#}") }));
+                }
+            }
+        }
+    }
+    if json {
+        print_json(out, &Value::Array(results))?;
+    }
+    if failed > 0 {
+        bail!("{failed} of {} sources could not be added", sources.len());
+    }
+    Ok(())
+}
+
+fn add_one(client: &TransmissionClient, source: &str) -> Result<AddTorrentOutcome> {
+    // Magnets and URLs go to the daemon as-is; anything else is a local file.
+    if source.starts_with("magnet:") || source.contains("://") {
+        return Ok(client.add_magnet(source, &AddOptions::default())?);
+    }
+    let metainfo = fs::read(source).with_context(|| format!("failed to read {source}"))?;
+    Ok(client.add_torrent_file(&metainfo, &AddOptions::default())?)
+}
+
+fn prefs(
+    client: &TransmissionClient,
+    action: PrefsCommand,
+    json: bool,
+    out: &mut impl Write,
+) -> Result<()> {
+    let mut prefs = client.fetch_preferences()?;
+    let (key, values) = match action {
+        PrefsCommand::Get { key } => (key, prefs.to_rpc_map()),
+        PrefsCommand::Set { key, value } => {
+            prefs
+                .set(&key, &value)
+                .map_err(anyhow::Error::msg)
+                .with_context(|| format!("cannot set {key}"))?;
+            client.update_preferences(&prefs)?;
+            (Some(key), prefs.to_rpc_map())
+        }
+    };
+    let keys: Vec<&str> = match &key {
+        Some(key) if PREFERENCE_FIELDS.contains(&key.as_str()) => vec![key.as_str()],
+        Some(key) => bail!(
+            "unknown preference '{key}'; expected one of {}",
+            PREFERENCE_FIELDS.join(", ")
+        ),
+        None => PREFERENCE_FIELDS.to_vec(),
+    };
+    if json {
+        let object = keys
+            .iter()
+            .map(|key| (key.to_string(), values[*key].clone()))
+            .collect();
+        print_json(out, &Value::Object(object))?;
+    } else {
+        for key in keys {
+            match &values[key] {
+                Value::String(text) => writeln!(out, "{key} = {text}")?,
+                other => writeln!(out, "{key} = {other}")?,
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_json(out: &mut impl Write, value: &Value) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, value)?;
+    writeln!(out)
+}
+
+fn print_table(out: &mut impl Write, torrents: &[&TorrentSummary]) -> io::Result<()> {
+    writeln!(
+        out,
+        "{:>5}  {:<13} {:>6}  {:>11} {:>11} {:>6} {:>6}  Name",
+        "ID", "Status", "Done", "Down", "Up", "Ratio", "ETA"
+    )?;
+    for torrent in torrents {
+        writeln!(
+            out,
+            "{:>5}  {:<13} {:>6}  {:>11} {:>11} {:>6.2} {:>6}  {}",
+            torrent.torrent_id,
+            torrent.status,
+            format_progress(torrent.percent_done),
+            format_speed(torrent.rate_download),
+            format_speed(torrent.rate_upload),
+            torrent.upload_ratio,
+            format_eta(torrent.eta),
+            torrent.name
+        )?;
+    }
+    Ok(())
+}
+
+fn print_info(out: &mut impl Write, torrent: &TorrentSummary) -> io::Result<()> {
+    writeln!(out, "Name: {}", torrent.name)?;
+    writeln!(out, "Id: {}", torrent.torrent_id)?;
+    writeln!(out, "Status: {}", torrent.status)?;
+    writeln!(
+        out,
+        "Progress: {}  ETA {}",
+        format_progress(torrent.percent_done).trim(),
+        format_eta(torrent.eta)
+    )?;
+    writeln!(
+        out,
+        "Size: {} (remaining {})",
+        format_bytes(torrent.size_when_done).trim(),
+        format_bytes(torrent.left_until_done).trim()
+    )?;
+    writeln!(
+        out,
+        "Rates: DL {}  UL {}",
+        format_speed(torrent.rate_download).trim(),
+        format_speed(torrent.rate_upload).trim()
+    )?;
+    writeln!(out, "Ratio: {:.2}", torrent.upload_ratio)?;
+    writeln!(
+        out,
+        "Peers: sending {} | receiving {} | connected {}",
+        torrent.peers_sending, torrent.peers_receiving, torrent.peers_connected
+    )?;
+    writeln!(out, "Location: {}", torrent.download_dir)?;
+    if !torrent.labels.is_empty() {
+        writeln!(out, "Labels: {}", torrent.labels.join(", "))?;
+    }
+    if let Some(count) = torrent.file_count {
+        writeln!(out, "Files: {count}")?;
+    }
+    if let Some(group) = &torrent.bandwidth_group {
+        writeln!(out, "Group: {group}")?;
+    }
+    if let Some(error) = &torrent.error {
+        writeln!(out, "Error: {error}")?;
+    }
+    Ok(())
+}
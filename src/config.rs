@@ -1,5 +1,7 @@
 use std::{
-    env, fs,
+    collections::BTreeMap,
+    env, fmt, fs,
+    net::IpAddr,
     path::{Path, PathBuf},
     str::FromStr,
     time::Duration,
@@ -11,11 +13,58 @@ use dirs::config_dir;
 use log::LevelFilter;
 use serde::Deserialize;
 
+use crate::{
+    columns::{Column, DEFAULT_COLUMNS},
+    commands::Command,
+    hooks::{Hook, HookConfig},
+    keymap::{FileKeys, Keymap},
+    model::TORRENT_STATUSES,
+    netrc::Netrc,
+    notify::NotifyConfig,
+    rpc::AddOptions,
+    rules::{Rule, RuleAction, RuleCondition},
+    secret::{expand_home, Secret, SecretSource},
+    theme::Theme,
+    transitions::TransitionKind,
+    tunnel::{RemoteTarget, SshConfig},
+    views::{self, View},
+    watch::WatchFolder,
+};
+
 #[derive(Debug, Clone)]
 pub struct AppConfig {
-    pub rpc: RpcConfig,
+    pub profiles: Vec<Profile>,
+    pub active_profile: usize,
+    pub aggregate: bool,
     pub poll_interval: Duration,
     pub log_level: LevelFilter,
+    pub watch: Vec<WatchFolder>,
+    pub hooks: HookConfig,
+    pub rules: Vec<Rule>,
+    /// Log what rules would do instead of doing it.
+    pub rules_dry_run: bool,
+    pub notifications: NotifyConfig,
+    pub keymap: Keymap,
+    pub theme: Theme,
+    /// Torrent list columns, in order.
+    pub columns: Vec<Column>,
+    /// Capture the mouse; off leaves text selection to the terminal.
+    pub mouse: bool,
+    /// Start with the name filter in fuzzy mode.
+    pub fuzzy_filter: bool,
+    /// Saved views, in the order number keys pick them.
+    pub views: Vec<View>,
+    /// The config file in use, where views saved from the UI are appended;
+    /// it may not exist yet.
+    pub config_path: Option<PathBuf>,
+}
+
+/// A named daemon connection. Profiles come from `[profiles.<name>]` tables,
+/// with the top-level `[rpc]` table acting as the implicit `default` profile.
+#[derive(Debug, Clone)]
+pub struct Profile {
+    pub name: String,
+    pub rpc: RpcConfig,
 }
 
 #[derive(Debug, Clone)]
@@ -25,23 +74,245 @@ pub struct RpcConfig {
     pub port: u16,
     pub path: String,
     pub username: Option<String>,
-    pub password: Option<String>,
+    /// Resolved up front for the profiles shown at startup; other profiles
+    /// run their command or keyring lookup when first connected.
+    pub password: Option<SecretSource>,
     pub timeout: Duration,
     pub verify_ssl: bool,
+    /// PEM bundle trusted in addition to the built-in roots.
+    pub ca_file: Option<PathBuf>,
+    /// PEM client certificate for mutual TLS; may also hold the private key.
+    pub client_cert: Option<PathBuf>,
+    pub client_key: Option<PathBuf>,
+    pub proxy: ProxySetting,
+    /// Reach the daemon through an `ssh -N -L` tunnel; see [`Self::through_tunnel`].
+    pub ssh: Option<SshConfig>,
     pub user_agent: String,
     pub url: Option<String>,
+    pub capture: TrafficCapture,
+}
+
+/// How the RPC client reaches the daemon.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub enum ProxySetting {
+    /// Honour `HTTPS_PROXY`/`HTTP_PROXY`/`ALL_PROXY` and `NO_PROXY`.
+    #[default]
+    System,
+    /// Connect directly, ignoring proxy environment variables.
+    Direct,
+    /// http, https, socks5 or socks5h proxy; credentials may be in the URL.
+    Url(reqwest::Url),
+}
+
+impl ProxySetting {
+    fn parse(value: &str) -> Result<Self> {
+        let url = reqwest::Url::parse(value)
+            .with_context(|| format!("invalid proxy URL '{}'", redact_url(value)))?;
+        match url.scheme() {
+            "http" | "https" | "socks5" | "socks5h" => Ok(ProxySetting::Url(url)),
+            other => anyhow::bail!(
+                "unsupported proxy scheme '{other}' (expected http, https, socks5 or socks5h)"
+            ),
+        }
+    }
+
+    /// Short description for the session header, without credentials.
+    /// `scheme` picks which proxy variable applies when following the
+    /// environment.
+    pub fn label(&self, scheme: &str) -> Option<String> {
+        match self {
+            ProxySetting::System => {
+                let names: &[&str] = if scheme == "https" {
+                    &["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"]
+                } else {
+                    &["HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"]
+                };
+                names
+                    .iter()
+                    .find_map(|name| env::var(name).ok().filter(|v| !v.is_empty()))
+                    .map(|value| format!("via {} from env", redact_url(&value)))
+            }
+            ProxySetting::Direct => Some("direct".to_string()),
+            ProxySetting::Url(url) => Some(format!("via {}", redact_url(url.as_str()))),
+        }
+    }
+}
+
+impl fmt::Debug for ProxySetting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProxySetting::System => f.write_str("System"),
+            ProxySetting::Direct => f.write_str("Direct"),
+            ProxySetting::Url(url) => write!(f, "Url({})", redact_url(url.as_str())),
+        }
+    }
+}
+
+/// Drops any `user:password@` part so proxy URLs can be shown and logged.
+fn redact_url(value: &str) -> String {
+    match reqwest::Url::parse(value) {
+        Ok(mut url) => {
+            let _ = url.set_username("");
+            let _ = url.set_password(None);
+            url.as_str().trim_end_matches('/').to_string()
+        }
+        Err(_) => "<unparseable>".to_string(),
+    }
+}
+
+/// Whether RPC exchanges are written to, or served from, a JSON lines file.
+/// Entries are tagged with the profile so daemons sharing a capture replay
+/// only their own traffic.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TrafficCapture {
+    #[default]
+    Off,
+    Record {
+        path: PathBuf,
+        profile: String,
+    },
+    Replay {
+        path: PathBuf,
+        profile: String,
+    },
 }
 
 impl RpcConfig {
+    /// Host the credentials are for: taken from `url` when one is set.
+    pub fn host_name(&self) -> String {
+        self.url
+            .as_deref()
+            .and_then(|url| reqwest::Url::parse(url).ok())
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_else(|| self.host.clone())
+    }
+
+    /// Whether the daemon runs on this machine, so local paths are its
+    /// paths too: a Unix socket or a loopback host, not through SSH.
+    pub fn is_local(&self) -> bool {
+        if self.ssh.is_some() {
+            return false;
+        }
+        if self.unix_socket().is_some() {
+            return true;
+        }
+        let host = self.host_name();
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        host.eq_ignore_ascii_case("localhost")
+            || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+    }
+
+    /// Fills a missing username and/or password from the netrc entry for this
+    /// host. An entry for a different login than the configured username is
+    /// ignored.
+    fn apply_netrc(&mut self, netrc: &Netrc) {
+        if !self.needs_netrc() {
+            return;
+        }
+        let Some(entry) = netrc.find(&self.host_name()) else {
+            return;
+        };
+        if let (Some(user), Some(login)) = (&self.username, &entry.login) {
+            if user != login {
+                return;
+            }
+        }
+        if self.username.is_none() {
+            self.username = entry.login.clone();
+        }
+        if self.password.is_none() {
+            self.password = entry.password.clone().map(SecretSource::Inline);
+        }
+    }
+
+    /// Whether a netrc entry could still supply part of the credentials.
+    fn needs_netrc(&self) -> bool {
+        self.username.is_none() || self.password.is_none()
+    }
+
+    pub fn resolve_password(&mut self) -> Result<()> {
+        if let Some(source) = &self.password {
+            self.password = Some(SecretSource::Inline(source.resolve()?));
+        }
+        Ok(())
+    }
+
+    pub fn proxy_label(&self) -> Option<String> {
+        if let Some(ssh) = &self.ssh {
+            return Some(format!("via ssh {}", ssh.destination));
+        }
+        if self.unix_socket().is_some() {
+            return None;
+        }
+        let scheme = self
+            .url
+            .as_deref()
+            .and_then(|url| reqwest::Url::parse(url).ok())
+            .map(|url| url.scheme().to_string())
+            .unwrap_or_else(|| self.scheme.clone());
+        self.proxy.label(&scheme)
+    }
+
     pub fn endpoint(&self) -> String {
         if let Some(url) = &self.url {
             return url.clone();
         }
+        format!(
+            "{}://{}:{}{}",
+            self.scheme,
+            self.host,
+            self.port,
+            self.rpc_path()
+        )
+    }
+
+    /// Socket path when `url` is `unix:///path` (or Transmission's own
+    /// `unix:/path` spelling).
+    pub fn unix_socket(&self) -> Option<PathBuf> {
+        let rest = self.url.as_deref()?.strip_prefix("unix:")?;
+        let path = rest.strip_prefix("//").unwrap_or(rest);
+        Some(PathBuf::from(path))
+    }
+
+    /// URL requests are sent to. Over a Unix socket the host is only
+    /// nominal; the HTTP path still comes from `path`.
+    pub fn request_url(&self) -> String {
+        if self.unix_socket().is_some() {
+            format!("http://localhost{}", self.rpc_path())
+        } else {
+            self.endpoint()
+        }
+    }
+
+    /// Copy of this config that talks to the local end of an SSH tunnel on
+    /// `local_port`. The scheme and path are kept; proxies are bypassed since
+    /// the tunnel already is the route.
+    pub fn through_tunnel(&self, local_port: u16) -> RpcConfig {
+        let scheme = match &self.url {
+            Some(_) if self.unix_socket().is_some() => "http".to_string(),
+            Some(url) => reqwest::Url::parse(url)
+                .map(|url| url.scheme().to_string())
+                .unwrap_or_else(|_| self.scheme.clone()),
+            None => self.scheme.clone(),
+        };
+        let path = match self.url.as_deref().map(reqwest::Url::parse) {
+            Some(Ok(url)) if self.unix_socket().is_none() => url.path().to_string(),
+            _ => self.rpc_path(),
+        };
+        RpcConfig {
+            url: Some(format!("{scheme}://127.0.0.1:{local_port}{path}")),
+            proxy: ProxySetting::Direct,
+            ssh: None,
+            ..self.clone()
+        }
+    }
+
+    fn rpc_path(&self) -> String {
         let mut path = self.path.clone();
         if !path.starts_with('/') {
             path.insert(0, '/');
         }
-        format!("{}://{}:{}{}", self.scheme, self.host, self.port, path)
+        path
     }
 }
 
@@ -59,7 +330,13 @@ pub struct Cli {
     #[arg(long)]
     pub username: Option<String>,
     #[arg(long)]
-    pub password: Option<String>,
+    pub password: Option<Secret>,
+    #[arg(long, value_name = "CMD")]
+    pub password_command: Option<String>,
+    #[arg(long, value_name = "FILE")]
+    pub password_file: Option<String>,
+    #[arg(long, value_name = "FILE")]
+    pub netrc_file: Option<PathBuf>,
     #[arg(long)]
     pub timeout: Option<f64>,
     #[arg(long)]
@@ -70,20 +347,152 @@ pub struct Cli {
     pub no_tls: bool,
     #[arg(long)]
     pub insecure: bool,
+    #[arg(long, value_name = "FILE")]
+    pub ca_file: Option<PathBuf>,
+    #[arg(long, value_name = "FILE")]
+    pub client_cert: Option<PathBuf>,
+    #[arg(long, value_name = "FILE", requires = "client_cert")]
+    pub client_key: Option<PathBuf>,
+    #[arg(long, value_name = "URL", conflicts_with = "no_proxy")]
+    pub proxy: Option<String>,
+    #[arg(long = "no-proxy", action = ArgAction::SetTrue)]
+    pub no_proxy: bool,
     #[arg(long)]
     pub config: Option<PathBuf>,
     #[arg(long)]
+    pub profile: Option<String>,
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub aggregate: bool,
+    #[arg(long)]
     pub log_level: Option<String>,
+    /// Built-in color theme: dark, light, solarized, high-contrast or monochrome
+    #[arg(long, value_name = "NAME")]
+    pub theme: Option<String>,
+    #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+    #[arg(long, value_name = "FILE")]
+    pub replay: Option<PathBuf>,
+    /// Print subcommand output as JSON
+    #[arg(long, global = true)]
+    pub json: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
 }
 
+pub const DEFAULT_PROFILE: &str = "default";
+
 #[derive(Debug, Default, Deserialize)]
 struct FileConfig {
     rpc: Option<FileRpcConfig>,
+    profile: Option<String>,
+    #[serde(default)]
+    profiles: BTreeMap<String, FileRpcConfig>,
+    aggregate: Option<bool>,
     poll_interval: Option<f64>,
     log_level: Option<String>,
+    #[serde(default)]
+    watch: Vec<FileWatchFolder>,
+    #[serde(default)]
+    hooks: Vec<FileHook>,
+    hook_concurrency: Option<usize>,
+    hook_timeout: Option<f64>,
+    #[serde(default)]
+    rules: Vec<FileRule>,
+    #[serde(default)]
+    rules_dry_run: bool,
+    notifications: Option<FileNotifications>,
+    #[serde(default)]
+    keys: FileKeys,
+    #[serde(default)]
+    theme: FileTheme,
+    columns: Option<Vec<Column>>,
+    mouse: Option<bool>,
+    fuzzy_filter: Option<bool>,
+    #[serde(default)]
+    views: Vec<FileView>,
 }
 
+/// The `[theme]` table: an optional built-in to start from, then any slot
+/// (`error = "bold red"`, `selection = "black on yellow"`, …) to restyle.
 #[derive(Debug, Default, Deserialize)]
+struct FileTheme {
+    name: Option<String>,
+    #[serde(flatten)]
+    styles: BTreeMap<String, String>,
+}
+
+/// The `[notifications]` table.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileNotifications {
+    desktop: Option<bool>,
+    notify_command: Option<String>,
+    bell: Option<bool>,
+    title: Option<bool>,
+}
+
+/// One `[[hooks]]` table.
+#[derive(Debug, Deserialize)]
+struct FileHook {
+    on: Vec<TransitionKind>,
+    command: String,
+    ratio: Option<f64>,
+    profile: Option<String>,
+}
+
+/// One `[[rules]]` table. `action` picks which of the parameters below it
+/// takes.
+#[derive(Debug, Deserialize)]
+struct FileRule {
+    name: Option<String>,
+    profile: Option<String>,
+    #[serde(default)]
+    when: RuleCondition,
+    action: FileRuleAction,
+    label: Option<String>,
+    location: Option<String>,
+    download_limit: Option<i64>,
+    upload_limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum FileRuleAction {
+    Stop,
+    Remove,
+    RemoveDelete,
+    SetLabel,
+    Move,
+    SpeedLimit,
+}
+
+/// One `[[views]]` table.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileView {
+    name: String,
+    #[serde(default)]
+    filter: String,
+    fuzzy: Option<bool>,
+    sort: Option<String>,
+    columns: Option<Vec<Column>>,
+}
+
+/// One `[[watch]]` table.
+#[derive(Debug, Deserialize)]
+struct FileWatchFolder {
+    dir: PathBuf,
+    profile: Option<String>,
+    download_dir: Option<String>,
+    #[serde(default)]
+    labels: Vec<String>,
+    #[serde(default)]
+    paused: bool,
+    processed_dir: Option<PathBuf>,
+    failed_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
 struct FileRpcConfig {
     url: Option<String>,
     scheme: Option<String>,
@@ -91,153 +500,747 @@ struct FileRpcConfig {
     port: Option<u16>,
     path: Option<String>,
     username: Option<String>,
-    password: Option<String>,
+    password: Option<Secret>,
+    password_command: Option<String>,
+    password_file: Option<String>,
+    keyring: Option<bool>,
+    timeout: Option<f64>,
+    tls: Option<bool>,
+    verify_ssl: Option<bool>,
+    ca_file: Option<PathBuf>,
+    client_cert: Option<PathBuf>,
+    client_key: Option<PathBuf>,
+    proxy: Option<String>,
+    no_proxy: Option<bool>,
+    ssh: Option<String>,
+    ssh_command: Option<String>,
+    user_agent: Option<String>,
+}
+
+impl FileRpcConfig {
+    /// Fills keys the profile leaves unset from the shared `[rpc]` table.
+    fn inherit(&self, base: Option<&FileRpcConfig>) -> FileRpcConfig {
+        let Some(base) = base else {
+            return self.clone();
+        };
+        FileRpcConfig {
+            url: self.url.clone().or_else(|| base.url.clone()),
+            scheme: self.scheme.clone().or_else(|| base.scheme.clone()),
+            host: self.host.clone().or_else(|| base.host.clone()),
+            port: self.port.or(base.port),
+            path: self.path.clone().or_else(|| base.path.clone()),
+            username: self.username.clone().or_else(|| base.username.clone()),
+            password: self.password.clone().or_else(|| base.password.clone()),
+            password_command: self
+                .password_command
+                .clone()
+                .or_else(|| base.password_command.clone()),
+            password_file: self
+                .password_file
+                .clone()
+                .or_else(|| base.password_file.clone()),
+            keyring: self.keyring.or(base.keyring),
+            timeout: self.timeout.or(base.timeout),
+            tls: self.tls.or(base.tls),
+            verify_ssl: self.verify_ssl.or(base.verify_ssl),
+            ca_file: self.ca_file.clone().or_else(|| base.ca_file.clone()),
+            client_cert: self
+                .client_cert
+                .clone()
+                .or_else(|| base.client_cert.clone()),
+            client_key: self.client_key.clone().or_else(|| base.client_key.clone()),
+            proxy: self.proxy.clone().or_else(|| base.proxy.clone()),
+            no_proxy: self.no_proxy.or(base.no_proxy),
+            ssh: self.ssh.clone().or_else(|| base.ssh.clone()),
+            ssh_command: self
+                .ssh_command
+                .clone()
+                .or_else(|| base.ssh_command.clone()),
+            user_agent: self.user_agent.clone().or_else(|| base.user_agent.clone()),
+        }
+    }
+
+    /// Password source named by this table; `password` wins over
+    /// `password_command`, which wins over `password_file` and the keyring.
+    fn password_source(&self, profile: &str) -> Option<SecretSource> {
+        if let Some(secret) = &self.password {
+            Some(SecretSource::Inline(secret.clone()))
+        } else if let Some(command) = &self.password_command {
+            Some(SecretSource::Command(command.clone()))
+        } else if let Some(path) = &self.password_file {
+            Some(SecretSource::File(path.clone()))
+        } else if self.keyring == Some(true) {
+            Some(SecretSource::Keyring {
+                account: profile.to_string(),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Connection settings taken from CLI flags and environment variables. They
+/// only apply to the profile selected at startup so that switching profiles
+/// in-app reaches each daemon exactly as configured.
+#[derive(Debug, Default)]
+struct RpcOverrides {
+    url: Option<String>,
+    host: Option<String>,
+    port: Option<u16>,
+    path: Option<String>,
+    username: Option<String>,
+    password: Option<SecretSource>,
     timeout: Option<f64>,
     tls: Option<bool>,
     verify_ssl: Option<bool>,
+    ca_file: Option<PathBuf>,
+    client_cert: Option<PathBuf>,
+    client_key: Option<PathBuf>,
+    proxy: Option<String>,
+    no_proxy: Option<bool>,
     user_agent: Option<String>,
 }
 
+impl RpcOverrides {
+    fn from_cli_and_env(cli: &Cli) -> Self {
+        let tls_flag = if cli.tls {
+            Some(true)
+        } else if cli.no_tls {
+            Some(false)
+        } else {
+            None
+        };
+        let verify_ssl = if cli.insecure {
+            Some(false)
+        } else {
+            env_bool("TRANSMISSION_VERIFY_SSL")
+        };
+        Self {
+            url: cli
+                .url
+                .clone()
+                .or_else(|| env::var("TRANSMISSION_URL").ok()),
+            host: cli
+                .host
+                .clone()
+                .or_else(|| env::var("TRANSMISSION_HOST").ok()),
+            port: cli.port.or_else(|| env_var_parse("TRANSMISSION_PORT")),
+            path: cli
+                .path
+                .clone()
+                .or_else(|| env::var("TRANSMISSION_RPC_PATH").ok()),
+            username: cli
+                .username
+                .clone()
+                .or_else(|| env::var("TRANSMISSION_USERNAME").ok()),
+            password: cli_password_source(cli).or_else(env_password_source),
+            timeout: cli.timeout.or_else(|| env_float("TRANSMISSION_TIMEOUT")),
+            tls: tls_flag.or_else(|| env_bool("TRANSMISSION_TLS")),
+            verify_ssl,
+            ca_file: cli
+                .ca_file
+                .clone()
+                .or_else(|| env::var_os("TRANSMISSION_CA_FILE").map(PathBuf::from)),
+            client_cert: cli
+                .client_cert
+                .clone()
+                .or_else(|| env::var_os("TRANSMISSION_CLIENT_CERT").map(PathBuf::from)),
+            client_key: cli
+                .client_key
+                .clone()
+                .or_else(|| env::var_os("TRANSMISSION_CLIENT_KEY").map(PathBuf::from)),
+            proxy: cli
+                .proxy
+                .clone()
+                .or_else(|| env::var("TRANSMISSION_PROXY").ok()),
+            no_proxy: if cli.no_proxy {
+                Some(true)
+            } else {
+                env_bool("TRANSMISSION_NO_PROXY")
+            },
+            user_agent: env::var("TRANSMISSION_USER_AGENT").ok(),
+        }
+    }
+}
+
+fn cli_password_source(cli: &Cli) -> Option<SecretSource> {
+    if let Some(secret) = &cli.password {
+        Some(SecretSource::Inline(secret.clone()))
+    } else if let Some(command) = &cli.password_command {
+        Some(SecretSource::Command(command.clone()))
+    } else {
+        cli.password_file.clone().map(SecretSource::File)
+    }
+}
+
+fn env_password_source() -> Option<SecretSource> {
+    if let Ok(password) = env::var("TRANSMISSION_PASSWORD") {
+        Some(SecretSource::Inline(Secret::new(password)))
+    } else if let Ok(command) = env::var("TRANSMISSION_PASSWORD_COMMAND") {
+        Some(SecretSource::Command(command))
+    } else {
+        env::var("TRANSMISSION_PASSWORD_FILE")
+            .ok()
+            .map(SecretSource::File)
+    }
+}
+
 pub fn build_config(cli: &Cli) -> Result<AppConfig> {
-    let file_config = load_file_config(cli.config.as_deref())?;
-    let rpc_file = file_config.as_ref().and_then(|cfg| cfg.rpc.as_ref());
+    build_config_with_netrc(cli, dirs::home_dir().map(|home| home.join(".netrc")))
+}
 
-    let url = cli
-        .url
+/// Reads the netrc file named by `--netrc-file` or `NETRC`, falling back to
+/// `default` (normally `~/.netrc`). Only an explicitly named file is fatal when
+/// it cannot be read or parsed; problems with the default are logged and the
+/// file is ignored.
+fn load_netrc(cli: &Cli, default: Option<PathBuf>) -> Result<Option<Netrc>> {
+    let explicit = cli
+        .netrc_file
         .clone()
-        .or_else(|| env::var("TRANSMISSION_URL").ok())
-        .or_else(|| rpc_file.and_then(|cfg| cfg.url.clone()));
+        .or_else(|| env::var_os("NETRC").map(PathBuf::from));
+    if let Some(path) = explicit {
+        return Netrc::load(&path);
+    }
+    let Some(path) = default else {
+        return Ok(None);
+    };
+    Netrc::load(&path).or_else(|err| {
+        log::warn!("ignoring {}: {err:#}", path.display());
+        Ok(None)
+    })
+}
 
-    let host = cli
-        .host
-        .clone()
-        .or_else(|| env::var("TRANSMISSION_HOST").ok())
-        .or_else(|| rpc_file.and_then(|cfg| cfg.host.clone()))
-        .unwrap_or_else(|| "localhost".to_string());
+fn build_config_with_netrc(cli: &Cli, default_netrc: Option<PathBuf>) -> Result<AppConfig> {
+    let config_path = config_file_path(cli.config.as_deref());
+    let file_config = match &config_path {
+        Some(path) => read_file_config(path)?.unwrap_or_default(),
+        None => FileConfig::default(),
+    };
+    let rpc_file = file_config.rpc.as_ref();
 
-    let port = cli
-        .port
-        .or_else(|| env_var_parse("TRANSMISSION_PORT"))
-        .or_else(|| rpc_file.and_then(|cfg| cfg.port))
-        .unwrap_or(9091);
+    let capture = |profile: &str| match (&cli.record, &cli.replay) {
+        (Some(path), _) => TrafficCapture::Record {
+            path: path.clone(),
+            profile: profile.to_string(),
+        },
+        (None, Some(path)) => TrafficCapture::Replay {
+            path: path.clone(),
+            profile: profile.to_string(),
+        },
+        (None, None) => TrafficCapture::Off,
+    };
 
-    let path = cli
-        .path
-        .clone()
-        .or_else(|| env::var("TRANSMISSION_RPC_PATH").ok())
-        .or_else(|| rpc_file.and_then(|cfg| cfg.path.clone()))
-        .unwrap_or_else(|| "/transmission/rpc".to_string());
+    let mut named: Vec<(String, FileRpcConfig)> = Vec::new();
+    let implicit_default = (rpc_file.is_some() || file_config.profiles.is_empty())
+        && !file_config.profiles.contains_key(DEFAULT_PROFILE);
+    if implicit_default {
+        named.push((
+            DEFAULT_PROFILE.to_string(),
+            rpc_file.cloned().unwrap_or_default(),
+        ));
+    }
+    for (name, profile) in &file_config.profiles {
+        named.push((name.clone(), profile.inherit(rpc_file)));
+    }
 
-    let username = cli
-        .username
+    let requested = cli
+        .profile
         .clone()
-        .or_else(|| env::var("TRANSMISSION_USERNAME").ok())
-        .or_else(|| rpc_file.and_then(|cfg| cfg.username.clone()));
+        .or_else(|| env::var("TRANSMISSION_PROFILE").ok())
+        .or_else(|| file_config.profile.clone());
+    let active_profile = match &requested {
+        Some(name) => named
+            .iter()
+            .position(|(candidate, _)| candidate == name)
+            .with_context(|| {
+                let available: Vec<&str> = named.iter().map(|(n, _)| n.as_str()).collect();
+                format!(
+                    "unknown profile '{name}' (available: {})",
+                    available.join(", ")
+                )
+            })?,
+        None => 0,
+    };
 
-    let password = cli
-        .password
-        .clone()
-        .or_else(|| env::var("TRANSMISSION_PASSWORD").ok())
-        .or_else(|| rpc_file.and_then(|cfg| cfg.password.clone()));
+    let overrides = RpcOverrides::from_cli_and_env(cli);
+    let no_overrides = RpcOverrides::default();
+    let mut profiles = Vec::with_capacity(named.len());
+    for (idx, (name, file_rpc)) in named.into_iter().enumerate() {
+        let overrides = if idx == active_profile {
+            &overrides
+        } else {
+            &no_overrides
+        };
+        let rpc = resolve_rpc(&name, overrides, &file_rpc, capture(&name))
+            .with_context(|| format!("invalid settings for profile '{name}'"))?;
+        profiles.push(Profile { name, rpc });
+    }
 
-    let timeout_secs = cli
-        .timeout
-        .or_else(|| env_float("TRANSMISSION_TIMEOUT"))
-        .or_else(|| rpc_file.and_then(|cfg| cfg.timeout))
-        .unwrap_or(10.0);
+    let aggregate = if cli.aggregate {
+        true
+    } else {
+        env_bool("TRANSMISSION_AGGREGATE")
+            .or(file_config.aggregate)
+            .unwrap_or(false)
+    };
 
-    if timeout_secs <= 0.0 {
-        anyhow::bail!("timeout must be positive");
+    if profiles.iter().any(|profile| profile.rpc.needs_netrc()) {
+        if let Some(netrc) = load_netrc(cli, default_netrc)? {
+            for profile in &mut profiles {
+                profile.rpc.apply_netrc(&netrc);
+            }
+        }
+    }
+
+    for (idx, profile) in profiles.iter_mut().enumerate() {
+        if idx == active_profile || aggregate {
+            profile
+                .rpc
+                .resolve_password()
+                .with_context(|| format!("cannot read password for profile '{}'", profile.name))?;
+        }
     }
 
     let poll_secs = cli
         .poll_interval
         .or_else(|| env_float("TRANSMISSION_POLL_INTERVAL"))
-        .or_else(|| file_config.as_ref().and_then(|cfg| cfg.poll_interval))
+        .or(file_config.poll_interval)
         .unwrap_or(3.0);
 
     if poll_secs < 0.0 {
         anyhow::bail!("poll interval cannot be negative");
     }
 
-    let tls_flag = if cli.tls {
-        Some(true)
-    } else if cli.no_tls {
-        Some(false)
-    } else {
-        None
+    let log_level_str = cli
+        .log_level
+        .clone()
+        .or_else(|| env::var("TRANSMISSION_LOG_LEVEL").ok())
+        .or_else(|| file_config.log_level.clone())
+        .unwrap_or_else(|| "info".to_string());
+    let log_level = LevelFilter::from_str(&log_level_str).unwrap_or(LevelFilter::Info);
+
+    let watch = file_config
+        .watch
+        .iter()
+        .map(|folder| resolve_watch(folder, &profiles, active_profile))
+        .collect::<Result<Vec<_>>>()?;
+
+    let hooks = file_config
+        .hooks
+        .iter()
+        .map(|hook| resolve_hook(hook, &profiles))
+        .collect::<Result<Vec<_>>>()?;
+    let hook_timeout = file_config.hook_timeout.unwrap_or(600.0);
+    if hook_timeout <= 0.0 {
+        anyhow::bail!("hook_timeout must be positive");
+    }
+
+    let fuzzy_filter = file_config.fuzzy_filter.unwrap_or(false);
+
+    let rules = file_config
+        .rules
+        .iter()
+        .enumerate()
+        .map(|(index, rule)| resolve_rule(rule, index, &profiles))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(AppConfig {
+        profiles,
+        active_profile,
+        aggregate,
+        poll_interval: Duration::from_secs_f64(poll_secs.max(0.0)),
+        log_level,
+        watch,
+        hooks: HookConfig {
+            hooks,
+            concurrency: file_config.hook_concurrency.unwrap_or(2).max(1),
+            timeout: Duration::from_secs_f64(hook_timeout),
+        },
+        rules,
+        rules_dry_run: file_config.rules_dry_run,
+        notifications: resolve_notifications(file_config.notifications.as_ref())?,
+        keymap: Keymap::from_config(&file_config.keys)?,
+        theme: resolve_theme(cli, &file_config.theme)?,
+        columns: resolve_columns(file_config.columns.as_deref())?,
+        mouse: file_config.mouse.unwrap_or(true),
+        fuzzy_filter,
+        views: resolve_views(&file_config.views, fuzzy_filter)?,
+        config_path,
+    })
+}
+
+/// Views without `fuzzy` use the `fuzzy_filter` default.
+fn resolve_views(views: &[FileView], fuzzy_filter: bool) -> Result<Vec<View>> {
+    let mut resolved: Vec<View> = Vec::new();
+    for view in views {
+        let name = view.name.trim();
+        if name.is_empty() {
+            anyhow::bail!("[[views]] entries need a name");
+        }
+        if resolved.iter().any(|other| other.name == name) {
+            anyhow::bail!("two views are named '{name}'");
+        }
+        let sort = match &view.sort {
+            Some(sort) => views::parse_sort(sort).with_context(|| format!("view '{name}'"))?,
+            None => None,
+        };
+        let columns = match &view.columns {
+            Some(columns) => {
+                Some(resolve_columns(Some(columns)).with_context(|| format!("view '{name}'"))?)
+            }
+            None => None,
+        };
+        resolved.push(View {
+            name: name.to_string(),
+            filter: view.filter.trim().to_string(),
+            fuzzy: view.fuzzy.unwrap_or(fuzzy_filter),
+            sort,
+            columns,
+        });
+    }
+    Ok(resolved)
+}
+
+fn resolve_columns(columns: Option<&[Column]>) -> Result<Vec<Column>> {
+    let Some(columns) = columns else {
+        return Ok(DEFAULT_COLUMNS.to_vec());
     };
+    if !columns.contains(&Column::Name) {
+        anyhow::bail!("columns must include \"name\"");
+    }
+    for (idx, column) in columns.iter().enumerate() {
+        if columns[..idx].contains(column) {
+            anyhow::bail!("columns lists the {} column twice", column.title());
+        }
+    }
+    Ok(columns.to_vec())
+}
 
-    let tls_env = env_bool("TRANSMISSION_TLS");
-    let use_tls = tls_flag
-        .or(tls_env)
-        .or_else(|| rpc_file.and_then(|cfg| cfg.tls))
-        .unwrap_or(false);
+/// A non-empty `NO_COLOR` (https://no-color.org) overrides any theme.
+fn resolve_theme(cli: &Cli, file: &FileTheme) -> Result<Theme> {
+    let name = cli
+        .theme
+        .clone()
+        .or_else(|| env::var("TRANSMISSION_THEME").ok())
+        .or_else(|| file.name.clone())
+        .unwrap_or_else(|| "dark".to_string());
+    let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    Theme::resolve(&name, &file.styles, no_color).context("invalid theme")
+}
 
-    let verify_env = env_bool("TRANSMISSION_VERIFY_SSL");
-    let mut verify_ssl = rpc_file.and_then(|cfg| cfg.verify_ssl).unwrap_or(true);
-    if let Some(value) = verify_env {
-        verify_ssl = value;
+/// Notifications are off without a `[notifications]` table. Inside tmux the
+/// bell and title default to on, since tmux turns them into window alerts.
+fn resolve_notifications(file: Option<&FileNotifications>) -> Result<NotifyConfig> {
+    let Some(file) = file else {
+        return Ok(NotifyConfig::default());
+    };
+    let in_tmux = env::var_os("TMUX").is_some_and(|value| !value.is_empty());
+    let desktop = file.desktop.unwrap_or(false);
+    if desktop && !cfg!(feature = "desktop-notifications") {
+        anyhow::bail!(
+            "notifications.desktop is set, but this build lacks the `desktop-notifications` feature"
+        );
     }
-    if cli.insecure {
-        verify_ssl = false;
+    Ok(NotifyConfig {
+        desktop,
+        command: file
+            .notify_command
+            .clone()
+            .filter(|command| !command.trim().is_empty()),
+        bell: file.bell.unwrap_or(in_tmux),
+        title: file.title.unwrap_or(in_tmux),
+    })
+}
+
+fn resolve_hook(hook: &FileHook, profiles: &[Profile]) -> Result<Hook> {
+    let command = &hook.command;
+    if hook.on.is_empty() {
+        anyhow::bail!("hook `{command}` has an empty `on` list");
+    }
+    let wants_ratio = hook.on.contains(&TransitionKind::RatioReached);
+    match hook.ratio {
+        None if wants_ratio => anyhow::bail!("hook `{command}`: ratio_reached needs `ratio`"),
+        Some(_) if !wants_ratio => {
+            anyhow::bail!("hook `{command}`: `ratio` only applies to ratio_reached")
+        }
+        Some(ratio) if ratio <= 0.0 => anyhow::bail!("hook `{command}`: ratio must be positive"),
+        _ => {}
+    }
+    if let Some(name) = &hook.profile {
+        if !profiles.iter().any(|profile| &profile.name == name) {
+            anyhow::bail!("hook `{command}` names unknown profile '{name}'");
+        }
+    }
+    Ok(Hook {
+        on: hook.on.clone(),
+        command: command.clone(),
+        ratio: hook.ratio,
+        profile: hook.profile.clone(),
+    })
+}
+
+/// Unnamed rules are called `rule N`, counting from 1 in file order.
+fn resolve_rule(rule: &FileRule, index: usize, profiles: &[Profile]) -> Result<Rule> {
+    let name = rule
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("rule {}", index + 1));
+    if let Some(status) = &rule.when.status {
+        if !TORRENT_STATUSES.contains(&status.as_str()) {
+            anyhow::bail!(
+                "rule '{name}': unknown status '{status}' (expected one of {})",
+                TORRENT_STATUSES.join(", ")
+            );
+        }
+    }
+    if let Some(profile) = &rule.profile {
+        if !profiles.iter().any(|known| &known.name == profile) {
+            anyhow::bail!("rule '{name}' names unknown profile '{profile}'");
+        }
+    }
+    let kind = rule.action;
+    let allowed: &[&str] = match kind {
+        FileRuleAction::SetLabel => &["label"],
+        FileRuleAction::Move => &["location"],
+        FileRuleAction::SpeedLimit => &["download_limit", "upload_limit"],
+        _ => &[],
+    };
+    let given = [
+        ("label", rule.label.is_some()),
+        ("location", rule.location.is_some()),
+        ("download_limit", rule.download_limit.is_some()),
+        ("upload_limit", rule.upload_limit.is_some()),
+    ];
+    if let Some((key, _)) = given
+        .iter()
+        .find(|(key, set)| *set && !allowed.contains(key))
+    {
+        anyhow::bail!("rule '{name}': `{key}` does not apply to this action");
+    }
+    let required = |value: &Option<String>, key: &str| {
+        value
+            .clone()
+            .filter(|value| !value.trim().is_empty())
+            .with_context(|| format!("rule '{name}': this action needs `{key}`"))
+    };
+    let action = match kind {
+        FileRuleAction::Stop => RuleAction::Stop,
+        FileRuleAction::Remove => RuleAction::Remove,
+        FileRuleAction::RemoveDelete => RuleAction::RemoveWithData,
+        FileRuleAction::SetLabel => RuleAction::SetLabel(required(&rule.label, "label")?),
+        FileRuleAction::Move => RuleAction::Move(required(&rule.location, "location")?),
+        FileRuleAction::SpeedLimit => {
+            if rule.download_limit.is_none() && rule.upload_limit.is_none() {
+                anyhow::bail!(
+                    "rule '{name}': speed_limit needs `download_limit` or `upload_limit`"
+                );
+            }
+            if rule.download_limit.unwrap_or(0) < 0 || rule.upload_limit.unwrap_or(0) < 0 {
+                anyhow::bail!("rule '{name}': speed limits cannot be negative");
+            }
+            RuleAction::SpeedLimit {
+                download: rule.download_limit,
+                upload: rule.upload_limit,
+            }
+        }
+    };
+    Ok(Rule {
+        name,
+        profile: rule.profile.clone(),
+        when: rule.when.clone(),
+        action,
+    })
+}
+
+/// Folders without a `profile` feed the profile selected at startup; the
+/// processed and failed folders default to subfolders of `dir`.
+fn resolve_watch(
+    folder: &FileWatchFolder,
+    profiles: &[Profile],
+    active_profile: usize,
+) -> Result<WatchFolder> {
+    let dir = expand_home(&folder.dir);
+    let profile = match &folder.profile {
+        Some(name) if profiles.iter().any(|profile| &profile.name == name) => name.clone(),
+        Some(name) => anyhow::bail!(
+            "watch folder {} names unknown profile '{name}'",
+            dir.display()
+        ),
+        None => profiles[active_profile].name.clone(),
+    };
+    let subfolder = |configured: &Option<PathBuf>, default: &str| match configured {
+        Some(path) => expand_home(path),
+        None => dir.join(default),
+    };
+    Ok(WatchFolder {
+        processed_dir: subfolder(&folder.processed_dir, "processed"),
+        failed_dir: subfolder(&folder.failed_dir, "failed"),
+        profile,
+        options: AddOptions {
+            download_dir: folder.download_dir.clone(),
+            labels: folder.labels.clone(),
+            paused: folder.paused,
+        },
+        dir,
+    })
+}
+
+fn resolve_rpc(
+    profile: &str,
+    overrides: &RpcOverrides,
+    rpc_file: &FileRpcConfig,
+    capture: TrafficCapture,
+) -> Result<RpcConfig> {
+    let url = overrides.url.clone().or_else(|| rpc_file.url.clone());
+    if let Some(socket) = url.as_deref().and_then(|url| url.strip_prefix("unix:")) {
+        let socket = socket.strip_prefix("//").unwrap_or(socket);
+        if !socket.starts_with('/') {
+            anyhow::bail!("unix socket URL must hold an absolute path, e.g. unix:///run/transmission/rpc.sock");
+        }
+    }
+
+    let host = overrides
+        .host
+        .clone()
+        .or_else(|| rpc_file.host.clone())
+        .unwrap_or_else(|| "localhost".to_string());
+
+    let port = overrides.port.or(rpc_file.port).unwrap_or(9091);
+
+    let path = overrides
+        .path
+        .clone()
+        .or_else(|| rpc_file.path.clone())
+        .unwrap_or_else(|| "/transmission/rpc".to_string());
+
+    let username = overrides
+        .username
+        .clone()
+        .or_else(|| rpc_file.username.clone());
+
+    let password = overrides
+        .password
+        .clone()
+        .or_else(|| rpc_file.password_source(profile));
+
+    let timeout_secs = overrides.timeout.or(rpc_file.timeout).unwrap_or(10.0);
+
+    if timeout_secs <= 0.0 {
+        anyhow::bail!("timeout must be positive");
     }
 
+    let use_tls = overrides.tls.or(rpc_file.tls).unwrap_or(false);
+
+    let verify_ssl = overrides.verify_ssl.or(rpc_file.verify_ssl).unwrap_or(true);
+
     let scheme = rpc_file
-        .and_then(|cfg| cfg.scheme.clone())
+        .scheme
+        .clone()
         .unwrap_or_else(|| if use_tls { "https" } else { "http" }.to_string());
 
-    let user_agent = env::var("TRANSMISSION_USER_AGENT")
-        .ok()
-        .or_else(|| rpc_file.and_then(|cfg| cfg.user_agent.clone()))
-        .unwrap_or_else(|| "transmission-tui".to_string());
+    let ca_file = overrides
+        .ca_file
+        .clone()
+        .or_else(|| rpc_file.ca_file.clone());
 
-    let log_level_str = cli
-        .log_level
+    // The certificate and key travel together so that a CLI certificate never
+    // pairs with a key from the config file.
+    let (client_cert, client_key) = match &overrides.client_cert {
+        Some(cert) => (Some(cert.clone()), overrides.client_key.clone()),
+        None => (rpc_file.client_cert.clone(), rpc_file.client_key.clone()),
+    };
+    if client_key.is_some() && client_cert.is_none() {
+        anyhow::bail!("client_key requires client_cert");
+    }
+
+    // An explicit proxy in one layer beats `no_proxy` from a lower one, and
+    // vice versa, so `--proxy` can override a file's `no_proxy = true`.
+    let proxy_layer = |proxy: &Option<String>, no_proxy: Option<bool>| match (proxy, no_proxy) {
+        (_, Some(true)) => Some(Ok(ProxySetting::Direct)),
+        (Some(url), _) => Some(ProxySetting::parse(url)),
+        (None, _) => None,
+    };
+    let proxy = proxy_layer(&overrides.proxy, overrides.no_proxy)
+        .or_else(|| proxy_layer(&rpc_file.proxy, rpc_file.no_proxy))
+        .transpose()?
+        .unwrap_or_default();
+
+    let ssh = match &rpc_file.ssh {
+        Some(destination) => Some(SshConfig {
+            destination: destination.clone(),
+            program: rpc_file
+                .ssh_command
+                .clone()
+                .unwrap_or_else(|| "ssh".to_string()),
+            remote: ssh_remote(url.as_deref(), &host, port)?,
+        }),
+        None => None,
+    };
+
+    let user_agent = overrides
+        .user_agent
         .clone()
-        .or_else(|| env::var("TRANSMISSION_LOG_LEVEL").ok())
-        .or_else(|| file_config.as_ref().and_then(|cfg| cfg.log_level.clone()))
-        .unwrap_or_else(|| "info".to_string());
-    let log_level = LevelFilter::from_str(&log_level_str).unwrap_or(LevelFilter::Info);
+        .or_else(|| rpc_file.user_agent.clone())
+        .unwrap_or_else(|| "transmission-tui".to_string());
 
-    Ok(AppConfig {
-        rpc: RpcConfig {
-            scheme,
-            host,
+    Ok(RpcConfig {
+        scheme,
+        host,
+        port,
+        path,
+        username,
+        password,
+        timeout: Duration::from_secs_f64(timeout_secs),
+        verify_ssl,
+        ca_file,
+        client_cert,
+        client_key,
+        proxy,
+        ssh,
+        user_agent,
+        url,
+        capture,
+    })
+}
+
+/// Where the far end of an SSH tunnel connects: the socket or host/port the
+/// profile names, resolved on the SSH host rather than locally.
+fn ssh_remote(url: Option<&str>, host: &str, port: u16) -> Result<RemoteTarget> {
+    let Some(url) = url else {
+        return Ok(RemoteTarget::Tcp {
+            host: host.to_string(),
             port,
-            path,
-            username,
-            password,
-            timeout: Duration::from_secs_f64(timeout_secs),
-            verify_ssl,
-            user_agent,
-            url,
-        },
-        poll_interval: Duration::from_secs_f64(poll_secs.max(0.0)),
-        log_level,
+        });
+    };
+    if let Some(socket) = url.strip_prefix("unix:") {
+        let socket = socket.strip_prefix("//").unwrap_or(socket);
+        return Ok(RemoteTarget::Unix(socket.to_string()));
+    }
+    let parsed = reqwest::Url::parse(url).with_context(|| format!("invalid RPC URL '{url}'"))?;
+    Ok(RemoteTarget::Tcp {
+        host: parsed.host_str().unwrap_or("localhost").to_string(),
+        port: parsed.port_or_known_default().unwrap_or(port),
     })
 }
 
-fn load_file_config(path: Option<&Path>) -> Result<Option<FileConfig>> {
+/// `--config`, then `TRANSMISSION_TUI_CONFIG`, then `config.toml` in the
+/// config dir, unless only the legacy `transmission-tui.toml` exists.
+fn config_file_path(path: Option<&Path>) -> Option<PathBuf> {
     if let Some(path) = path {
-        return read_file_config(path);
+        return Some(path.to_path_buf());
     }
 
     if let Ok(env_path) = env::var("TRANSMISSION_TUI_CONFIG") {
-        return read_file_config(Path::new(&env_path));
+        return Some(PathBuf::from(env_path));
     }
 
-    if let Some(dir) = config_dir() {
-        let modern_path = dir.join("transmission-tui").join("config.toml");
-        if let Some(cfg) = read_file_config(&modern_path)? {
-            return Ok(Some(cfg));
-        }
-
-        let legacy_path = dir.join("transmission-tui.toml");
-        return read_file_config(&legacy_path);
+    let dir = config_dir()?;
+    let modern_path = dir.join("transmission-tui").join("config.toml");
+    let legacy_path = dir.join("transmission-tui.toml");
+    if !modern_path.exists() && legacy_path.exists() {
+        return Some(legacy_path);
     }
-
-    Ok(None)
+    Some(modern_path)
 }
 
 fn read_file_config(path: &Path) -> Result<Option<FileConfig>> {
@@ -272,3 +1275,79 @@ fn env_bool(name: &str) -> Option<bool> {
             _ => None,
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BAD_NETRC: &str = "machine localhost login alice password secret port 9091\n";
+
+    fn cli_for(dir: &Path, config: &str, extra: &[&str]) -> Cli {
+        let config_path = dir.join("config.toml");
+        fs::write(&config_path, config).unwrap();
+        let mut args = vec![
+            "transmission-tui",
+            "--config",
+            config_path.to_str().unwrap(),
+        ];
+        args.extend_from_slice(extra);
+        Cli::parse_from(args)
+    }
+
+    #[test]
+    fn unreadable_default_netrc_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let netrc = dir.path().join(".netrc");
+        fs::write(&netrc, BAD_NETRC).unwrap();
+        let cli = cli_for(dir.path(), "[rpc]\nhost = \"localhost\"\n", &[]);
+
+        let config = build_config_with_netrc(&cli, Some(netrc)).unwrap();
+        let rpc = &config.profiles[config.active_profile].rpc;
+        assert_eq!(rpc.username, None);
+        assert!(rpc.password.is_none());
+    }
+
+    #[test]
+    fn explicit_netrc_file_errors_are_fatal() {
+        let dir = tempfile::tempdir().unwrap();
+        let netrc = dir.path().join("netrc");
+        fs::write(&netrc, BAD_NETRC).unwrap();
+        let cli = cli_for(
+            dir.path(),
+            "[rpc]\nhost = \"localhost\"\n",
+            &["--netrc-file", netrc.to_str().unwrap()],
+        );
+
+        let err = build_config_with_netrc(&cli, None).unwrap_err();
+        assert!(format!("{err:#}").contains("unexpected token 'port'"));
+    }
+
+    #[test]
+    fn netrc_is_not_read_when_credentials_are_configured() {
+        let dir = tempfile::tempdir().unwrap();
+        let netrc = dir.path().join("netrc");
+        fs::write(&netrc, BAD_NETRC).unwrap();
+        let cli = cli_for(
+            dir.path(),
+            "[rpc]\nhost = \"localhost\"\nusername = \"bob\"\npassword = \"hunter2\"\n",
+            &["--netrc-file", netrc.to_str().unwrap()],
+        );
+
+        let config = build_config_with_netrc(&cli, None).unwrap();
+        let rpc = &config.profiles[config.active_profile].rpc;
+        assert_eq!(rpc.username.as_deref(), Some("bob"));
+    }
+
+    #[test]
+    fn default_netrc_fills_missing_credentials() {
+        let dir = tempfile::tempdir().unwrap();
+        let netrc = dir.path().join(".netrc");
+        fs::write(&netrc, "machine localhost login alice password secret\n").unwrap();
+        let cli = cli_for(dir.path(), "[rpc]\nhost = \"localhost\"\n", &[]);
+
+        let config = build_config_with_netrc(&cli, Some(netrc)).unwrap();
+        let rpc = &config.profiles[config.active_profile].rpc;
+        assert_eq!(rpc.username.as_deref(), Some("alice"));
+        assert!(rpc.password.is_some());
+    }
+}
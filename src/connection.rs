@@ -0,0 +1,218 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Consecutive failed polls before a degraded link is reported as offline.
+const OFFLINE_AFTER_FAILURES: u32 = 3;
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// Retry delays are spread by up to this fraction in either direction.
+const JITTER_RATIO: f64 = 0.2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connecting,
+    Online,
+    Degraded,
+    Offline,
+}
+
+impl ConnectionState {
+    pub fn label(self) -> &'static str {
+        match self {
+            ConnectionState::Connecting => "CONNECTING",
+            ConnectionState::Online => "ONLINE",
+            ConnectionState::Degraded => "DEGRADED",
+            ConnectionState::Offline => "OFFLINE",
+        }
+    }
+}
+
+/// Point-in-time view of the link that the worker hands to the UI.
+#[derive(Debug, Clone)]
+pub struct ConnectionStatus {
+    pub state: ConnectionState,
+    pub failures: u32,
+    pub last_success: Option<Instant>,
+    pub next_retry: Option<Instant>,
+    pub last_error: Option<String>,
+}
+
+impl Default for ConnectionStatus {
+    fn default() -> Self {
+        Self {
+            state: ConnectionState::Connecting,
+            failures: 0,
+            last_success: None,
+            next_retry: None,
+            last_error: None,
+        }
+    }
+}
+
+/// Tracks poll outcomes and decides when the worker should poll next. While
+/// online the regular poll interval applies; after a failure the delay grows
+/// exponentially (with jitter) up to `MAX_BACKOFF`.
+pub struct ConnectionTracker {
+    poll_interval: Duration,
+    status: ConnectionStatus,
+    jitter: RandomState,
+}
+
+impl ConnectionTracker {
+    pub fn new(poll_interval: Duration) -> Self {
+        Self {
+            poll_interval,
+            status: ConnectionStatus::default(),
+            jitter: RandomState::new(),
+        }
+    }
+
+    pub fn status(&self) -> &ConnectionStatus {
+        &self.status
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.status.state == ConnectionState::Offline
+    }
+
+    /// Time left until the next scheduled poll, or `None` when polling is
+    /// disabled and the daemon is reachable.
+    pub fn next_poll_in(&self) -> Option<Duration> {
+        self.status
+            .next_retry
+            .map(|at| at.saturating_duration_since(Instant::now()))
+    }
+
+    pub fn record_success(&mut self) {
+        let now = Instant::now();
+        self.status.state = ConnectionState::Online;
+        self.status.failures = 0;
+        self.status.last_success = Some(now);
+        self.status.last_error = None;
+        self.status.next_retry = if self.poll_interval > Duration::ZERO {
+            Some(now + self.poll_interval)
+        } else {
+            None
+        };
+    }
+
+    pub fn record_failure(&mut self, error: String) {
+        self.status.failures = self.status.failures.saturating_add(1);
+        self.status.state = if self.status.failures >= OFFLINE_AFTER_FAILURES {
+            ConnectionState::Offline
+        } else if self.status.last_success.is_some() {
+            ConnectionState::Degraded
+        } else {
+            ConnectionState::Connecting
+        };
+        self.status.last_error = Some(error);
+        self.status.next_retry = Some(Instant::now() + self.backoff());
+    }
+
+    fn backoff(&self) -> Duration {
+        let base = self.poll_interval.max(MIN_BACKOFF);
+        let exponent = self.status.failures.saturating_sub(1).min(16);
+        let delay = base.saturating_mul(1 << exponent).min(MAX_BACKOFF);
+        let mut hasher = self.jitter.build_hasher();
+        hasher.write_u32(self.status.failures);
+        if let Ok(since_epoch) = SystemTime::now().duration_since(UNIX_EPOCH) {
+            hasher.write_u128(since_epoch.as_nanos());
+        }
+        let unit = (hasher.finish() % 10_000) as f64 / 10_000.0;
+        let factor = 1.0 + JITTER_RATIO * (unit * 2.0 - 1.0);
+        delay.mul_f64(factor).min(MAX_BACKOFF)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn within_jitter(delay: Duration, base: Duration) -> bool {
+        delay >= base.mul_f64(1.0 - JITTER_RATIO) && delay <= base.mul_f64(1.0 + JITTER_RATIO)
+    }
+
+    #[test]
+    fn failures_move_through_degraded_to_offline() {
+        let mut tracker = ConnectionTracker::new(Duration::from_secs(2));
+        assert_eq!(tracker.status().state, ConnectionState::Connecting);
+        // Without a success yet the link is still connecting.
+        tracker.record_failure("refused".to_string());
+        assert_eq!(tracker.status().state, ConnectionState::Connecting);
+
+        tracker.record_success();
+        assert_eq!(tracker.status().state, ConnectionState::Online);
+        assert_eq!(tracker.status().failures, 0);
+        assert_eq!(tracker.status().last_error, None);
+
+        let mut states = Vec::new();
+        for _ in 0..OFFLINE_AFTER_FAILURES + 1 {
+            tracker.record_failure("timed out".to_string());
+            states.push(tracker.status().state);
+        }
+        assert_eq!(
+            states,
+            [
+                ConnectionState::Degraded,
+                ConnectionState::Degraded,
+                ConnectionState::Offline,
+                ConnectionState::Offline,
+            ]
+        );
+        assert!(tracker.is_offline());
+        assert_eq!(tracker.status().last_error.as_deref(), Some("timed out"));
+
+        tracker.record_success();
+        assert_eq!(tracker.status().state, ConnectionState::Online);
+        assert!(!tracker.is_offline());
+    }
+
+    #[test]
+    fn backoff_doubles_from_the_poll_interval_and_caps() {
+        let mut tracker = ConnectionTracker::new(Duration::from_secs(3));
+        for failures in 1..=6u32 {
+            tracker.record_failure("refused".to_string());
+            let expected = (Duration::from_secs(3) * 2u32.pow(failures - 1)).min(MAX_BACKOFF);
+            let delay = tracker.backoff();
+            assert!(
+                within_jitter(delay, expected) && delay <= MAX_BACKOFF,
+                "failure {failures}: {delay:?} vs {expected:?}"
+            );
+        }
+        for _ in 0..40 {
+            tracker.record_failure("refused".to_string());
+        }
+        let delay = tracker.backoff();
+        assert!(within_jitter(delay, MAX_BACKOFF) && delay <= MAX_BACKOFF);
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        let mut tracker = ConnectionTracker::new(Duration::ZERO);
+        tracker.record_failure("refused".to_string());
+        // A zero poll interval still backs off from MIN_BACKOFF.
+        for _ in 0..200 {
+            assert!(within_jitter(tracker.backoff(), MIN_BACKOFF));
+        }
+        let retry_in = tracker
+            .next_poll_in()
+            .expect("retry scheduled after failure");
+        assert!(retry_in <= MIN_BACKOFF.mul_f64(1.0 + JITTER_RATIO));
+    }
+
+    #[test]
+    fn success_schedules_the_next_poll_only_when_polling() {
+        let mut polling = ConnectionTracker::new(Duration::from_secs(5));
+        polling.record_success();
+        assert!(polling
+            .next_poll_in()
+            .is_some_and(|delay| delay <= Duration::from_secs(5)));
+
+        let mut manual = ConnectionTracker::new(Duration::ZERO);
+        manual.record_success();
+        assert_eq!(manual.next_poll_in(), None);
+    }
+}
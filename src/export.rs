@@ -0,0 +1,290 @@
+use std::io::Write;
+
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use serde_json::{Map, Value};
+
+use crate::model::TorrentSummary;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ExportFormat {
+    /// One JSON array
+    #[default]
+    Json,
+    /// One JSON object per line
+    Ndjson,
+    /// Comma-separated values with a header row
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "JSON",
+            ExportFormat::Ndjson => "NDJSON",
+            ExportFormat::Csv => "CSV",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Ndjson => "ndjson",
+            ExportFormat::Csv => "csv",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            ExportFormat::Json => ExportFormat::Ndjson,
+            ExportFormat::Ndjson => ExportFormat::Csv,
+            ExportFormat::Csv => ExportFormat::Json,
+        }
+    }
+}
+
+/// Serialized `TorrentSummary` fields that can be exported, in output order.
+/// Values are raw: bytes, bytes/s, seconds and 0–1 fractions.
+pub const EXPORT_COLUMNS: &[&str] = &[
+    "id",
+    "name",
+    "status",
+    "percent_done",
+    "rate_download",
+    "rate_upload",
+    "eta",
+    "upload_ratio",
+    "size_when_done",
+    "left_until_done",
+    "download_dir",
+    "peers_connected",
+    "peers_sending",
+    "peers_receiving",
+    "error",
+    "labels",
+    "file_count",
+    "bandwidth_group",
+    "seconds_seeding",
+    "trackers",
+    "daemon",
+    "downloaded_ever",
+    "uploaded_ever",
+    "added_date",
+    "done_date",
+    "queue_position",
+    "hash",
+    "peers",
+];
+
+/// Every column except the nested peer list.
+pub fn default_columns() -> Vec<String> {
+    EXPORT_COLUMNS
+        .iter()
+        .filter(|&&column| column != "peers")
+        .map(|column| column.to_string())
+        .collect()
+}
+
+/// Checks user-supplied column names against `format`, falling back to the
+/// defaults when none are given.
+pub fn parse_columns(requested: &[String], format: ExportFormat) -> Result<Vec<String>> {
+    if requested.is_empty() {
+        return Ok(default_columns());
+    }
+    for (idx, column) in requested.iter().enumerate() {
+        if !EXPORT_COLUMNS.contains(&column.as_str()) {
+            bail!(
+                "unknown column '{column}'; expected one of {}",
+                EXPORT_COLUMNS.join(", ")
+            );
+        }
+        if requested[..idx].contains(column) {
+            bail!("column '{column}' is listed twice");
+        }
+    }
+    check_columns(requested, format)?;
+    Ok(requested.to_vec())
+}
+
+fn check_columns(columns: &[String], format: ExportFormat) -> Result<()> {
+    if format == ExportFormat::Csv && columns.iter().any(|column| column == "peers") {
+        bail!("the peers column is only available in JSON and NDJSON exports");
+    }
+    Ok(())
+}
+
+/// Writes `torrents` with the chosen columns, in the given order.
+pub fn write_torrents(
+    out: &mut impl Write,
+    torrents: &[&TorrentSummary],
+    format: ExportFormat,
+    columns: &[String],
+) -> Result<()> {
+    check_columns(columns, format)?;
+    let rows = torrents
+        .iter()
+        .map(|torrent| pick_columns(torrent, columns))
+        .collect::<Result<Vec<_>>>()?;
+    match format {
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut *out, &rows)?;
+            writeln!(out)?;
+        }
+        ExportFormat::Ndjson => {
+            for row in &rows {
+                serde_json::to_writer(&mut *out, row)?;
+                writeln!(out)?;
+            }
+        }
+        ExportFormat::Csv => {
+            let mut csv = csv::Writer::from_writer(&mut *out);
+            csv.write_record(columns)?;
+            for row in &rows {
+                csv.write_record(row.values().map(csv_cell))?;
+            }
+            csv.flush()?;
+        }
+    }
+    Ok(())
+}
+
+fn pick_columns(torrent: &TorrentSummary, columns: &[String]) -> Result<Map<String, Value>> {
+    let Value::Object(mut fields) =
+        serde_json::to_value(torrent).context("failed to serialize torrent")?
+    else {
+        bail!("torrent did not serialize to an object");
+    };
+    Ok(columns
+        .iter()
+        .map(|column| {
+            let value = fields.remove(column).unwrap_or(Value::Null);
+            (column.clone(), value)
+        })
+        .collect())
+}
+
+/// Flattens one value for CSV: `null` is empty and lists (labels, trackers) are
+/// joined with `;`.
+fn csv_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items.iter().map(csv_cell).collect::<Vec<_>>().join(";"),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{model::PeerSummary, transitions::tests::torrent};
+
+    fn names(columns: &[&str]) -> Vec<String> {
+        columns.iter().map(|column| column.to_string()).collect()
+    }
+
+    #[test]
+    fn rejects_unknown_and_repeated_columns() {
+        let columns = names(&["id", "name"]);
+        assert_eq!(parse_columns(&columns, ExportFormat::Csv).unwrap(), columns);
+        assert!(parse_columns(&names(&["id", "bogus"]), ExportFormat::Json).is_err());
+        let err = parse_columns(&names(&["id", "name", "id"]), ExportFormat::Json).unwrap_err();
+        assert_eq!(err.to_string(), "column 'id' is listed twice");
+        assert!(parse_columns(&names(&["peers"]), ExportFormat::Csv).is_err());
+    }
+
+    fn fixtures() -> Vec<TorrentSummary> {
+        let mut first = torrent(1, "Big, \"quoted\" ISO");
+        first.rate_download = 1_048_576;
+        first.size_when_done = 4_700_000_000;
+        first.eta = Some(3600);
+        first.labels = vec!["linux".to_string(), "iso".to_string()];
+        first.peers = vec![PeerSummary {
+            address: "10.0.0.2".to_string(),
+            client: "Transmission 4.0.5".to_string(),
+            progress: 0.25,
+            rate_down: 2048,
+            rate_up: 0,
+            is_encrypted: true,
+        }];
+        let second = torrent(2, "plain");
+        vec![first, second]
+    }
+
+    fn export(format: ExportFormat, columns: &[&str]) -> String {
+        let torrents = fixtures();
+        let rows: Vec<&TorrentSummary> = torrents.iter().collect();
+        let mut out = Vec::new();
+        write_torrents(&mut out, &rows, format, &names(columns)).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    const COLUMNS: &[&str] = &[
+        "id",
+        "name",
+        "rate_download",
+        "size_when_done",
+        "eta",
+        "labels",
+    ];
+
+    #[test]
+    fn writes_json_with_raw_values_in_column_order() {
+        let expected = r#"[
+  {
+    "id": 1,
+    "name": "Big, \"quoted\" ISO",
+    "rate_download": 1048576,
+    "size_when_done": 4700000000,
+    "eta": 3600,
+    "labels": [
+      "linux",
+      "iso"
+    ]
+  },
+  {
+    "id": 2,
+    "name": "plain",
+    "rate_download": 0,
+    "size_when_done": 1000,
+    "eta": null,
+    "labels": []
+  }
+]
+"#;
+        assert_eq!(export(ExportFormat::Json, COLUMNS), expected);
+    }
+
+    #[test]
+    fn writes_one_ndjson_object_per_line_including_peers() {
+        let expected = concat!(
+            r#"{"id":1,"percent_done":0.5,"peers":[{"address":"10.0.0.2","client":"Transmission 4.0.5","progress":0.25,"rate_down":2048,"rate_up":0,"is_encrypted":true}]}"#,
+            "\n",
+            r#"{"id":2,"percent_done":0.5,"peers":[]}"#,
+            "\n",
+        );
+        assert_eq!(
+            export(ExportFormat::Ndjson, &["id", "percent_done", "peers"]),
+            expected
+        );
+    }
+
+    #[test]
+    fn writes_csv_with_quoted_names_and_joined_lists() {
+        let expected = concat!(
+            "id,name,rate_download,size_when_done,eta,labels\n",
+            "1,\"Big, \"\"quoted\"\" ISO\",1048576,4700000000,3600,linux;iso\n",
+            "2,plain,0,1000,,\n",
+        );
+        assert_eq!(export(ExportFormat::Csv, COLUMNS), expected);
+    }
+
+    #[test]
+    fn refuses_peers_in_csv_when_writing() {
+        let torrents = fixtures();
+        let rows: Vec<&TorrentSummary> = torrents.iter().collect();
+        let mut out = Vec::new();
+        assert!(write_torrents(&mut out, &rows, ExportFormat::Csv, &names(&["peers"])).is_err());
+        assert!(out.is_empty());
+    }
+}
@@ -0,0 +1,197 @@
+/// A fuzzy match of a pattern against some text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    /// Higher is better; only comparable between matches of one pattern.
+    pub score: i64,
+    /// Char indexes of `text` that matched, ascending.
+    pub positions: Vec<usize>,
+}
+
+const MATCH_SCORE: i64 = 16;
+/// Matching right after the previous pattern char.
+const CONSECUTIVE_BONUS: i64 = 12;
+/// Matching the first char of the text or of a word in it.
+const WORD_START_BONUS: i64 = 8;
+const FIRST_CHAR_BONUS: i64 = 8;
+/// Per text char skipped between two matched chars.
+const GAP_PENALTY: i64 = 1;
+/// Cost of a word matched with one of its chars left out.
+const TYPO_PENALTY: i64 = 2 * MATCH_SCORE;
+/// Words shorter than this have to match every char.
+const TYPO_MIN_LEN: usize = 4;
+
+/// Matches the chars of `pattern` in order anywhere in `text`, ignoring
+/// case, and returns the best scoring alignment. Runs of consecutive chars
+/// and matches at word starts (after a separator or a lower-to-upper case
+/// change) score higher; gaps cost a little. An empty pattern matches
+/// everything with score 0.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<Match> {
+    let pattern: Vec<char> = pattern.chars().filter(|c| !c.is_whitespace()).collect();
+    if pattern.is_empty() {
+        return Some(Match {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+    let text: Vec<char> = text.chars().collect();
+    if pattern.len() > text.len() {
+        return None;
+    }
+    let bonuses: Vec<i64> = (0..text.len())
+        .map(|idx| position_bonus(&text, idx))
+        .collect();
+    let n = text.len();
+    // best[i][j]: best score with pattern[i] matched at text[j]; from[i][j]
+    // the text index pattern[i - 1] matched at on that path.
+    let mut best = vec![vec![None::<i64>; n]; pattern.len()];
+    let mut from = vec![vec![0usize; n]; pattern.len()];
+    for (j, &c) in text.iter().enumerate() {
+        if same_char(pattern[0], c) {
+            best[0][j] = Some(MATCH_SCORE + bonuses[j]);
+        }
+    }
+    for i in 1..pattern.len() {
+        // Best `best[i - 1][k] + k` over k < j - 1, for the gap penalty.
+        let mut gapped: Option<(i64, usize)> = None;
+        for j in i..n {
+            if j >= 2 {
+                if let Some(score) = best[i - 1][j - 2] {
+                    let candidate = score + (j - 2) as i64 * GAP_PENALTY;
+                    if gapped.is_none_or(|(top, _)| candidate > top) {
+                        gapped = Some((candidate, j - 2));
+                    }
+                }
+            }
+            if !same_char(pattern[i], text[j]) {
+                continue;
+            }
+            let adjacent = best[i - 1][j - 1].map(|score| (score + CONSECUTIVE_BONUS, j - 1));
+            let skipped = gapped.map(|(top, k)| (top - (j as i64 - 1) * GAP_PENALTY, k));
+            let previous = match (adjacent, skipped) {
+                (Some(a), Some(s)) => Some(if s.0 > a.0 { s } else { a }),
+                (a, s) => a.or(s),
+            };
+            if let Some((score, k)) = previous {
+                best[i][j] = Some(score + MATCH_SCORE + bonuses[j]);
+                from[i][j] = k;
+            }
+        }
+    }
+    let last = pattern.len() - 1;
+    let (mut j, score) = best[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, score)| score.map(|score| (j, score)))
+        .max_by_key(|&(j, score)| (score, std::cmp::Reverse(j)))?;
+    let mut positions = vec![0; pattern.len()];
+    for i in (0..pattern.len()).rev() {
+        positions[i] = j;
+        if i > 0 {
+            j = from[i][j];
+        }
+    }
+    Some(Match { score, positions })
+}
+
+/// Matches each whitespace-separated word of `query` on its own, so words
+/// may come in any order; every word has to match. A word of
+/// `TYPO_MIN_LEN` chars or more may leave out one char, which forgives a
+/// typo or two swapped letters at a cost.
+pub fn match_words(query: &str, text: &str) -> Option<Match> {
+    let mut score = 0;
+    let mut positions = Vec::new();
+    for word in query.split_whitespace() {
+        let found = fuzzy_match(word, text).or_else(|| match_with_typo(word, text))?;
+        score += found.score;
+        positions.extend(found.positions);
+    }
+    positions.sort_unstable();
+    positions.dedup();
+    Some(Match { score, positions })
+}
+
+fn match_with_typo(word: &str, text: &str) -> Option<Match> {
+    let chars: Vec<char> = word.chars().collect();
+    if chars.len() < TYPO_MIN_LEN {
+        return None;
+    }
+    (0..chars.len())
+        .filter_map(|skip| {
+            let shorter: String = chars
+                .iter()
+                .enumerate()
+                .filter(|&(idx, _)| idx != skip)
+                .map(|(_, &c)| c)
+                .collect();
+            fuzzy_match(&shorter, text)
+        })
+        .max_by_key(|found| found.score)
+        .map(|found| Match {
+            score: found.score - TYPO_PENALTY,
+            positions: found.positions,
+        })
+}
+
+fn same_char(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+fn position_bonus(text: &[char], idx: usize) -> i64 {
+    if idx == 0 {
+        return WORD_START_BONUS + FIRST_CHAR_BONUS;
+    }
+    let (previous, current) = (text[idx - 1], text[idx]);
+    let separator = !previous.is_alphanumeric();
+    let camel = previous.is_lowercase() && current.is_uppercase();
+    if (separator && current.is_alphanumeric()) || camel {
+        WORD_START_BONUS
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(pattern: &str, text: &str) -> i64 {
+        fuzzy_match(pattern, text).expect("should match").score
+    }
+
+    #[test]
+    fn matches_chars_in_order_ignoring_case() {
+        let found = fuzzy_match("dbn", "Debian.iso").unwrap();
+        assert_eq!(found.positions, vec![0, 2, 5]);
+        assert!(fuzzy_match("nbd", "Debian.iso").is_none());
+        assert!(fuzzy_match("isoo", "debian.iso").is_none());
+    }
+
+    #[test]
+    fn prefers_runs_and_word_starts() {
+        let found = fuzzy_match("iso", "linux-distro.iso").unwrap();
+        assert_eq!(found.positions, vec![13, 14, 15]);
+        assert!(score("ubu", "ubuntu") > score("ubu", "my-ubuntu"));
+        assert!(score("ubu", "my-ubuntu") > score("ubu", "xubuntu"));
+        assert!(score("rat", "ratio") > score("rat", "progress-rate"));
+    }
+
+    #[test]
+    fn words_match_in_any_order_and_forgive_one_typo() {
+        let found = match_words("iso deb", "debian-12.iso").unwrap();
+        assert_eq!(found.positions, vec![0, 1, 2, 10, 11, 12]);
+        assert!(match_words("debain", "debian-12.iso").is_some());
+        assert!(
+            score("debian", "debian-12.iso")
+                > match_words("debain", "debian-12.iso").unwrap().score
+        );
+        assert!(match_words("arch", "debian-12.iso").is_none());
+        assert!(match_words("iso xyz", "debian-12.iso").is_none());
+        assert!(match_words("dbx", "debian").is_none());
+    }
+
+    #[test]
+    fn empty_pattern_matches_everything() {
+        assert_eq!(score("", "anything"), 0);
+        assert_eq!(score(" ", ""), 0);
+    }
+}
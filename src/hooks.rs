@@ -0,0 +1,412 @@
+use std::{
+    collections::VecDeque,
+    io::{Read, Write},
+    process::{Command, Stdio},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crossbeam_channel::{bounded, unbounded, Sender};
+use serde_json::json;
+
+use crate::transitions::{Transition, TransitionKind};
+
+/// Runs kept for the hook log overlay.
+const LOG_LIMIT: usize = 100;
+const CHECK_INTERVAL: Duration = Duration::from_millis(100);
+/// How long to wait for a finished hook's stderr to close; a background
+/// grandchild may hold it open indefinitely.
+const OUTPUT_GRACE: Duration = Duration::from_secs(1);
+
+/// One `[[hooks]]` entry: a shell command run for some transitions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hook {
+    pub on: Vec<TransitionKind>,
+    pub command: String,
+    /// Target for `ratio_reached`.
+    pub ratio: Option<f64>,
+    /// Only fire for torrents from this profile.
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HookConfig {
+    pub hooks: Vec<Hook>,
+    /// Hooks allowed to run at once; later ones wait in a queue.
+    pub concurrency: usize,
+    /// Hooks still running after this are killed.
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed(String),
+}
+
+/// One entry in the hook log.
+#[derive(Debug, Clone)]
+pub struct HookRun {
+    id: u64,
+    pub command: String,
+    pub event: TransitionKind,
+    pub torrent: String,
+    pub profile: String,
+    pub queued_at: Instant,
+    pub duration: Option<Duration>,
+    pub status: HookStatus,
+}
+
+struct Job {
+    id: u64,
+    command: String,
+    env: Vec<(&'static str, String)>,
+    payload: Vec<u8>,
+}
+
+/// Feeds matching transitions to a fixed pool of worker threads. Dropping the
+/// runner stops the pool once queued hooks are done; hooks that are still
+/// running when the app exits are left to finish on their own.
+pub struct HookRunner {
+    hooks: Vec<Hook>,
+    jobs: Option<Sender<Job>>,
+    log: Arc<Mutex<VecDeque<HookRun>>>,
+    next_id: u64,
+}
+
+impl HookRunner {
+    /// `on_finish` is called from a worker thread after every run.
+    pub fn start(config: HookConfig, on_finish: impl Fn(&HookRun) + Send + Sync + 'static) -> Self {
+        let log = Arc::new(Mutex::new(VecDeque::new()));
+        if config.hooks.is_empty() {
+            return Self {
+                hooks: Vec::new(),
+                jobs: None,
+                log,
+                next_id: 0,
+            };
+        }
+        let (jobs, queue) = unbounded::<Job>();
+        let on_finish = Arc::new(on_finish);
+        for _ in 0..config.concurrency.max(1) {
+            let queue = queue.clone();
+            let log = Arc::clone(&log);
+            let on_finish = Arc::clone(&on_finish);
+            let timeout = config.timeout;
+            thread::spawn(move || {
+                for job in queue.iter() {
+                    update(&log, job.id, |run| run.status = HookStatus::Running);
+                    let started = Instant::now();
+                    let status = run_job(&job, timeout);
+                    let finished = update(&log, job.id, |run| {
+                        run.status = status;
+                        run.duration = Some(started.elapsed());
+                    });
+                    if let Some(run) = finished {
+                        on_finish(&run);
+                    }
+                }
+            });
+        }
+        Self {
+            hooks: config.hooks,
+            jobs: Some(jobs),
+            log,
+            next_id: 0,
+        }
+    }
+
+    /// Distinct `ratio_reached` targets, for [`crate::transitions::diff`].
+    pub fn ratio_targets(&self) -> Vec<f64> {
+        let mut targets: Vec<f64> = self.hooks.iter().filter_map(|hook| hook.ratio).collect();
+        targets.sort_by(f64::total_cmp);
+        targets.dedup();
+        targets
+    }
+
+    /// Queues every hook that matches one of `transitions` from `profile`.
+    pub fn dispatch(&mut self, profile: &str, transitions: &[Transition]) {
+        let Some(jobs) = &self.jobs else {
+            return;
+        };
+        for transition in transitions {
+            for hook in &self.hooks {
+                let matches = hook.on.contains(&transition.kind)
+                    && hook.profile.as_deref().is_none_or(|name| name == profile)
+                    && (transition.kind != TransitionKind::RatioReached
+                        || hook.ratio == transition.ratio);
+                if !matches {
+                    continue;
+                }
+                self.next_id += 1;
+                let id = self.next_id;
+                if let Ok(mut log) = self.log.lock() {
+                    if log.len() == LOG_LIMIT {
+                        log.pop_back();
+                    }
+                    log.push_front(HookRun {
+                        id,
+                        command: hook.command.clone(),
+                        event: transition.kind,
+                        torrent: transition.torrent.name.clone(),
+                        profile: profile.to_string(),
+                        queued_at: Instant::now(),
+                        duration: None,
+                        status: HookStatus::Queued,
+                    });
+                }
+                let _ = jobs.send(Job {
+                    id,
+                    command: hook.command.clone(),
+                    env: hook_env(profile, transition),
+                    payload: hook_payload(profile, transition),
+                });
+            }
+        }
+    }
+
+    /// Recent runs, newest first.
+    pub fn recent(&self) -> Vec<HookRun> {
+        self.log
+            .lock()
+            .map(|log| log.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+fn update(
+    log: &Mutex<VecDeque<HookRun>>,
+    id: u64,
+    change: impl FnOnce(&mut HookRun),
+) -> Option<HookRun> {
+    let mut log = log.lock().ok()?;
+    let run = log.iter_mut().find(|run| run.id == id)?;
+    change(run);
+    Some(run.clone())
+}
+
+/// Environment named after the variables Transmission's own
+/// `script-torrent-done` hooks receive, plus a few extras.
+fn hook_env(profile: &str, transition: &Transition) -> Vec<(&'static str, String)> {
+    let torrent = &transition.torrent;
+    vec![
+        ("TR_EVENT", transition.kind.label().to_string()),
+        ("TR_PROFILE", profile.to_string()),
+        ("TR_TORRENT_ID", torrent.torrent_id.to_string()),
+        ("TR_TORRENT_NAME", torrent.name.clone()),
+        ("TR_TORRENT_DIR", torrent.download_dir.clone()),
+        ("TR_TORRENT_STATUS", torrent.status.clone()),
+        ("TR_TORRENT_PERCENT_DONE", torrent.percent_done.to_string()),
+        ("TR_TORRENT_RATIO", torrent.upload_ratio.to_string()),
+        ("TR_TORRENT_SIZE", torrent.size_when_done.to_string()),
+        ("TR_TORRENT_LABELS", torrent.labels.join(",")),
+        (
+            "TR_TORRENT_ERROR",
+            torrent.error.clone().unwrap_or_default(),
+        ),
+        (
+            "TR_RATIO_TARGET",
+            transition
+                .ratio
+                .map(|ratio| ratio.to_string())
+                .unwrap_or_default(),
+        ),
+    ]
+}
+
+fn hook_payload(profile: &str, transition: &Transition) -> Vec<u8> {
+    let mut payload = json!({
+        "event": transition.kind,
+        "profile": profile,
+        "ratio_target": transition.ratio,
+        "torrent": transition.torrent,
+    })
+    .to_string()
+    .into_bytes();
+    payload.push(b'\n');
+    payload
+}
+
+fn run_job(job: &Job, timeout: Duration) -> HookStatus {
+    let spawned = Command::new("sh")
+        .arg("-c")
+        .arg(&job.command)
+        .envs(job.env.iter().map(|(key, value)| (key, value)))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(err) => return HookStatus::Failed(format!("failed to run: {err}")),
+    };
+    // Written from a thread so a hook that never reads stdin cannot block us.
+    if let Some(mut stdin) = child.stdin.take() {
+        let payload = job.payload.clone();
+        thread::spawn(move || {
+            let _ = stdin.write_all(&payload);
+        });
+    }
+    let (stderr_tx, stderr_rx) = bounded(1);
+    if let Some(mut stderr) = child.stderr.take() {
+        thread::spawn(move || {
+            let mut output = String::new();
+            let _ = stderr.read_to_string(&mut output);
+            let _ = stderr_tx.send(output);
+        });
+    }
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return HookStatus::Failed(format!("timed out after {}s", timeout.as_secs()));
+            }
+            Ok(None) => thread::sleep(CHECK_INTERVAL),
+            Err(err) => return HookStatus::Failed(err.to_string()),
+        }
+    };
+    if status.success() {
+        return HookStatus::Succeeded;
+    }
+    let stderr = stderr_rx.recv_timeout(OUTPUT_GRACE).unwrap_or_default();
+    match stderr.lines().rev().find(|line| !line.trim().is_empty()) {
+        Some(line) => HookStatus::Failed(format!("{status}: {}", line.trim())),
+        None => HookStatus::Failed(status.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::transitions::tests::torrent;
+
+    fn hook(on: &[TransitionKind], command: &str) -> Hook {
+        Hook {
+            on: on.to_vec(),
+            command: command.to_string(),
+            ratio: None,
+            profile: None,
+        }
+    }
+
+    fn transition(kind: TransitionKind, ratio: Option<f64>) -> Transition {
+        Transition {
+            kind,
+            torrent: torrent(7, "Debian ISO"),
+            ratio,
+        }
+    }
+
+    /// Runs `hooks` for `transitions` and waits for every queued run.
+    fn dispatch(hooks: Vec<Hook>, profile: &str, transitions: &[Transition]) -> Vec<HookRun> {
+        let (done, finished) = unbounded();
+        let mut runner = HookRunner::start(
+            HookConfig {
+                hooks,
+                concurrency: 1,
+                timeout: Duration::from_secs(10),
+            },
+            move |run| {
+                let _ = done.send(run.clone());
+            },
+        );
+        runner.dispatch(profile, transitions);
+        let queued = runner.recent().len();
+        (0..queued)
+            .map(|_| finished.recv_timeout(Duration::from_secs(10)).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn hooks_match_on_event_profile_and_ratio() {
+        let mut ratio_two = hook(&[TransitionKind::RatioReached], "true");
+        ratio_two.ratio = Some(2.0);
+        let mut elsewhere = hook(&[TransitionKind::Finished], "true");
+        elsewhere.profile = Some("seedbox".to_string());
+        let hooks = vec![
+            hook(&[TransitionKind::Finished, TransitionKind::Removed], "true"),
+            ratio_two,
+            elsewhere,
+        ];
+        let config = HookConfig {
+            hooks: hooks.clone(),
+            concurrency: 1,
+            timeout: Duration::from_secs(10),
+        };
+        assert_eq!(HookRunner::start(config, |_| {}).ratio_targets(), [2.0]);
+
+        let mut runs = dispatch(
+            hooks,
+            "home",
+            &[
+                transition(TransitionKind::Finished, None),
+                transition(TransitionKind::Seeding, None),
+                transition(TransitionKind::RatioReached, Some(1.0)),
+                transition(TransitionKind::RatioReached, Some(2.0)),
+                transition(TransitionKind::Removed, None),
+            ],
+        );
+        runs.sort_by_key(|run| run.id);
+        let events: Vec<TransitionKind> = runs.iter().map(|run| run.event).collect();
+        assert_eq!(
+            events,
+            [
+                TransitionKind::Finished,
+                TransitionKind::RatioReached,
+                TransitionKind::Removed,
+            ]
+        );
+        assert!(runs
+            .iter()
+            .all(|run| run.profile == "home" && run.status == HookStatus::Succeeded));
+    }
+
+    #[test]
+    fn hooks_get_the_transition_in_env_and_stdin() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out");
+        let command = format!(
+            "printf '%s %s %s %s\\n' \"$TR_EVENT\" \"$TR_PROFILE\" \"$TR_TORRENT_ID\" \"$TR_RATIO_TARGET\" > '{0}'; cat >> '{0}'",
+            out.display()
+        );
+        let mut on_ratio = hook(&[TransitionKind::RatioReached], &command);
+        on_ratio.ratio = Some(1.5);
+        let runs = dispatch(
+            vec![on_ratio],
+            "home",
+            &[transition(TransitionKind::RatioReached, Some(1.5))],
+        );
+        assert_eq!(runs[0].status, HookStatus::Succeeded);
+        let text = fs::read_to_string(&out).unwrap();
+        let (env, payload) = text.split_once('\n').unwrap();
+        assert_eq!(env, "ratio_reached home 7 1.5");
+        let payload: serde_json::Value = serde_json::from_str(payload).unwrap();
+        assert_eq!(payload["event"], "ratio_reached");
+        assert_eq!(payload["ratio_target"], 1.5);
+        assert_eq!(payload["torrent"]["name"], "Debian ISO");
+    }
+
+    #[test]
+    fn failed_hooks_report_their_last_stderr_line() {
+        let runs = dispatch(
+            vec![hook(
+                &[TransitionKind::Errored],
+                "echo first >&2; echo 'disk full' >&2; exit 3",
+            )],
+            "home",
+            &[transition(TransitionKind::Errored, None)],
+        );
+        assert_eq!(
+            runs[0].status,
+            HookStatus::Failed("exit status: 3: disk full".to_string())
+        );
+    }
+}
@@ -0,0 +1,738 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use anyhow::{bail, Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+/// Input modes with their own key map. Text fields (filter, add prompt,
+/// export path, preference values being edited) keep fixed keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyMode {
+    Normal,
+    Confirm,
+    Help,
+    Profiles,
+    Hooks,
+    Rules,
+    Columns,
+    Views,
+    Preferences,
+}
+
+impl KeyMode {
+    pub const ALL: [KeyMode; 9] = [
+        KeyMode::Normal,
+        KeyMode::Confirm,
+        KeyMode::Help,
+        KeyMode::Profiles,
+        KeyMode::Hooks,
+        KeyMode::Rules,
+        KeyMode::Columns,
+        KeyMode::Views,
+        KeyMode::Preferences,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            KeyMode::Normal => "normal",
+            KeyMode::Confirm => "confirm",
+            KeyMode::Help => "help",
+            KeyMode::Profiles => "profiles",
+            KeyMode::Hooks => "hooks",
+            KeyMode::Rules => "rules",
+            KeyMode::Columns => "columns",
+            KeyMode::Views => "views",
+            KeyMode::Preferences => "preferences",
+        }
+    }
+
+    /// Default bindings, in the order the help overlay lists them.
+    fn defaults(self) -> &'static [(Action, &'static [&'static str])] {
+        match self {
+            KeyMode::Normal => &[
+                (Action::Down, &["j"]),
+                (Action::Up, &["k"]),
+                (Action::Top, &["g"]),
+                (Action::Bottom, &["G"]),
+                (Action::PageDown, &["ctrl+d"]),
+                (Action::PageUp, &["ctrl+u"]),
+                (Action::Details, &["enter"]),
+                (Action::Resume, &["r"]),
+                (Action::Refresh, &["R"]),
+                (Action::Pause, &["p"]),
+                (Action::Reannounce, &["u"]),
+                (Action::AddMagnet, &["a"]),
+                (Action::Preferences, &["o"]),
+                (Action::Profiles, &["P"]),
+                (Action::CycleDaemon, &["t"]),
+                (Action::Export, &["e"]),
+                (Action::Hooks, &["H"]),
+                (Action::Rules, &["A"]),
+                (Action::Columns, &["C"]),
+                (Action::Views, &["V"]),
+                (Action::Remove, &["d d"]),
+                (Action::Trash, &["D D"]),
+                (Action::Filter, &["/"]),
+                (Action::ClearFilter, &["esc"]),
+                (Action::Command, &[":"]),
+                (Action::Help, &["?"]),
+                (Action::Quit, &["q"]),
+            ],
+            KeyMode::Confirm => &[
+                (Action::Accept, &["y", "enter"]),
+                (Action::Cancel, &["n", "esc"]),
+            ],
+            KeyMode::Help => &[(Action::Close, &["?", "esc", "enter", "q"])],
+            KeyMode::Profiles => &[
+                (Action::Down, &["j", "down"]),
+                (Action::Up, &["k", "up"]),
+                (Action::Select, &["enter"]),
+                (Action::Close, &["esc", "q", "P"]),
+            ],
+            KeyMode::Hooks => &[
+                (Action::Down, &["j", "down"]),
+                (Action::Up, &["k", "up"]),
+                (Action::Close, &["esc", "q", "H"]),
+            ],
+            KeyMode::Rules => &[
+                (Action::Down, &["j", "down"]),
+                (Action::Up, &["k", "up"]),
+                (Action::Close, &["esc", "q", "A"]),
+            ],
+            KeyMode::Columns => &[
+                (Action::Down, &["j", "down"]),
+                (Action::Up, &["k", "up"]),
+                (Action::Toggle, &["space"]),
+                (Action::MoveDown, &["J"]),
+                (Action::MoveUp, &["K"]),
+                (Action::Accept, &["enter"]),
+                (Action::Cancel, &["esc", "q"]),
+            ],
+            KeyMode::Views => &[
+                (Action::Down, &["j", "down"]),
+                (Action::Up, &["k", "up"]),
+                (Action::Select, &["enter"]),
+                (Action::SaveView, &["s"]),
+                (Action::Close, &["esc", "q", "V"]),
+            ],
+            KeyMode::Preferences => &[
+                (Action::Down, &["j", "down"]),
+                (Action::Up, &["k", "up"]),
+                (Action::Toggle, &["space"]),
+                (Action::Select, &["enter"]),
+                (Action::Previous, &["left"]),
+                (Action::Next, &["right"]),
+                (Action::Save, &["s"]),
+                (Action::Refresh, &["r", "R"]),
+                (Action::Close, &["esc", "q"]),
+            ],
+        }
+    }
+}
+
+/// Something a key can do. Which actions a mode accepts is fixed by its
+/// defaults; `[keys.<mode>]` can only rebind them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Down,
+    Up,
+    Top,
+    Bottom,
+    PageDown,
+    PageUp,
+    Details,
+    Resume,
+    Refresh,
+    Pause,
+    Reannounce,
+    AddMagnet,
+    Preferences,
+    Profiles,
+    CycleDaemon,
+    Export,
+    Hooks,
+    Rules,
+    Columns,
+    Views,
+    Remove,
+    Trash,
+    Filter,
+    ClearFilter,
+    Command,
+    Help,
+    Quit,
+    Accept,
+    Cancel,
+    Select,
+    Close,
+    Toggle,
+    MoveDown,
+    MoveUp,
+    SaveView,
+    Previous,
+    Next,
+    Save,
+}
+
+impl Action {
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Down => "Move down",
+            Action::Up => "Move up",
+            Action::Top => "Jump to first",
+            Action::Bottom => "Jump to last",
+            Action::PageDown => "Half-page down",
+            Action::PageUp => "Half-page up",
+            Action::Details => "Toggle full-height details",
+            Action::Resume => "Resume selected torrent",
+            Action::Refresh => "Refresh now",
+            Action::Pause => "Pause selected torrent",
+            Action::Reannounce => "Reannounce selected torrent",
+            Action::AddMagnet => "Add magnet",
+            Action::Preferences => "Edit daemon preferences",
+            Action::Profiles => "Switch daemon profile / all profiles",
+            Action::CycleDaemon => "Cycle daemon filter (all profiles)",
+            Action::Export => "Export visible torrents (JSON / NDJSON / CSV)",
+            Action::Hooks => "Show recent hook runs",
+            Action::Rules => "Show rules, what they match and the rule log",
+            Action::Columns => "Choose and order list columns",
+            Action::Views => "Pick a saved view",
+            Action::Remove => "Delete highlighted torrent",
+            Action::Trash => "Trash data + remove highlighted torrent",
+            Action::Filter => "Filter list",
+            Action::ClearFilter => "Clear filter",
+            Action::Command => "Open command line",
+            Action::Help => "Toggle this help",
+            Action::Quit => "Quit",
+            Action::Accept => "Accept",
+            Action::Cancel => "Cancel",
+            Action::Select => "Select",
+            Action::Close => "Close",
+            Action::Toggle => "Show / hide",
+            Action::MoveDown => "Move later",
+            Action::MoveUp => "Move earlier",
+            Action::SaveView => "Save current filter, sort and columns",
+            Action::Previous => "Previous option",
+            Action::Next => "Next option",
+            Action::Save => "Save changes",
+        }
+    }
+
+    /// Whether the help overlay lists this under Navigation.
+    pub fn is_navigation(self) -> bool {
+        matches!(
+            self,
+            Action::Down
+                | Action::Up
+                | Action::Top
+                | Action::Bottom
+                | Action::PageDown
+                | Action::PageUp
+        )
+    }
+}
+
+/// One key press with its modifiers. Shift is folded into the character for
+/// printable keys, so `G` and `shift+g` are the same chord.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let relevant = KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT;
+        let mut modifiers = modifiers & relevant;
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            KeyCode::BackTab => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            other => other,
+        };
+        Self { code, modifiers }
+    }
+}
+
+impl From<KeyEvent> for KeyChord {
+    fn from(key: KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = anyhow::Error;
+
+    /// Parses `x`, `ctrl+x`, `alt+shift+up`, `enter`, `f5`, `space`, …
+    fn from_str(text: &str) -> Result<Self> {
+        let (prefix, key) = if text.chars().count() == 1 {
+            ("", text)
+        } else if let Some(prefix) = text.strip_suffix("++") {
+            (prefix, "+")
+        } else {
+            match text.rsplit_once('+') {
+                Some((prefix, key)) => (prefix, key),
+                None => ("", text),
+            }
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for name in prefix.split('+').filter(|name| !name.is_empty()) {
+            modifiers |= match name.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => bail!("unknown modifier `{name}` in `{text}`"),
+            };
+        }
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "space" => KeyCode::Char(' '),
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=24).contains(&n) => KeyCode::F(n),
+                    _ => bail!("unknown key `{key}` in `{text}`"),
+                },
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            f.write_str("Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::Enter => f.write_str("Enter"),
+            KeyCode::Esc => f.write_str("Esc"),
+            KeyCode::Tab => f.write_str("Tab"),
+            KeyCode::BackTab => f.write_str("Shift+Tab"),
+            KeyCode::Backspace => f.write_str("Backspace"),
+            KeyCode::Delete => f.write_str("Delete"),
+            KeyCode::Insert => f.write_str("Insert"),
+            KeyCode::Home => f.write_str("Home"),
+            KeyCode::End => f.write_str("End"),
+            KeyCode::PageUp => f.write_str("PageUp"),
+            KeyCode::PageDown => f.write_str("PageDown"),
+            KeyCode::Up => f.write_str("Up"),
+            KeyCode::Down => f.write_str("Down"),
+            KeyCode::Left => f.write_str("Left"),
+            KeyCode::Right => f.write_str("Right"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            other => write!(f, "{other:?}"),
+        }
+    }
+}
+
+/// Keys pressed in order; `d d` is two presses of `d`.
+pub type KeySequence = Vec<KeyChord>;
+
+fn parse_sequence(text: &str) -> Result<KeySequence> {
+    let sequence = text
+        .split_whitespace()
+        .map(KeyChord::from_str)
+        .collect::<Result<KeySequence>>()?;
+    if sequence.is_empty() {
+        bail!("empty key binding");
+    }
+    Ok(sequence)
+}
+
+/// Sequences of plain characters print run together (`dd`), anything else
+/// with spaces (`g Ctrl+d`).
+pub fn format_sequence(sequence: &[KeyChord]) -> String {
+    let plain = sequence.iter().all(|chord| {
+        chord.modifiers.is_empty() && matches!(chord.code, KeyCode::Char(c) if c != ' ')
+    });
+    let parts: Vec<String> = sequence.iter().map(ToString::to_string).collect();
+    parts.join(if plain { "" } else { " " })
+}
+
+/// Result of looking up the keys pressed so far.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyLookup {
+    Action(Action),
+    /// The keys start one or more longer bindings.
+    Prefix(Vec<(KeySequence, Action)>),
+    None,
+}
+
+/// A `[keys.<mode>]` value: one binding or a list of them.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum FileBinding {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// The `[keys]` table as read from the config file.
+pub type FileKeys = BTreeMap<KeyMode, BTreeMap<Action, FileBinding>>;
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    modes: BTreeMap<KeyMode, Vec<(KeySequence, Action)>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(&FileKeys::new()).expect("default key bindings parse")
+    }
+}
+
+impl Keymap {
+    /// Starts from the defaults; an action listed in `[keys.<mode>]` loses
+    /// its default keys, and keys it takes over are removed from whatever
+    /// action had them.
+    pub fn from_config(overrides: &FileKeys) -> Result<Self> {
+        let mut modes = BTreeMap::new();
+        for mode in KeyMode::ALL {
+            let defaults = mode.defaults();
+            let custom = overrides.get(&mode);
+            let mut bindings: Vec<(KeySequence, Action)> = Vec::new();
+            for &(action, keys) in defaults {
+                if custom.is_some_and(|custom| custom.contains_key(&action)) {
+                    continue;
+                }
+                for key in keys {
+                    bindings.push((parse_sequence(key)?, action));
+                }
+            }
+            for (action, binding) in custom.into_iter().flatten() {
+                if !defaults.iter().any(|(known, _)| known == action) {
+                    bail!(
+                        "[keys.{}]: `{}` is not an action in this mode",
+                        mode.label(),
+                        action_name(*action)
+                    );
+                }
+                let keys = match binding {
+                    FileBinding::One(key) => vec![key.clone()],
+                    FileBinding::Many(keys) => keys.clone(),
+                };
+                for key in keys {
+                    let sequence = parse_sequence(&key).with_context(|| {
+                        format!("[keys.{}] {}", mode.label(), action_name(*action))
+                    })?;
+                    bindings.retain(|(existing, _)| existing != &sequence);
+                    bindings.push((sequence, *action));
+                }
+            }
+            check_prefixes(mode, &bindings)?;
+            modes.insert(mode, bindings);
+        }
+        Ok(Self { modes })
+    }
+
+    pub fn lookup(&self, mode: KeyMode, pressed: &[KeyChord]) -> KeyLookup {
+        let bindings = &self.modes[&mode];
+        if let Some((_, action)) = bindings.iter().find(|(keys, _)| keys == pressed) {
+            return KeyLookup::Action(*action);
+        }
+        let longer: Vec<(KeySequence, Action)> = bindings
+            .iter()
+            .filter(|(keys, _)| keys.len() > pressed.len() && keys.starts_with(pressed))
+            .cloned()
+            .collect();
+        if longer.is_empty() {
+            KeyLookup::None
+        } else {
+            KeyLookup::Prefix(longer)
+        }
+    }
+
+    /// The first key bound to `action` in `mode`, as shown to the user.
+    pub fn first_key(&self, mode: KeyMode, action: Action) -> Option<String> {
+        self.modes[&mode]
+            .iter()
+            .find(|(_, bound)| *bound == action)
+            .map(|(keys, _)| format_sequence(keys))
+    }
+
+    /// `label` after the first key of each of `actions`, e.g. `j/k scroll`.
+    /// `None` when one of them has no key.
+    pub fn hint(&self, mode: KeyMode, actions: &[Action], label: &str) -> Option<String> {
+        let keys = actions
+            .iter()
+            .map(|&action| self.first_key(mode, action))
+            .collect::<Option<Vec<_>>>()?;
+        Some(format!("{} {label}", keys.join("/")))
+    }
+
+    /// Every binding of `mode`, grouped by action in help order.
+    pub fn entries(&self, mode: KeyMode) -> Vec<(Action, Vec<String>)> {
+        mode.defaults()
+            .iter()
+            .map(|&(action, _)| {
+                let keys = self.modes[&mode]
+                    .iter()
+                    .filter(|(_, bound)| *bound == action)
+                    .map(|(keys, _)| format_sequence(keys))
+                    .collect();
+                (action, keys)
+            })
+            .filter(|(_, keys): &(Action, Vec<String>)| !keys.is_empty())
+            .collect()
+    }
+}
+
+/// A binding that is also the start of a longer one could never complete.
+fn check_prefixes(mode: KeyMode, bindings: &[(KeySequence, Action)]) -> Result<()> {
+    for (short, short_action) in bindings {
+        for (long, long_action) in bindings {
+            if long.len() > short.len() && long.starts_with(short) {
+                bail!(
+                    "[keys.{}]: `{}` ({}) shadows `{}` ({})",
+                    mode.label(),
+                    format_sequence(short),
+                    action_name(*short_action),
+                    format_sequence(long),
+                    action_name(*long_action)
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The config name of `action`, as `rename_all = "snake_case"` spells it.
+fn action_name(action: Action) -> &'static str {
+    match action {
+        Action::Down => "down",
+        Action::Up => "up",
+        Action::Top => "top",
+        Action::Bottom => "bottom",
+        Action::PageDown => "page_down",
+        Action::PageUp => "page_up",
+        Action::Details => "details",
+        Action::Resume => "resume",
+        Action::Refresh => "refresh",
+        Action::Pause => "pause",
+        Action::Reannounce => "reannounce",
+        Action::AddMagnet => "add_magnet",
+        Action::Preferences => "preferences",
+        Action::Profiles => "profiles",
+        Action::CycleDaemon => "cycle_daemon",
+        Action::Export => "export",
+        Action::Hooks => "hooks",
+        Action::Rules => "rules",
+        Action::Columns => "columns",
+        Action::Views => "views",
+        Action::Remove => "remove",
+        Action::Trash => "trash",
+        Action::Filter => "filter",
+        Action::ClearFilter => "clear_filter",
+        Action::Command => "command",
+        Action::Help => "help",
+        Action::Quit => "quit",
+        Action::Accept => "accept",
+        Action::Cancel => "cancel",
+        Action::Select => "select",
+        Action::Close => "close",
+        Action::Toggle => "toggle",
+        Action::MoveDown => "move_down",
+        Action::MoveUp => "move_up",
+        Action::SaveView => "save_view",
+        Action::Previous => "previous",
+        Action::Next => "next",
+        Action::Save => "save",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(text: &str) -> KeyChord {
+        text.parse().expect(text)
+    }
+
+    fn keymap(toml: &str) -> Result<Keymap> {
+        Keymap::from_config(&toml::from_str(toml).expect("valid [keys] toml"))
+    }
+
+    fn keys(keymap: &Keymap, mode: KeyMode, action: Action) -> Vec<String> {
+        keymap
+            .entries(mode)
+            .into_iter()
+            .find(|(bound, _)| *bound == action)
+            .map(|(_, keys)| keys)
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn parses_key_specs() {
+        assert_eq!(
+            chord("ctrl+d"),
+            KeyChord::new(KeyCode::Char('d'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(chord("Control+Alt+x"), chord("alt+ctrl+x"));
+        assert_eq!(
+            chord("ctrl++"),
+            KeyChord::new(KeyCode::Char('+'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            chord("+"),
+            KeyChord::new(KeyCode::Char('+'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            chord("F24"),
+            KeyChord::new(KeyCode::F(24), KeyModifiers::NONE)
+        );
+        assert_eq!(chord("space"), chord(" "));
+        assert_eq!(chord("PgDn"), chord("pagedown"));
+        assert!("f25".parse::<KeyChord>().is_err());
+        assert!("f0".parse::<KeyChord>().is_err());
+        assert!("hyper+x".parse::<KeyChord>().is_err());
+        assert!("enterr".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn shift_folds_into_printable_keys() {
+        assert_eq!(chord("shift+g"), chord("G"));
+        assert_eq!(
+            KeyChord::from(KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT)),
+            chord("G")
+        );
+        assert_eq!(
+            chord("shift+up"),
+            KeyChord::new(KeyCode::Up, KeyModifiers::SHIFT)
+        );
+        assert_eq!(chord("alt+shift+up").to_string(), "Alt+Shift+Up");
+    }
+
+    #[test]
+    fn multi_key_bindings_wait_for_the_rest() {
+        let keymap = Keymap::default();
+        let d = chord("d");
+        assert!(matches!(
+            keymap.lookup(KeyMode::Normal, &[d]),
+            KeyLookup::Prefix(longer) if longer == [(vec![d, d], Action::Remove)]
+        ));
+        assert_eq!(
+            keymap.lookup(KeyMode::Normal, &[d, d]),
+            KeyLookup::Action(Action::Remove)
+        );
+        assert_eq!(
+            keymap.lookup(KeyMode::Normal, &[chord("D"), chord("D")]),
+            KeyLookup::Action(Action::Trash)
+        );
+        assert_eq!(
+            keymap.lookup(KeyMode::Normal, &[d, chord("x")]),
+            KeyLookup::None
+        );
+        assert_eq!(format_sequence(&[d, d]), "dd");
+        assert_eq!(format_sequence(&[chord("g"), chord("ctrl+d")]), "g Ctrl+d");
+    }
+
+    #[test]
+    fn overrides_replace_defaults_and_take_keys_over() {
+        let keymap = keymap(
+            r#"
+            [normal]
+            quit = ["Q", "ctrl+q"]
+            up = "j"
+            remove = "x x"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            keys(&keymap, KeyMode::Normal, Action::Quit),
+            ["Q", "Ctrl+q"]
+        );
+        assert_eq!(keys(&keymap, KeyMode::Normal, Action::Up), ["j"]);
+        // `j` moved to `up`, leaving `down` unbound.
+        assert!(keys(&keymap, KeyMode::Normal, Action::Down).is_empty());
+        assert_eq!(
+            keymap.lookup(KeyMode::Normal, &[chord("q")]),
+            KeyLookup::None
+        );
+        assert_eq!(
+            keymap.lookup(KeyMode::Normal, &[chord("d"), chord("d")]),
+            KeyLookup::None
+        );
+        // Other modes keep their defaults.
+        assert_eq!(
+            keys(&keymap, KeyMode::Profiles, Action::Down),
+            ["j", "Down"]
+        );
+    }
+
+    #[test]
+    fn rejects_foreign_actions_bad_keys_and_shadowing() {
+        let err = keymap("[help]\nquit = \"x\"").unwrap_err().to_string();
+        assert_eq!(err, "[keys.help]: `quit` is not an action in this mode");
+        let err = keymap("[normal]\npage_down = \"ctrl+shft+d\"").unwrap_err();
+        assert_eq!(err.to_string(), "[keys.normal] page_down");
+        let err = keymap("[normal]\npause = \"d\"").unwrap_err().to_string();
+        assert_eq!(err, "[keys.normal]: `d` (pause) shadows `dd` (remove)");
+        assert!(keymap("[normal]\npause = \"\"").is_err());
+        assert!(toml::from_str::<FileKeys>("[normal]\nfly = \"f\"").is_err());
+    }
+
+    #[test]
+    fn action_names_match_the_config_spelling() {
+        for mode in KeyMode::ALL {
+            for &(action, _) in mode.defaults() {
+                let parsed: Action = serde_json::from_value(serde_json::json!(action_name(action)))
+                    .unwrap_or_else(|err| panic!("{action:?}: {err}"));
+                assert_eq!(parsed, action);
+            }
+        }
+    }
+
+    #[test]
+    fn hints_follow_remapped_keys() {
+        let hooks = keymap("[hooks]\ndown = \"n\"\nclose = [\"x\", \"esc\"]").unwrap();
+        assert_eq!(
+            hooks.hint(KeyMode::Hooks, &[Action::Down, Action::Up], "scroll"),
+            Some("n/k scroll".to_string())
+        );
+        assert_eq!(
+            hooks.hint(KeyMode::Hooks, &[Action::Close], "close"),
+            Some("x close".to_string())
+        );
+        let prefs = keymap("[preferences]\nsave = \"r\"").unwrap();
+        assert_eq!(
+            prefs.hint(KeyMode::Preferences, &[Action::Refresh], "reload"),
+            Some("R reload".to_string())
+        );
+        // `k` and `up` moved to `close`, leaving `up` without a key.
+        let unbound = keymap("[hooks]\nclose = [\"k\", \"up\"]").unwrap();
+        assert_eq!(
+            unbound.hint(KeyMode::Hooks, &[Action::Down, Action::Up], "scroll"),
+            None
+        );
+    }
+}
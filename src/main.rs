@@ -1,8 +1,28 @@
+mod capabilities;
+mod cmdline;
+mod columns;
+mod commands;
 mod config;
+mod connection;
+mod export;
+mod fuzzy;
+mod hooks;
+mod keymap;
+mod metrics;
 mod model;
+mod netrc;
+mod notify;
 mod preferences;
 mod rpc;
+mod rules;
+mod secret;
+mod theme;
+mod traffic;
+mod transitions;
 mod tui;
+mod tunnel;
+mod views;
+mod watch;
 
 use std::process;
 
@@ -14,16 +34,20 @@ use log::LevelFilter;
 
 fn main() {
     if let Err(err) = try_main() {
-        eprintln!("error: {err}");
-        process::exit(1);
+        eprintln!("error: {err:#}");
+        process::exit(commands::exit_code(&err));
     }
 }
 
 fn try_main() -> Result<()> {
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    let command = cli.command.take();
     let config = build_config(&cli)?;
     init_logging(config.log_level);
-    tui::run(config)
+    match command {
+        Some(command) => commands::run(command, &config, cli.json),
+        None => tui::run(config),
+    }
 }
 
 fn init_logging(level: LevelFilter) {
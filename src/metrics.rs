@@ -0,0 +1,599 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    io::{BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+
+use crate::{
+    commands,
+    config::AppConfig,
+    model::{Snapshot, TorrentSummary, TORRENT_STATUSES},
+    rpc::TransmissionClient,
+    tunnel::SshTunnel,
+};
+
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Controls how many per-torrent series the exporter emits.
+#[derive(Args, Debug, Clone)]
+pub struct MetricsOptions {
+    /// Only export session totals and status counts, no per-torrent series
+    #[arg(long)]
+    pub no_torrent_metrics: bool,
+    /// Labels attached to per-torrent series; `id` is always included so
+    /// every series stays unique
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "id,name",
+        value_name = "LABELS"
+    )]
+    pub torrent_labels: Vec<TorrentLabel>,
+    /// Export only the N torrents with the highest combined rate
+    #[arg(long, value_name = "N")]
+    pub max_torrents: Option<usize>,
+    /// Skip idle torrents (no transfer and no error)
+    #[arg(long)]
+    pub active_only: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TorrentLabel {
+    Id,
+    Name,
+    Status,
+    DownloadDir,
+    Labels,
+}
+
+impl MetricsOptions {
+    /// `--torrent-labels` without repeats, with `id` first when not listed.
+    fn series_labels(&self) -> Vec<TorrentLabel> {
+        let mut labels = Vec::new();
+        if !self.torrent_labels.contains(&TorrentLabel::Id) {
+            labels.push(TorrentLabel::Id);
+        }
+        for &label in &self.torrent_labels {
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+        labels
+    }
+}
+
+impl TorrentLabel {
+    fn name(self) -> &'static str {
+        match self {
+            TorrentLabel::Id => "id",
+            TorrentLabel::Name => "name",
+            TorrentLabel::Status => "status",
+            TorrentLabel::DownloadDir => "download_dir",
+            TorrentLabel::Labels => "labels",
+        }
+    }
+
+    fn value(self, torrent: &TorrentSummary) -> String {
+        match self {
+            TorrentLabel::Id => torrent.torrent_id.to_string(),
+            TorrentLabel::Name => torrent.name.clone(),
+            TorrentLabel::Status => torrent.status.clone(),
+            TorrentLabel::DownloadDir => torrent.download_dir.clone(),
+            TorrentLabel::Labels => torrent.labels.join(","),
+        }
+    }
+}
+
+/// Latest poll result for one profile.
+struct Sample {
+    profile: String,
+    snapshot: Option<Snapshot>,
+    up: bool,
+    last_success: Option<SystemTime>,
+}
+
+/// Polls the startup profile (or every profile with `--aggregate`) and serves
+/// the latest results on `listen` until the process is killed.
+pub fn serve(config: &AppConfig, listen: SocketAddr, options: MetricsOptions) -> Result<()> {
+    let profiles: Vec<_> = if config.aggregate {
+        config.profiles.iter().collect()
+    } else {
+        vec![&config.profiles[config.active_profile]]
+    };
+    let mut clients = Vec::new();
+    for profile in &profiles {
+        let (client, tunnel) = commands::connect(&profile.rpc)
+            .with_context(|| format!("profile '{}'", profile.name))?;
+        clients.push((client, tunnel));
+    }
+    let samples = Arc::new(Mutex::new(
+        profiles
+            .iter()
+            .map(|profile| Sample {
+                profile: profile.name.clone(),
+                snapshot: None,
+                up: false,
+                last_success: None,
+            })
+            .collect::<Vec<_>>(),
+    ));
+    let listener =
+        TcpListener::bind(listen).with_context(|| format!("cannot listen on {listen}"))?;
+    log::info!("serving metrics on http://{listen}/metrics");
+
+    let poll_samples = Arc::clone(&samples);
+    let poll_interval = config.poll_interval.max(Duration::from_secs(1));
+    thread::spawn(move || poll_loop(clients, poll_samples, poll_interval));
+
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        if let Err(err) = respond(stream, &samples, &options) {
+            log::debug!("metrics request failed: {err}");
+        }
+    }
+    Ok(())
+}
+
+fn poll_loop(
+    clients: Vec<(TransmissionClient, Option<SshTunnel>)>,
+    samples: Arc<Mutex<Vec<Sample>>>,
+    interval: Duration,
+) {
+    loop {
+        for (idx, (client, _tunnel)) in clients.iter().enumerate() {
+            let result = match client.capabilities() {
+                Some(_) => client.fetch_snapshot(),
+                None => client.handshake().and_then(|_| client.fetch_snapshot()),
+            };
+            let Ok(mut guard) = samples.lock() else {
+                return;
+            };
+            let sample = &mut guard[idx];
+            match result {
+                Ok(snapshot) => {
+                    sample.snapshot = Some(snapshot);
+                    sample.up = true;
+                    sample.last_success = Some(SystemTime::now());
+                }
+                Err(err) => {
+                    log::warn!("poll of profile '{}' failed: {err}", sample.profile);
+                    sample.up = false;
+                }
+            }
+        }
+        thread::sleep(interval);
+    }
+}
+
+fn respond(
+    stream: TcpStream,
+    samples: &Mutex<Vec<Sample>>,
+    options: &MetricsOptions,
+) -> Result<()> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Only the Accept header matters; the rest is read and dropped.
+    let mut openmetrics = false;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("accept") {
+                openmetrics = value.contains("application/openmetrics-text");
+            }
+        }
+    }
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/metrics") => {
+            let body = match samples.lock() {
+                Ok(samples) => render(&samples, options, openmetrics),
+                Err(_) => String::new(),
+            };
+            let content_type = if openmetrics {
+                "application/openmetrics-text; version=1.0.0; charset=utf-8"
+            } else {
+                "text/plain; version=0.0.4; charset=utf-8"
+            };
+            ("200 OK", content_type, body)
+        }
+        ("GET", "/") => (
+            "200 OK",
+            "text/plain; charset=utf-8",
+            "transmission-tui metrics exporter; scrape /metrics\n".to_string(),
+        ),
+        ("GET", _) => (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            "not found\n".to_string(),
+        ),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain; charset=utf-8",
+            "only GET is supported\n".to_string(),
+        ),
+    };
+    let mut stream = &stream;
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Collects the samples of one metric family before it is written.
+struct Family {
+    name: &'static str,
+    help: &'static str,
+    samples: Vec<(String, String)>,
+}
+
+impl Family {
+    fn new(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            samples: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, labels: &[(&str, String)], value: impl ToString) {
+        self.samples
+            .push((format_labels(labels), value.to_string()));
+    }
+
+    fn write(&self, out: &mut String) {
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} gauge", self.name);
+        for (labels, value) in &self.samples {
+            let _ = writeln!(out, "{}{labels} {value}", self.name);
+        }
+    }
+}
+
+/// The `# EOF` terminator is only valid in the OpenMetrics format.
+fn render(samples: &[Sample], options: &MetricsOptions, openmetrics: bool) -> String {
+    let series_labels = options.series_labels();
+    let mut up = Family::new(
+        "transmission_up",
+        "Whether the last poll of the daemon succeeded",
+    );
+    let mut last_success = Family::new(
+        "transmission_last_success_timestamp_seconds",
+        "Unix time of the last successful poll",
+    );
+    let mut info = Family::new("transmission_info", "Daemon version");
+    let mut session_down = Family::new(
+        "transmission_session_download_bytes_per_second",
+        "Session download rate",
+    );
+    let mut session_up = Family::new(
+        "transmission_session_upload_bytes_per_second",
+        "Session upload rate",
+    );
+    let mut by_status = Family::new("transmission_torrents", "Torrents by status");
+    let mut omitted = Family::new(
+        "transmission_torrents_omitted",
+        "Torrents left out of per-torrent series by --max-torrents or --active-only",
+    );
+    let mut torrent_down = Family::new(
+        "transmission_torrent_download_bytes_per_second",
+        "Torrent download rate",
+    );
+    let mut torrent_up = Family::new(
+        "transmission_torrent_upload_bytes_per_second",
+        "Torrent upload rate",
+    );
+    let mut ratio = Family::new("transmission_torrent_upload_ratio", "Torrent upload ratio");
+    let mut done = Family::new(
+        "transmission_torrent_percent_done",
+        "Fraction of the wanted data downloaded (0-1)",
+    );
+    let mut errored = Family::new(
+        "transmission_torrent_error",
+        "1 when the daemon reports an error for the torrent",
+    );
+
+    for sample in samples {
+        let profile = [("profile", sample.profile.clone())];
+        up.add(&profile, u8::from(sample.up));
+        if let Some(at) = sample.last_success {
+            let secs = at.duration_since(UNIX_EPOCH).unwrap_or_default();
+            last_success.add(&profile, format!("{:.3}", secs.as_secs_f64()));
+        }
+        let Some(snapshot) = &sample.snapshot else {
+            continue;
+        };
+        info.add(
+            &[
+                ("profile", sample.profile.clone()),
+                ("version", snapshot.version.clone()),
+            ],
+            1,
+        );
+        session_down.add(&profile, snapshot.download_speed);
+        session_up.add(&profile, snapshot.upload_speed);
+
+        // Zero counts are kept so series do not appear and vanish.
+        let mut counts: BTreeMap<&str, usize> = TORRENT_STATUSES.iter().map(|s| (*s, 0)).collect();
+        for torrent in &snapshot.torrents {
+            *counts.entry(torrent.status.as_str()).or_default() += 1;
+        }
+        for (status, count) in counts {
+            by_status.add(
+                &[
+                    ("profile", sample.profile.clone()),
+                    ("status", status.to_string()),
+                ],
+                count,
+            );
+        }
+
+        if options.no_torrent_metrics {
+            continue;
+        }
+        let exported = limit_torrents(&snapshot.torrents, options);
+        omitted.add(&profile, snapshot.torrents.len() - exported.len());
+        for torrent in exported {
+            let mut labels = vec![("profile", sample.profile.clone())];
+            labels.extend(
+                series_labels
+                    .iter()
+                    .map(|label| (label.name(), label.value(torrent))),
+            );
+            torrent_down.add(&labels, torrent.rate_download);
+            torrent_up.add(&labels, torrent.rate_upload);
+            ratio.add(&labels, torrent.upload_ratio);
+            done.add(&labels, torrent.percent_done);
+            errored.add(&labels, u8::from(torrent.error.is_some()));
+        }
+    }
+
+    let mut out = String::new();
+    for family in [
+        &up,
+        &last_success,
+        &info,
+        &session_down,
+        &session_up,
+        &by_status,
+    ] {
+        family.write(&mut out);
+    }
+    if !options.no_torrent_metrics {
+        for family in [
+            &omitted,
+            &torrent_down,
+            &torrent_up,
+            &ratio,
+            &done,
+            &errored,
+        ] {
+            family.write(&mut out);
+        }
+    }
+    if openmetrics {
+        out.push_str("# EOF\n");
+    }
+    out
+}
+
+/// Applies `--active-only` and `--max-torrents`, keeping daemon order.
+fn limit_torrents<'a>(
+    torrents: &'a [TorrentSummary],
+    options: &MetricsOptions,
+) -> Vec<&'a TorrentSummary> {
+    let mut kept: Vec<&TorrentSummary> = torrents
+        .iter()
+        .filter(|t| {
+            !options.active_only || t.rate_download > 0 || t.rate_upload > 0 || t.error.is_some()
+        })
+        .collect();
+    if let Some(max) = options.max_torrents {
+        if kept.len() > max {
+            kept.sort_by_key(|t| std::cmp::Reverse(t.rate_download + t.rate_upload));
+            kept.truncate(max);
+            kept.sort_by_key(|t| t.torrent_id);
+        }
+    }
+    kept
+}
+
+fn format_labels(labels: &[(&str, String)]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let pairs: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| format!("{name}=\"{}\"", escape_label(value)))
+        .collect();
+    format!("{{{}}}", pairs.join(","))
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transitions::tests::{snapshot, torrent};
+
+    fn options(max_torrents: Option<usize>, active_only: bool) -> MetricsOptions {
+        MetricsOptions {
+            no_torrent_metrics: false,
+            torrent_labels: vec![TorrentLabel::Name],
+            max_torrents,
+            active_only,
+        }
+    }
+
+    fn busy(id: i64, name: &str, rate: i64) -> TorrentSummary {
+        TorrentSummary {
+            rate_download: rate,
+            ..torrent(id, name)
+        }
+    }
+
+    #[test]
+    fn renders_a_small_snapshot() {
+        let samples = [
+            Sample {
+                profile: "home".to_string(),
+                snapshot: Some(snapshot(vec![busy(7, "Debian \"netinst\"", 1024)])),
+                up: true,
+                last_success: Some(UNIX_EPOCH + Duration::from_millis(1_700_000_000_250)),
+            },
+            Sample {
+                profile: "seedbox".to_string(),
+                snapshot: None,
+                up: false,
+                last_success: None,
+            },
+        ];
+        let expected = r#"# HELP transmission_up Whether the last poll of the daemon succeeded
+# TYPE transmission_up gauge
+transmission_up{profile="home"} 1
+transmission_up{profile="seedbox"} 0
+# HELP transmission_last_success_timestamp_seconds Unix time of the last successful poll
+# TYPE transmission_last_success_timestamp_seconds gauge
+transmission_last_success_timestamp_seconds{profile="home"} 1700000000.250
+# HELP transmission_info Daemon version
+# TYPE transmission_info gauge
+transmission_info{profile="home",version="4.0.0"} 1
+# HELP transmission_session_download_bytes_per_second Session download rate
+# TYPE transmission_session_download_bytes_per_second gauge
+transmission_session_download_bytes_per_second{profile="home"} 0
+# HELP transmission_session_upload_bytes_per_second Session upload rate
+# TYPE transmission_session_upload_bytes_per_second gauge
+transmission_session_upload_bytes_per_second{profile="home"} 0
+# HELP transmission_torrents Torrents by status
+# TYPE transmission_torrents gauge
+transmission_torrents{profile="home",status="check-wait"} 0
+transmission_torrents{profile="home",status="checking"} 0
+transmission_torrents{profile="home",status="download-wait"} 0
+transmission_torrents{profile="home",status="downloading"} 1
+transmission_torrents{profile="home",status="seed-wait"} 0
+transmission_torrents{profile="home",status="seeding"} 0
+transmission_torrents{profile="home",status="stopped"} 0
+# HELP transmission_torrents_omitted Torrents left out of per-torrent series by --max-torrents or --active-only
+# TYPE transmission_torrents_omitted gauge
+transmission_torrents_omitted{profile="home"} 0
+# HELP transmission_torrent_download_bytes_per_second Torrent download rate
+# TYPE transmission_torrent_download_bytes_per_second gauge
+transmission_torrent_download_bytes_per_second{profile="home",id="7",name="Debian \"netinst\""} 1024
+# HELP transmission_torrent_upload_bytes_per_second Torrent upload rate
+# TYPE transmission_torrent_upload_bytes_per_second gauge
+transmission_torrent_upload_bytes_per_second{profile="home",id="7",name="Debian \"netinst\""} 0
+# HELP transmission_torrent_upload_ratio Torrent upload ratio
+# TYPE transmission_torrent_upload_ratio gauge
+transmission_torrent_upload_ratio{profile="home",id="7",name="Debian \"netinst\""} 0
+# HELP transmission_torrent_percent_done Fraction of the wanted data downloaded (0-1)
+# TYPE transmission_torrent_percent_done gauge
+transmission_torrent_percent_done{profile="home",id="7",name="Debian \"netinst\""} 0.5
+# HELP transmission_torrent_error 1 when the daemon reports an error for the torrent
+# TYPE transmission_torrent_error gauge
+transmission_torrent_error{profile="home",id="7",name="Debian \"netinst\""} 0
+"#;
+        assert_eq!(render(&samples, &options(None, false), false), expected);
+        assert_eq!(
+            render(&samples, &options(None, false), true),
+            format!("{expected}# EOF\n")
+        );
+    }
+
+    #[test]
+    fn session_only_output_skips_torrent_families() {
+        let samples = [Sample {
+            profile: "home".to_string(),
+            snapshot: Some(snapshot(vec![torrent(1, "a")])),
+            up: true,
+            last_success: None,
+        }];
+        let options = MetricsOptions {
+            no_torrent_metrics: true,
+            ..options(None, false)
+        };
+        let text = render(&samples, &options, true);
+        assert!(!text.contains("transmission_torrent_"));
+        assert!(!text.contains("transmission_torrents_omitted"));
+        assert!(text.ends_with("status=\"stopped\"} 0\n# EOF\n"), "{text}");
+    }
+
+    #[test]
+    fn escapes_quotes_backslashes_and_newlines_in_labels() {
+        assert_eq!(
+            escape_label("say \"hi\"\\n\nbye"),
+            "say \\\"hi\\\"\\\\n\\nbye"
+        );
+        assert_eq!(
+            format_labels(&[("name", "a\\b".to_string()), ("dir", "/x".to_string())]),
+            "{name=\"a\\\\b\",dir=\"/x\"}"
+        );
+        assert_eq!(format_labels(&[]), "");
+    }
+
+    #[test]
+    fn limits_torrents_to_the_busiest_in_id_order() {
+        let torrents = vec![
+            busy(1, "slow", 10),
+            busy(2, "idle", 0),
+            busy(3, "fast", 500),
+            busy(4, "medium", 100),
+        ];
+        let ids = |kept: Vec<&TorrentSummary>| -> Vec<i64> {
+            kept.iter().map(|t| t.torrent_id).collect()
+        };
+        assert_eq!(
+            ids(limit_torrents(&torrents, &options(None, false))),
+            [1, 2, 3, 4]
+        );
+        assert_eq!(
+            ids(limit_torrents(&torrents, &options(Some(2), false))),
+            [3, 4]
+        );
+        assert_eq!(
+            ids(limit_torrents(&torrents, &options(None, true))),
+            [1, 3, 4]
+        );
+        assert_eq!(
+            ids(limit_torrents(&torrents, &options(Some(5), true))),
+            [1, 3, 4]
+        );
+
+        let samples = [Sample {
+            profile: "home".to_string(),
+            snapshot: Some(snapshot(torrents)),
+            up: true,
+            last_success: None,
+        }];
+        let text = render(&samples, &options(Some(2), false), false);
+        assert!(text.contains("transmission_torrents_omitted{profile=\"home\"} 2\n"));
+        assert_eq!(
+            text.matches("transmission_torrent_error{").count(),
+            2,
+            "{text}"
+        );
+    }
+}
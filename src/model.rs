@@ -1,6 +1,8 @@
 use std::time::Duration;
 
-#[derive(Debug, Clone)]
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
 pub struct Snapshot {
     pub version: String,
     pub download_speed: i64,
@@ -11,8 +13,20 @@ pub struct Snapshot {
     pub torrents: Vec<TorrentSummary>,
 }
 
-#[derive(Debug, Clone)]
+/// Every status a torrent can report, in Transmission's numeric order.
+pub const TORRENT_STATUSES: &[&str] = &[
+    "stopped",
+    "check-wait",
+    "checking",
+    "download-wait",
+    "downloading",
+    "seed-wait",
+    "seeding",
+];
+
+#[derive(Debug, Clone, Serialize)]
 pub struct TorrentSummary {
+    #[serde(rename = "id")]
     pub torrent_id: i64,
     pub name: String,
     pub status: String,
@@ -29,9 +43,51 @@ pub struct TorrentSummary {
     pub peers_receiving: i64,
     pub error: Option<String>,
     pub peers: Vec<PeerSummary>,
+    pub labels: Vec<String>,
+    pub file_count: Option<i64>,
+    /// Bandwidth group the torrent belongs to, if any (RPC 17+).
+    pub bandwidth_group: Option<String>,
+    pub seconds_seeding: i64,
+    /// Announce URLs.
+    pub trackers: Vec<String>,
+    /// Name of the profile whose daemon reported this torrent.
+    pub daemon: String,
+    pub downloaded_ever: i64,
+    pub uploaded_ever: i64,
+    /// Unix time the torrent was added.
+    pub added_date: i64,
+    /// Unix time the download completed; 0 while incomplete.
+    pub done_date: i64,
+    pub queue_position: i64,
+    /// Info hash, hex-encoded.
+    pub hash: String,
+}
+
+/// Identifies a torrent across daemons; ids are only unique per daemon.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TorrentKey {
+    pub daemon: String,
+    pub id: i64,
 }
 
-#[derive(Debug, Clone)]
+impl TorrentSummary {
+    pub fn key(&self) -> TorrentKey {
+        TorrentKey {
+            daemon: self.daemon.clone(),
+            id: self.torrent_id,
+        }
+    }
+
+    /// Connected peers that already have the whole torrent.
+    pub fn seeds(&self) -> usize {
+        self.peers
+            .iter()
+            .filter(|peer| peer.progress >= 1.0)
+            .count()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct PeerSummary {
     pub address: String,
     pub client: String,
@@ -41,6 +97,28 @@ pub struct PeerSummary {
     pub is_encrypted: bool,
 }
 
+/// Combines per-daemon snapshots into one list with summed session totals.
+pub fn merge_snapshots<'a>(parts: impl IntoIterator<Item = &'a Snapshot>) -> Option<Snapshot> {
+    let mut merged: Option<Snapshot> = None;
+    for part in parts {
+        match &mut merged {
+            None => merged = Some(part.clone()),
+            Some(total) => {
+                if total.version != part.version {
+                    total.version = "mixed".to_string();
+                }
+                total.download_speed += part.download_speed;
+                total.upload_speed += part.upload_speed;
+                total.active_torrents += part.active_torrents;
+                total.paused_torrents += part.paused_torrents;
+                total.total_torrents += part.total_torrents;
+                total.torrents.extend(part.torrents.iter().cloned());
+            }
+        }
+    }
+    merged
+}
+
 pub fn format_speed(value: i64) -> String {
     const UNITS: [&str; 5] = ["B/s", "KiB/s", "MiB/s", "GiB/s", "TiB/s"];
     let mut magnitude = value.max(0) as f64;
@@ -0,0 +1,267 @@
+use std::{fs, io, path::Path};
+
+use anyhow::{bail, Context, Result};
+
+use crate::secret::Secret;
+
+/// Credentials from one `machine` (or `default`) block of a netrc file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetrcEntry {
+    pub login: Option<String>,
+    pub password: Option<Secret>,
+}
+
+/// Parsed netrc file. `machine` blocks are matched by exact host name; the
+/// `default` block, if any, answers for every other host.
+#[derive(Debug, Default)]
+pub struct Netrc {
+    machines: Vec<(String, NetrcEntry)>,
+    default: Option<NetrcEntry>,
+}
+
+impl Netrc {
+    /// Reads `path`, returning `None` when the file does not exist.
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Self::parse(&contents)
+                .with_context(|| format!("invalid netrc file {}", path.display()))
+                .map(Some),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => {
+                Err(err).with_context(|| format!("failed to read netrc file {}", path.display()))
+            }
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        let mut netrc = Netrc::default();
+        let mut tokens = Tokenizer::new(contents);
+        // Which block subsequent login/password tokens belong to.
+        let mut current: Option<(Option<String>, NetrcEntry)> = None;
+        while let Some(token) = tokens.next_token()? {
+            match token.as_str() {
+                "machine" => {
+                    netrc.finish(current.take());
+                    let host = tokens.value_for("machine")?;
+                    current = Some((Some(host), NetrcEntry::empty()));
+                }
+                "default" => {
+                    netrc.finish(current.take());
+                    current = Some((None, NetrcEntry::empty()));
+                }
+                "login" | "password" | "account" => {
+                    let value = tokens.value_for(&token)?;
+                    let Some((_, entry)) = current.as_mut() else {
+                        bail!("'{token}' appears before any machine or default entry");
+                    };
+                    match token.as_str() {
+                        "login" => entry.login = Some(value),
+                        "password" => entry.password = Some(Secret::new(value)),
+                        _ => {}
+                    }
+                }
+                "macdef" => {
+                    tokens.value_for("macdef")?;
+                    tokens.skip_macro();
+                }
+                other => bail!("unexpected token '{other}'"),
+            }
+        }
+        netrc.finish(current);
+        Ok(netrc)
+    }
+
+    pub fn find(&self, host: &str) -> Option<&NetrcEntry> {
+        self.machines
+            .iter()
+            .find(|(machine, _)| machine.eq_ignore_ascii_case(host))
+            .map(|(_, entry)| entry)
+            .or(self.default.as_ref())
+    }
+
+    fn finish(&mut self, block: Option<(Option<String>, NetrcEntry)>) {
+        match block {
+            Some((Some(host), entry)) => self.machines.push((host, entry)),
+            // Only the first default counts, as in ftp(1) and curl.
+            Some((None, entry)) if self.default.is_none() => self.default = Some(entry),
+            _ => {}
+        }
+    }
+}
+
+impl NetrcEntry {
+    fn empty() -> Self {
+        Self {
+            login: None,
+            password: None,
+        }
+    }
+}
+
+/// Splits netrc contents into whitespace-separated tokens. Double-quoted
+/// tokens may contain spaces and backslash escapes, and `#` starts a comment
+/// that runs to the end of the line.
+struct Tokenizer<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(contents: &'a str) -> Self {
+        Self {
+            chars: contents.chars().peekable(),
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<String>> {
+        loop {
+            match self.chars.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.chars.next();
+                }
+                Some('#') => {
+                    for c in self.chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                Some('"') => {
+                    self.chars.next();
+                    return self.quoted().map(Some);
+                }
+                Some(_) => {
+                    let mut token = String::new();
+                    while let Some(&c) = self.chars.peek() {
+                        if c.is_whitespace() {
+                            break;
+                        }
+                        token.push(c);
+                        self.chars.next();
+                    }
+                    return Ok(Some(token));
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+
+    fn quoted(&mut self) -> Result<String> {
+        let mut token = String::new();
+        while let Some(c) = self.chars.next() {
+            match c {
+                '"' => return Ok(token),
+                '\\' => match self.chars.next() {
+                    Some('n') => token.push('\n'),
+                    Some('r') => token.push('\r'),
+                    Some('t') => token.push('\t'),
+                    Some(other) => token.push(other),
+                    None => break,
+                },
+                other => token.push(other),
+            }
+        }
+        bail!("unterminated quoted token")
+    }
+
+    fn value_for(&mut self, keyword: &str) -> Result<String> {
+        self.next_token()?
+            .with_context(|| format!("missing value after '{keyword}'"))
+    }
+
+    /// Skips a macro body, which runs until the next empty line.
+    fn skip_macro(&mut self) {
+        let mut previous = '\0';
+        for c in self.chars.by_ref() {
+            if c == '\n' && previous == '\n' {
+                break;
+            }
+            if c != '\r' {
+                previous = c;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(netrc: &Netrc, host: &str) -> (Option<String>, Option<String>) {
+        let found = netrc.find(host).expect("entry");
+        (
+            found.login.clone(),
+            found.password.as_ref().map(|p| p.expose().to_string()),
+        )
+    }
+
+    #[test]
+    fn matches_machine_entries() {
+        let netrc = Netrc::parse(
+            "machine nas.lan login media password s3cret\n\
+             machine seedbox.example.net\n  login me\n  password other\n",
+        )
+        .unwrap();
+        assert_eq!(
+            entry(&netrc, "nas.lan"),
+            (Some("media".into()), Some("s3cret".into()))
+        );
+        assert_eq!(
+            entry(&netrc, "SEEDBOX.example.net"),
+            (Some("me".into()), Some("other".into()))
+        );
+        assert!(netrc.find("unknown.lan").is_none());
+    }
+
+    #[test]
+    fn default_entry_answers_unknown_hosts() {
+        let netrc = Netrc::parse(
+            "default login anon password guest\nmachine nas.lan login media password s3cret\n",
+        )
+        .unwrap();
+        assert_eq!(
+            entry(&netrc, "nas.lan"),
+            (Some("media".into()), Some("s3cret".into()))
+        );
+        assert_eq!(
+            entry(&netrc, "elsewhere"),
+            (Some("anon".into()), Some("guest".into()))
+        );
+    }
+
+    #[test]
+    fn quoted_tokens_keep_spaces_and_escapes() {
+        let netrc =
+            Netrc::parse(r#"machine nas.lan login "media user" password "pa ss\"word\\""#).unwrap();
+        assert_eq!(
+            entry(&netrc, "nas.lan"),
+            (Some("media user".into()), Some(r#"pa ss"word\"#.into()))
+        );
+    }
+
+    #[test]
+    fn skips_comments_accounts_and_macros() {
+        let netrc = Netrc::parse(
+            "# shared credentials\n\
+             machine ftp.lan login x password y account z\n\
+             macdef init\ncd /pub\nbinary\n\n\
+             machine nas.lan login media # trailing comment\n password s3cret\n",
+        )
+        .unwrap();
+        assert_eq!(
+            entry(&netrc, "nas.lan"),
+            (Some("media".into()), Some("s3cret".into()))
+        );
+        assert_eq!(
+            entry(&netrc, "ftp.lan"),
+            (Some("x".into()), Some("y".into()))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        assert!(Netrc::parse("login orphan").is_err());
+        assert!(Netrc::parse("machine nas.lan login").is_err());
+        assert!(Netrc::parse(r#"machine nas.lan password "open"#).is_err());
+        assert!(Netrc::parse("machine nas.lan bogus value").is_err());
+    }
+}
@@ -0,0 +1,230 @@
+use std::{
+    io::{self, Write},
+    process::{Command, Stdio},
+    sync::Arc,
+    thread,
+};
+
+use anyhow::{bail, Context, Result};
+use crossterm::{queue, terminal::SetTitle};
+
+use crate::transitions::{Transition, TransitionKind};
+
+/// The `[notifications]` table.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NotifyConfig {
+    /// Send notifications to the freedesktop notification service.
+    pub desktop: bool,
+    /// Shell command run per notification instead of (or as well as) D-Bus.
+    pub command: Option<String>,
+    /// Ring the terminal bell.
+    pub bell: bool,
+    /// Put the latest notification in the terminal (or tmux pane) title.
+    pub title: bool,
+}
+
+impl NotifyConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.desktop || self.command.is_some() || self.bell || self.title
+    }
+}
+
+/// What the user is told about one transition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub summary: String,
+    pub body: String,
+    pub urgent: bool,
+}
+
+impl Notification {
+    /// Finished downloads and new errors are worth interrupting for; other
+    /// transitions are left to hooks.
+    pub fn for_transition(profile: &str, transition: &Transition) -> Option<Self> {
+        let torrent = &transition.torrent;
+        let (summary, body, urgent) = match transition.kind {
+            TransitionKind::Finished => (
+                "Download finished".to_string(),
+                format!("{} [{profile}]", torrent.name),
+                false,
+            ),
+            TransitionKind::Errored => (
+                "Torrent error".to_string(),
+                format!(
+                    "{} [{profile}]: {}",
+                    torrent.name,
+                    torrent.error.as_deref().unwrap_or("unknown error")
+                ),
+                true,
+            ),
+            _ => return None,
+        };
+        Some(Self {
+            summary,
+            body,
+            urgent,
+        })
+    }
+
+    fn line(&self) -> String {
+        format!("{}: {}", self.summary, self.body)
+    }
+}
+
+/// Receives the profile and message of a notification that failed.
+type ErrorCallback = dyn Fn(&str, String) + Send + Sync;
+
+/// Delivers notifications. Desktop and command notifications are sent from a
+/// background thread so a slow notification daemon cannot stall the UI; the
+/// terminal bell and title are written by the UI between frames.
+pub struct Notifier {
+    config: NotifyConfig,
+    on_error: Arc<ErrorCallback>,
+    /// Set once the title has been changed, so it can be cleared on exit.
+    title_changed: bool,
+    pending_terminal: Option<Notification>,
+}
+
+impl Notifier {
+    /// `on_error` is called from a background thread with the profile and
+    /// message when a notification cannot be delivered.
+    pub fn new(
+        config: NotifyConfig,
+        on_error: impl Fn(&str, String) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            config,
+            on_error: Arc::new(on_error),
+            title_changed: false,
+            pending_terminal: None,
+        }
+    }
+
+    pub fn notify(&mut self, profile: &str, transitions: &[Transition]) {
+        if !self.config.is_enabled() {
+            return;
+        }
+        let notifications: Vec<Notification> = transitions
+            .iter()
+            .filter_map(|transition| Notification::for_transition(profile, transition))
+            .collect();
+        let Some(last) = notifications.last() else {
+            return;
+        };
+        if self.config.bell || self.config.title {
+            self.pending_terminal = Some(last.clone());
+        }
+        if !self.config.desktop && self.config.command.is_none() {
+            return;
+        }
+        let config = self.config.clone();
+        let on_error = Arc::clone(&self.on_error);
+        let profile = profile.to_string();
+        thread::spawn(move || {
+            for notification in &notifications {
+                if config.desktop {
+                    if let Err(err) = send_desktop(notification) {
+                        on_error(&profile, format!("Desktop notification failed: {err:#}"));
+                    }
+                }
+                if let Some(command) = &config.command {
+                    if let Err(err) = run_command(command, notification) {
+                        on_error(&profile, format!("notify_command failed: {err:#}"));
+                    }
+                }
+            }
+        });
+    }
+
+    /// Writes the bell and title for notifications since the last call.
+    pub fn flush_terminal(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let Some(notification) = self.pending_terminal.take() else {
+            return Ok(());
+        };
+        if self.config.title {
+            queue!(
+                out,
+                SetTitle(format!("transmission-tui: {}", notification.line()))
+            )?;
+            self.title_changed = true;
+        }
+        if self.config.bell {
+            out.write_all(b"\x07")?;
+        }
+        out.flush()
+    }
+
+    /// Clears a title set by [`Notifier::flush_terminal`].
+    pub fn reset_terminal(&mut self, out: &mut impl Write) -> io::Result<()> {
+        if !self.title_changed {
+            return Ok(());
+        }
+        self.title_changed = false;
+        queue!(out, SetTitle(""))?;
+        out.flush()
+    }
+}
+
+/// Runs `command` through the shell with the notification in
+/// `TR_NOTIFY_SUMMARY`, `TR_NOTIFY_BODY` and `TR_NOTIFY_URGENCY`.
+fn run_command(command: &str, notification: &Notification) -> Result<()> {
+    let urgency = if notification.urgent {
+        "critical"
+    } else {
+        "normal"
+    };
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("TR_NOTIFY_SUMMARY", &notification.summary)
+        .env("TR_NOTIFY_BODY", &notification.body)
+        .env("TR_NOTIFY_URGENCY", urgency)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .with_context(|| format!("failed to run `{command}`"))?;
+    if !status.success() {
+        bail!("`{command}` {status}");
+    }
+    Ok(())
+}
+
+#[cfg(feature = "desktop-notifications")]
+fn send_desktop(notification: &Notification) -> Result<()> {
+    use std::collections::HashMap;
+
+    use zbus::{blocking::Connection, zvariant::Value};
+
+    let connection = Connection::session().context("cannot reach the session bus")?;
+    let mut hints: HashMap<&str, Value> = HashMap::new();
+    // Urgency byte from the Desktop Notifications spec: 1 normal, 2 critical.
+    hints.insert(
+        "urgency",
+        Value::U8(if notification.urgent { 2 } else { 1 }),
+    );
+    connection
+        .call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Notify",
+            &(
+                "transmission-tui",
+                0u32,
+                "",
+                notification.summary.as_str(),
+                notification.body.as_str(),
+                Vec::<&str>::new(),
+                hints,
+                -1i32,
+            ),
+        )
+        .context("Notify call failed")?;
+    Ok(())
+}
+
+#[cfg(not(feature = "desktop-notifications"))]
+fn send_desktop(_notification: &Notification) -> Result<()> {
+    bail!("this build lacks the `desktop-notifications` feature")
+}
@@ -82,6 +82,63 @@ impl DaemonPreferences {
         );
         args
     }
+
+    /// Sets one preference from its RPC key (as listed in [`PREFERENCE_FIELDS`])
+    /// and a textual value, as typed on the command line.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "download_dir" => self.download_dir = value.to_string(),
+            "start_added_torrents" => self.start_when_added = parse_bool(value)?,
+            "speed_limit_up_enabled" => self.speed_limit_up_enabled = parse_bool(value)?,
+            "speed_limit_up" => self.speed_limit_up = parse_number(value)?,
+            "speed_limit_down_enabled" => self.speed_limit_down_enabled = parse_bool(value)?,
+            "speed_limit_down" => self.speed_limit_down = parse_number(value)?,
+            "seed_ratio_limited" => self.seed_ratio_limited = parse_bool(value)?,
+            "seed_ratio_limit" => {
+                self.seed_ratio_limit = value
+                    .parse()
+                    .ok()
+                    .filter(|ratio: &f64| *ratio >= 0.0)
+                    .ok_or_else(|| format!("expected a non-negative ratio, got '{value}'"))?
+            }
+            "idle_seeding_limit_enabled" => self.idle_seeding_limit_enabled = parse_bool(value)?,
+            "idle_seeding_limit" => self.idle_seeding_limit = parse_number(value)?,
+            "peer_limit_per_torrent" => self.peer_limit_per_torrent = parse_number(value)?,
+            "peer_limit_global" => self.peer_limit_global = parse_number(value)?,
+            "encryption" => {
+                self.encryption_mode = EncryptionMode::values()
+                    .iter()
+                    .copied()
+                    .find(|mode| mode.rpc_value() == value)
+                    .ok_or_else(|| {
+                        format!("expected preferred, allowed or required, got '{value}'")
+                    })?
+            }
+            "pex_enabled" => self.pex_enabled = parse_bool(value)?,
+            "dht_enabled" => self.dht_enabled = parse_bool(value)?,
+            "lpd_enabled" => self.lpd_enabled = parse_bool(value)?,
+            "blocklist_enabled" => self.blocklist_enabled = parse_bool(value)?,
+            "blocklist_url" => {
+                self.blocklist_url = Some(value.to_string()).filter(|url| !url.is_empty())
+            }
+            other => return Err(format!("unknown preference '{other}'")),
+        }
+        Ok(())
+    }
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err(format!("expected true or false, got '{value}'")),
+    }
+}
+
+fn parse_number(value: &str) -> Result<u32, String> {
+    value
+        .parse()
+        .map_err(|_| format!("expected a whole number, got '{value}'"))
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -1,28 +1,39 @@
 use std::{
     borrow::Cow,
-    io,
+    error::Error as StdError,
+    fs, io,
+    path::PathBuf,
     sync::{
         atomic::{AtomicBool, AtomicU64, Ordering},
         Mutex,
     },
 };
 
-use anyhow::Result;
-use reqwest::{blocking::Client, StatusCode};
+use anyhow::{Context, Result};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use reqwest::{
+    blocking::{Client, ClientBuilder},
+    Certificate, Identity, Proxy, StatusCode,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Map, Value};
 use thiserror::Error;
 
 use crate::{
-    config::RpcConfig,
+    capabilities::Capabilities,
+    config::{ProxySetting, RpcConfig, TrafficCapture},
     model::{PeerSummary, Snapshot, TorrentSummary},
     preferences::{DaemonPreferences, PreferencesResponse, PREFERENCE_FIELDS},
+    secret::Secret,
+    traffic::{TrafficOutcome, TrafficRecorder, TrafficReplay},
 };
 
 #[derive(Debug, Error)]
 pub enum TransmissionError {
     #[error("http error: {0}")]
     Http(#[from] reqwest::Error),
+    #[error("TLS certificate error: {0}")]
+    Certificate(String),
     #[error("authentication failed")]
     Authentication,
     #[error("session negotiation failed")]
@@ -37,6 +48,27 @@ pub enum TransmissionError {
     },
     #[error("response parse error: {0}")]
     Parse(#[from] serde_json::Error),
+    #[error("replay: {0}")]
+    Replay(String),
+    #[error("daemon offline")]
+    Offline,
+    #[error("daemon does not support {0}")]
+    Unsupported(&'static str),
+}
+
+impl TransmissionError {
+    /// The daemon could not be reached or refused us, as opposed to rejecting
+    /// one particular request.
+    pub fn is_connection_failure(&self) -> bool {
+        matches!(
+            self,
+            TransmissionError::Http(_)
+                | TransmissionError::Certificate(_)
+                | TransmissionError::Authentication
+                | TransmissionError::Session
+                | TransmissionError::Offline
+        )
+    }
 }
 
 pub type RpcResult<T> = std::result::Result<T, TransmissionError>;
@@ -44,29 +76,101 @@ pub type RpcResult<T> = std::result::Result<T, TransmissionError>;
 pub struct TransmissionClient {
     http: Client,
     endpoint: String,
-    auth: Option<(String, Option<String>)>,
+    auth: Option<(String, Option<Secret>)>,
     session_id: Mutex<Option<String>>,
     counter: AtomicU64,
     use_json_rpc: AtomicBool,
+    capabilities: Mutex<Option<Capabilities>>,
+    recorder: Option<TrafficRecorder>,
+    replay: Option<TrafficReplay>,
 }
 
 impl TransmissionClient {
     pub fn new(config: RpcConfig) -> Result<Self> {
-        let endpoint = config.endpoint();
+        let endpoint = config.request_url();
+        let unix_socket = config.unix_socket();
         let RpcConfig {
             username,
             password,
             timeout,
             verify_ssl,
+            ca_file,
+            client_cert,
+            client_key,
+            proxy,
             user_agent,
+            capture,
             ..
         } = config;
         let mut builder = Client::builder().timeout(timeout).user_agent(user_agent);
         if !verify_ssl {
             builder = builder.danger_accept_invalid_certs(true);
         }
+        if let Some(path) = &ca_file {
+            let pem = fs::read(path).map_err(|err| {
+                TransmissionError::Certificate(format!(
+                    "failed to read ca_file {}: {err}",
+                    path.display()
+                ))
+            })?;
+            let certs = Certificate::from_pem_bundle(&pem).map_err(|err| {
+                TransmissionError::Certificate(format!(
+                    "invalid PEM in ca_file {}: {err}",
+                    path.display()
+                ))
+            })?;
+            for cert in certs {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+        if let Some(cert_path) = &client_cert {
+            let mut pem = fs::read(cert_path).map_err(|err| {
+                TransmissionError::Certificate(format!(
+                    "failed to read client_cert {}: {err}",
+                    cert_path.display()
+                ))
+            })?;
+            if let Some(key_path) = &client_key {
+                let key = fs::read(key_path).map_err(|err| {
+                    TransmissionError::Certificate(format!(
+                        "failed to read client_key {}: {err}",
+                        key_path.display()
+                    ))
+                })?;
+                pem.push(b'\n');
+                pem.extend_from_slice(&key);
+            }
+            let identity = Identity::from_pem(&pem).map_err(|err| {
+                TransmissionError::Certificate(format!(
+                    "client_cert {} does not hold a usable certificate and private key: {err}",
+                    cert_path.display()
+                ))
+            })?;
+            builder = builder.identity(identity);
+        }
+        if let Some(path) = unix_socket {
+            builder = unix_transport(builder, path)?;
+        }
+        match proxy {
+            ProxySetting::System => {}
+            ProxySetting::Direct => builder = builder.no_proxy(),
+            ProxySetting::Url(url) => {
+                let proxy = Proxy::all(url).context("invalid proxy")?;
+                builder = builder.proxy(proxy);
+            }
+        }
         let http = builder.build()?;
+        let password = password.map(|source| source.resolve()).transpose()?;
         let auth = username.map(|user| (user, password));
+        let (recorder, replay) = match &capture {
+            TrafficCapture::Off => (None, None),
+            TrafficCapture::Record { path, profile } => {
+                (Some(TrafficRecorder::create(path, profile)?), None)
+            }
+            TrafficCapture::Replay { path, profile } => {
+                (None, Some(TrafficReplay::open(path, profile)?))
+            }
+        };
         Ok(Self {
             http,
             endpoint,
@@ -74,9 +178,48 @@ impl TransmissionClient {
             session_id: Mutex::new(None),
             counter: AtomicU64::new(1),
             use_json_rpc: AtomicBool::new(true),
+            capabilities: Mutex::new(None),
+            recorder,
+            replay,
         })
     }
 
+    /// Reads the daemon's RPC versions and settles the dialect and feature set
+    /// used for every later call. The probe is sent in the legacy dialect, which
+    /// every daemon understands, before switching to JSON-RPC where available.
+    pub fn handshake(&self) -> RpcResult<Capabilities> {
+        let args = json!({ "fields": HANDSHAKE_FIELDS });
+        let value =
+            match self.call_raw_inner(RpcProtocol::Legacy, "session_get", Some(args.clone())) {
+                Ok(value) => value,
+                Err(TransmissionError::Rpc { .. } | TransmissionError::Parse(_)) => {
+                    self.call_raw_inner(RpcProtocol::Json, "session_get", Some(args))?
+                }
+                Err(err) => return Err(err),
+            };
+        let versions: SessionVersions = serde_json::from_value(value)?;
+        let capabilities = Capabilities::from_versions(
+            versions.rpc_version.unwrap_or(0),
+            versions.rpc_version_minimum.unwrap_or(0),
+            versions
+                .rpc_version_semver
+                .filter(|semver| !semver.is_empty()),
+        );
+        self.use_json_rpc
+            .store(capabilities.json_rpc, Ordering::Relaxed);
+        if let Ok(mut guard) = self.capabilities.lock() {
+            *guard = Some(capabilities.clone());
+        }
+        Ok(capabilities)
+    }
+
+    pub fn capabilities(&self) -> Option<Capabilities> {
+        self.capabilities
+            .lock()
+            .ok()
+            .and_then(|guard| guard.clone())
+    }
+
     pub fn fetch_preferences(&self) -> RpcResult<DaemonPreferences> {
         let prefs: PreferencesResponse = self.session_get(PREFERENCE_FIELDS)?;
         Ok(DaemonPreferences::from(prefs))
@@ -89,7 +232,8 @@ impl TransmissionClient {
     }
 
     pub fn fetch_snapshot(&self) -> RpcResult<Snapshot> {
-        let fields = [
+        let capabilities = self.capabilities();
+        let mut fields = vec![
             "id",
             "name",
             "status",
@@ -106,8 +250,28 @@ impl TransmissionClient {
             "peers_getting_from_us",
             "error_string",
             "peers",
+            "seconds_seeding",
+            "trackers",
+            "downloaded_ever",
+            "uploaded_ever",
+            "added_date",
+            "done_date",
+            "queue_position",
+            "hash_string",
         ];
-        let torrents: TorrentGetResponse = self.torrent_get(&fields)?;
+        if let Some(caps) = &capabilities {
+            if caps.labels {
+                fields.push("labels");
+            }
+            if caps.file_count {
+                fields.push("file_count");
+            }
+            if caps.bandwidth_groups {
+                fields.push("group");
+            }
+        }
+        let table = capabilities.as_ref().is_some_and(|caps| caps.table_format);
+        let torrents: TorrentGetResponse = self.torrent_get(&fields, table)?;
         let stats: SessionStats = self.session_stats()?;
         let session: SessionInfo = self.session_get(&["version"])?;
         Ok(Snapshot {
@@ -125,10 +289,40 @@ impl TransmissionClient {
         })
     }
 
-    pub fn add_magnet(&self, magnet: &str) -> RpcResult<AddTorrentOutcome> {
+    pub fn add_magnet(&self, magnet: &str, options: &AddOptions) -> RpcResult<AddTorrentOutcome> {
         let args = json!({
             "filename": magnet,
         });
+        self.add_torrent(args, options)
+    }
+
+    /// Adds a `.torrent` file's contents, sent base64-encoded as `metainfo`.
+    pub fn add_torrent_file(
+        &self,
+        metainfo: &[u8],
+        options: &AddOptions,
+    ) -> RpcResult<AddTorrentOutcome> {
+        let args = json!({
+            "metainfo": BASE64_STANDARD.encode(metainfo),
+        });
+        self.add_torrent(args, options)
+    }
+
+    fn add_torrent(&self, mut args: Value, options: &AddOptions) -> RpcResult<AddTorrentOutcome> {
+        if let Value::Object(map) = &mut args {
+            if let Some(dir) = &options.download_dir {
+                map.insert("download_dir".to_string(), json!(dir));
+            }
+            if options.paused {
+                map.insert("paused".to_string(), json!(true));
+            }
+            if !options.labels.is_empty() {
+                if self.capabilities().is_some_and(|caps| !caps.labels) {
+                    return Err(TransmissionError::Unsupported("labels"));
+                }
+                map.insert("labels".to_string(), json!(options.labels));
+            }
+        }
         let response: AddTorrentResponse = self.call("torrent_add", Some(args))?;
         Ok(AddTorrentOutcome::from(response))
     }
@@ -154,6 +348,21 @@ impl TransmissionClient {
         Ok(())
     }
 
+    pub fn reannounce_torrents(&self, ids: &[i64]) -> RpcResult<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        if self
+            .capabilities()
+            .is_some_and(|caps| !caps.torrent_reannounce)
+        {
+            return Err(TransmissionError::Unsupported("torrent_reannounce"));
+        }
+        let args = json!({ "ids": ids });
+        self.call_raw("torrent_reannounce", Some(args))?;
+        Ok(())
+    }
+
     pub fn stop_torrents(&self, ids: &[i64]) -> RpcResult<()> {
         if ids.is_empty() {
             return Ok(());
@@ -163,6 +372,67 @@ impl TransmissionClient {
         Ok(())
     }
 
+    /// Replaces the labels of `ids`.
+    pub fn set_labels(&self, ids: &[i64], labels: &[String]) -> RpcResult<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        if self.capabilities().is_some_and(|caps| !caps.labels) {
+            return Err(TransmissionError::Unsupported("labels"));
+        }
+        let args = json!({ "ids": ids, "labels": labels });
+        self.call_raw("torrent_set", Some(args))?;
+        Ok(())
+    }
+
+    /// Per-torrent speed limits in KiB/s; `None` leaves that direction
+    /// unlimited.
+    pub fn set_speed_limits(
+        &self,
+        ids: &[i64],
+        download: Option<i64>,
+        upload: Option<i64>,
+    ) -> RpcResult<()> {
+        self.set_limits(ids, &[("download", download), ("upload", upload)])
+    }
+
+    /// Per-torrent download limit in KiB/s, leaving the upload limit as is;
+    /// `None` makes it unlimited.
+    pub fn set_download_limit(&self, ids: &[i64], limit: Option<i64>) -> RpcResult<()> {
+        self.set_limits(ids, &[("download", limit)])
+    }
+
+    /// Per-torrent upload limit in KiB/s, leaving the download limit as is.
+    pub fn set_upload_limit(&self, ids: &[i64], limit: Option<i64>) -> RpcResult<()> {
+        self.set_limits(ids, &[("upload", limit)])
+    }
+
+    /// Sets `{direction}_limited` and `{direction}_limit` for each
+    /// `(direction, limit)` pair; directions not listed are left as they are.
+    fn set_limits(&self, ids: &[i64], limits: &[(&str, Option<i64>)]) -> RpcResult<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let mut args = Map::new();
+        args.insert("ids".into(), json!(ids));
+        for (direction, limit) in limits {
+            args.insert(format!("{direction}_limited"), json!(limit.is_some()));
+            args.insert(format!("{direction}_limit"), json!(limit.unwrap_or(0)));
+        }
+        self.call_raw("torrent_set", Some(Value::Object(args)))?;
+        Ok(())
+    }
+
+    /// Moves the data of `ids` to `location` on the daemon's filesystem.
+    pub fn move_torrents(&self, ids: &[i64], location: &str) -> RpcResult<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let args = json!({ "ids": ids, "location": location, "move": true });
+        self.call_raw("torrent_set_location", Some(args))?;
+        Ok(())
+    }
+
     fn session_get<T>(&self, fields: &[&str]) -> RpcResult<T>
     where
         T: for<'de> Deserialize<'de>,
@@ -181,10 +451,14 @@ impl TransmissionClient {
         serde_json::from_value(value).map_err(TransmissionError::from)
     }
 
-    fn torrent_get(&self, fields: &[&str]) -> RpcResult<TorrentGetResponse> {
-        let args = json!({"fields": fields});
+    fn torrent_get(&self, fields: &[&str], table: bool) -> RpcResult<TorrentGetResponse> {
+        let args = if table {
+            json!({"fields": fields, "format": "table"})
+        } else {
+            json!({"fields": fields})
+        };
         let value = self.call_raw("torrent_get", Some(args))?;
-        serde_json::from_value(value).map_err(TransmissionError::from)
+        serde_json::from_value(expand_table_format(value)).map_err(TransmissionError::from)
     }
 
     fn call<T>(&self, method: &'static str, arguments: Option<Value>) -> RpcResult<T>
@@ -251,6 +525,40 @@ impl TransmissionClient {
     }
 
     fn perform_request<T>(&self, payload: &T) -> RpcResult<Value>
+    where
+        T: Serialize,
+    {
+        if self.replay.is_none() && self.recorder.is_none() {
+            return self.send_request(payload).and_then(handle_response_body);
+        }
+        let request = serde_json::to_value(payload)?;
+        if let Some(replay) = &self.replay {
+            let outcome = replay.next(&request).ok_or_else(|| {
+                TransmissionError::Replay(format!(
+                    "no recorded response for {}",
+                    request.get("method").unwrap_or(&Value::Null)
+                ))
+            })?;
+            return replayed_body(outcome).and_then(handle_response_body);
+        }
+        let result = self.send_request(payload);
+        if let Some(recorder) = &self.recorder {
+            let outcome = match &result {
+                Ok(body) => TrafficOutcome::Response(body.clone()),
+                Err(TransmissionError::Authentication) => {
+                    TrafficOutcome::Status(StatusCode::UNAUTHORIZED.as_u16())
+                }
+                Err(TransmissionError::HttpStatus(status)) => {
+                    TrafficOutcome::Status(status.as_u16())
+                }
+                Err(err) => TrafficOutcome::Error(err.to_string()),
+            };
+            recorder.record(&request, outcome);
+        }
+        result.and_then(handle_response_body)
+    }
+
+    fn send_request<T>(&self, payload: &T) -> RpcResult<Value>
     where
         T: Serialize,
     {
@@ -260,7 +568,7 @@ impl TransmissionClient {
                 .post(&self.endpoint)
                 .header("Content-Type", "application/json");
             if let Some((user, pass)) = &self.auth {
-                request = request.basic_auth(user, pass.as_ref());
+                request = request.basic_auth(user, pass.as_ref().map(Secret::expose));
             }
             let session_header = match self.session_id.lock() {
                 Ok(guard) => (*guard).clone(),
@@ -269,7 +577,7 @@ impl TransmissionClient {
             if let Some(session) = session_header {
                 request = request.header("X-Transmission-Session-Id", session);
             }
-            let response = request.json(payload).send()?;
+            let response = request.json(payload).send().map_err(classify_send_error)?;
             match response.status() {
                 StatusCode::CONFLICT => {
                     if let Some(id) = response.headers().get("X-Transmission-Session-Id") {
@@ -288,15 +596,88 @@ impl TransmissionClient {
                 status if !status.is_success() => {
                     return Err(TransmissionError::HttpStatus(status));
                 }
-                _ => {
-                    let body: Value = response.json()?;
-                    return handle_response_body(body);
-                }
+                _ => return Ok(response.json()?),
             }
         }
     }
 }
 
+#[cfg(unix)]
+fn unix_transport(builder: ClientBuilder, path: PathBuf) -> Result<ClientBuilder> {
+    Ok(builder.unix_socket(path))
+}
+
+#[cfg(not(unix))]
+fn unix_transport(_builder: ClientBuilder, path: PathBuf) -> Result<ClientBuilder> {
+    anyhow::bail!(
+        "cannot use unix socket {}: not supported on this platform",
+        path.display()
+    )
+}
+
+/// Pulls TLS certificate failures (unknown issuer, expired or rejected client
+/// certificates) out of the generic transport error so users see why the
+/// handshake failed rather than a bare connection error.
+fn classify_send_error(err: reqwest::Error) -> TransmissionError {
+    match certificate_failure(&err) {
+        Some(tls) => TransmissionError::Certificate(tls.to_string()),
+        None => TransmissionError::Http(err),
+    }
+}
+
+/// Finds a rustls error about certificates in the cause chain. hyper hands
+/// rustls errors up inside (nested) `io::Error`s, whose `source()` skips the
+/// wrapped error, so those are unwrapped by hand.
+fn certificate_failure<'a>(err: &'a (dyn StdError + 'static)) -> Option<&'a rustls::Error> {
+    let mut source = err.source();
+    while let Some(cause) = source {
+        let mut inner = cause;
+        while let Some(wrapped) = inner
+            .downcast_ref::<io::Error>()
+            .and_then(|io| io.get_ref())
+        {
+            inner = wrapped;
+        }
+        if let Some(tls) = inner.downcast_ref::<rustls::Error>() {
+            return is_certificate_error(tls).then_some(tls);
+        }
+        source = cause.source();
+    }
+    None
+}
+
+/// Our verification of the daemon's certificate failed, or the daemon
+/// rejected (or wanted) ours.
+fn is_certificate_error(err: &rustls::Error) -> bool {
+    use rustls::AlertDescription as Alert;
+    match err {
+        rustls::Error::InvalidCertificate(_) | rustls::Error::NoCertificatesPresented => true,
+        rustls::Error::AlertReceived(alert) => matches!(
+            alert,
+            Alert::BadCertificate
+                | Alert::UnsupportedCertificate
+                | Alert::CertificateRevoked
+                | Alert::CertificateExpired
+                | Alert::CertificateUnknown
+                | Alert::UnknownCA
+                | Alert::CertificateRequired
+        ),
+        _ => false,
+    }
+}
+
+fn replayed_body(outcome: TrafficOutcome) -> RpcResult<Value> {
+    match outcome {
+        TrafficOutcome::Response(body) => Ok(body),
+        TrafficOutcome::Status(401) => Err(TransmissionError::Authentication),
+        TrafficOutcome::Status(409) => Err(TransmissionError::Session),
+        TrafficOutcome::Status(code) => Err(TransmissionError::HttpStatus(
+            StatusCode::from_u16(code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+        )),
+        TrafficOutcome::Error(message) => Err(TransmissionError::Replay(message)),
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum RpcProtocol {
     Json,
@@ -398,11 +779,38 @@ fn extract_context_from_value(value: &Value) -> Option<String> {
     }
 }
 
+/// Turns a `"format": "table"` torrent list (a header row of field names
+/// followed by value rows) back into the object form the wire types expect.
+fn expand_table_format(value: Value) -> Value {
+    let Value::Object(mut map) = value else {
+        return value;
+    };
+    if let Some(Value::Array(rows)) = map.get_mut("torrents") {
+        if let Some(Value::Array(_)) = rows.first() {
+            let mut rows = std::mem::take(rows).into_iter();
+            let header: Vec<String> = match rows.next() {
+                Some(Value::Array(names)) => names
+                    .into_iter()
+                    .filter_map(|name| name.as_str().map(str::to_string))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            let objects = rows
+                .filter_map(|row| match row {
+                    Value::Array(values) => Some(Value::Object(
+                        header.iter().cloned().zip(values).collect::<Map<_, _>>(),
+                    )),
+                    _ => None,
+                })
+                .collect();
+            map.insert("torrents".to_string(), Value::Array(objects));
+        }
+    }
+    Value::Object(map)
+}
+
 fn response_parse_error(msg: &str) -> TransmissionError {
-    TransmissionError::Parse(serde_json::Error::io(io::Error::new(
-        io::ErrorKind::Other,
-        msg.to_string(),
-    )))
+    TransmissionError::Parse(serde_json::Error::io(io::Error::other(msg.to_string())))
 }
 
 fn method_for_protocol(method: &'static str, protocol: RpcProtocol) -> Cow<'static, str> {
@@ -416,6 +824,9 @@ fn method_for_protocol(method: &'static str, protocol: RpcProtocol) -> Cow<'stat
             "torrent_remove" => "torrent-remove",
             "torrent_start" => "torrent-start",
             "torrent_stop" => "torrent-stop",
+            "torrent_reannounce" => "torrent-reannounce",
+            "torrent_set" => "torrent-set",
+            "torrent_set_location" => "torrent-set-location",
             other => other,
         })
     } else {
@@ -436,6 +847,8 @@ fn translate_arguments_for_protocol(
         "torrent_get" => map_fields_argument(value, legacy_torrent_field_name),
         "session_set" => map_object_keys(value, legacy_session_field_name),
         "torrent_remove" => rename_key(value, "delete_local_data", "delete-local-data"),
+        "torrent_add" => map_object_keys(value, legacy_session_field_name),
+        "torrent_set" => map_object_keys(value, legacy_torrent_field_name),
         _ => value,
     })
 }
@@ -497,6 +910,9 @@ fn legacy_session_field_name(field: &str) -> Cow<'static, str> {
         "lpd_enabled" => Cow::Borrowed("lpd-enabled"),
         "blocklist_enabled" => Cow::Borrowed("blocklist-enabled"),
         "blocklist_url" => Cow::Borrowed("blocklist-url"),
+        "rpc_version" => Cow::Borrowed("rpc-version"),
+        "rpc_version_minimum" => Cow::Borrowed("rpc-version-minimum"),
+        "rpc_version_semver" => Cow::Borrowed("rpc-version-semver"),
         other => Cow::Owned(other.to_string()),
     }
 }
@@ -514,6 +930,18 @@ fn legacy_torrent_field_name(field: &str) -> Cow<'static, str> {
         "peers_sending_to_us" => Cow::Borrowed("peersSendingToUs"),
         "peers_getting_from_us" => Cow::Borrowed("peersGettingFromUs"),
         "error_string" => Cow::Borrowed("errorString"),
+        "file_count" => Cow::Borrowed("file-count"),
+        "seconds_seeding" => Cow::Borrowed("secondsSeeding"),
+        "downloaded_ever" => Cow::Borrowed("downloadedEver"),
+        "uploaded_ever" => Cow::Borrowed("uploadedEver"),
+        "added_date" => Cow::Borrowed("addedDate"),
+        "done_date" => Cow::Borrowed("doneDate"),
+        "queue_position" => Cow::Borrowed("queuePosition"),
+        "hash_string" => Cow::Borrowed("hashString"),
+        "download_limit" => Cow::Borrowed("downloadLimit"),
+        "download_limited" => Cow::Borrowed("downloadLimited"),
+        "upload_limit" => Cow::Borrowed("uploadLimit"),
+        "upload_limited" => Cow::Borrowed("uploadLimited"),
         other => Cow::Owned(other.to_string()),
     }
 }
@@ -537,6 +965,23 @@ struct SessionInfo {
     version: Option<String>,
 }
 
+const HANDSHAKE_FIELDS: &[&str] = &[
+    "version",
+    "rpc_version",
+    "rpc_version_minimum",
+    "rpc_version_semver",
+];
+
+#[derive(Debug, Deserialize)]
+struct SessionVersions {
+    #[serde(default, alias = "rpc-version")]
+    rpc_version: Option<i64>,
+    #[serde(default, alias = "rpc-version-minimum")]
+    rpc_version_minimum: Option<i64>,
+    #[serde(default, alias = "rpc-version-semver")]
+    rpc_version_semver: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct TorrentGetResponse {
     #[serde(default)]
@@ -577,6 +1022,34 @@ struct TorrentWire {
     error_string: String,
     #[serde(default)]
     peers: Vec<PeerWire>,
+    #[serde(default)]
+    labels: Vec<String>,
+    #[serde(default, alias = "file-count")]
+    file_count: Option<i64>,
+    #[serde(default)]
+    group: String,
+    #[serde(default, alias = "secondsSeeding")]
+    seconds_seeding: i64,
+    #[serde(default)]
+    trackers: Vec<TrackerWire>,
+    #[serde(default, alias = "downloadedEver")]
+    downloaded_ever: i64,
+    #[serde(default, alias = "uploadedEver")]
+    uploaded_ever: i64,
+    #[serde(default, alias = "addedDate")]
+    added_date: i64,
+    #[serde(default, alias = "doneDate")]
+    done_date: i64,
+    #[serde(default, alias = "queuePosition")]
+    queue_position: i64,
+    #[serde(default, alias = "hashString")]
+    hash_string: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TrackerWire {
+    #[serde(default)]
+    announce: String,
 }
 
 impl From<TorrentWire> for TorrentSummary {
@@ -598,6 +1071,17 @@ impl From<TorrentWire> for TorrentSummary {
             peers_getting_from_us,
             error_string,
             peers,
+            labels,
+            file_count,
+            group,
+            seconds_seeding,
+            trackers,
+            downloaded_ever,
+            uploaded_ever,
+            added_date,
+            done_date,
+            queue_position,
+            hash_string,
         } = wire;
         let eta = if eta >= 0 { Some(eta) } else { None };
         let status = match status {
@@ -631,6 +1115,21 @@ impl From<TorrentWire> for TorrentSummary {
                 Some(error_string)
             },
             peers: peers.into_iter().map(PeerSummary::from).collect(),
+            labels,
+            file_count,
+            bandwidth_group: if group.is_empty() { None } else { Some(group) },
+            seconds_seeding,
+            trackers: trackers
+                .into_iter()
+                .map(|tracker| tracker.announce)
+                .collect(),
+            daemon: String::new(),
+            downloaded_ever,
+            uploaded_ever,
+            added_date,
+            done_date,
+            queue_position,
+            hash: hash_string,
         }
     }
 }
@@ -686,6 +1185,15 @@ struct TorrentRef {
     name: Option<String>,
 }
 
+/// Optional `torrent_add` arguments; the defaults leave everything to the
+/// daemon's session settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AddOptions {
+    pub download_dir: Option<String>,
+    pub labels: Vec<String>,
+    pub paused: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct AddTorrentOutcome {
     pub torrent_id: Option<i64>,
@@ -720,3 +1228,155 @@ impl From<AddTorrentResponse> for AddTorrentOutcome {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_table_format_torrent_lists() {
+        let table = json!({
+            "torrents": [
+                ["id", "name", "status"],
+                [1, "debian.iso", 6],
+                [2, "arch.iso", 0],
+                "not a row",
+            ],
+        });
+        assert_eq!(
+            expand_table_format(table),
+            json!({
+                "torrents": [
+                    {"id": 1, "name": "debian.iso", "status": 6},
+                    {"id": 2, "name": "arch.iso", "status": 0},
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn leaves_object_format_and_other_values_alone() {
+        let objects = json!({"torrents": [{"id": 1}], "removed": [3]});
+        assert_eq!(expand_table_format(objects.clone()), objects);
+        let empty = json!({"torrents": []});
+        assert_eq!(expand_table_format(empty.clone()), empty);
+        assert_eq!(expand_table_format(json!(null)), json!(null));
+    }
+
+    #[test]
+    fn header_only_table_expands_to_no_torrents() {
+        assert_eq!(
+            expand_table_format(json!({"torrents": [["id", "name"]]})),
+            json!({"torrents": []})
+        );
+    }
+
+    #[test]
+    fn empty_bandwidth_group_means_none() {
+        let torrent = |group: &str| {
+            let wire: TorrentWire =
+                serde_json::from_value(json!({"id": 1, "name": "a", "group": group})).unwrap();
+            TorrentSummary::from(wire).bandwidth_group
+        };
+        assert_eq!(torrent("slow"), Some("slow".to_string()));
+        assert_eq!(torrent(""), None);
+    }
+
+    #[derive(Debug)]
+    struct Wrapped(io::Error);
+
+    impl std::fmt::Display for Wrapped {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("client error (Connect)")
+        }
+    }
+
+    impl StdError for Wrapped {
+        fn source(&self) -> Option<&(dyn StdError + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    /// Mirrors how hyper reports handshake failures: a rustls error two
+    /// `io::Error`s deep.
+    fn handshake_failure(tls: rustls::Error) -> Wrapped {
+        Wrapped(io::Error::other(io::Error::new(
+            io::ErrorKind::InvalidData,
+            tls,
+        )))
+    }
+
+    #[test]
+    fn finds_rustls_certificate_errors_behind_io_errors() {
+        let expired = handshake_failure(rustls::Error::InvalidCertificate(
+            rustls::CertificateError::Expired,
+        ));
+        assert!(matches!(
+            certificate_failure(&expired),
+            Some(rustls::Error::InvalidCertificate(_))
+        ));
+        let rejected = handshake_failure(rustls::Error::AlertReceived(
+            rustls::AlertDescription::CertificateRequired,
+        ));
+        assert!(certificate_failure(&rejected).is_some());
+
+        let decrypt = handshake_failure(rustls::Error::DecryptError);
+        assert!(certificate_failure(&decrypt).is_none());
+        // Only the rustls type counts, not a message that mentions certificates.
+        let message = Wrapped(io::Error::other("proxy wants a certificate"));
+        assert!(certificate_failure(&message).is_none());
+    }
+
+    fn tls_config() -> RpcConfig {
+        RpcConfig {
+            scheme: "https".to_string(),
+            host: "localhost".to_string(),
+            port: 9091,
+            path: "/transmission/rpc".to_string(),
+            username: None,
+            password: None,
+            timeout: std::time::Duration::from_secs(1),
+            verify_ssl: true,
+            ca_file: None,
+            client_cert: None,
+            client_key: None,
+            proxy: ProxySetting::System,
+            ssh: None,
+            user_agent: "test".to_string(),
+            url: None,
+            capture: TrafficCapture::Off,
+        }
+    }
+
+    #[test]
+    fn unusable_tls_files_are_certificate_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let junk = dir.path().join("junk.pem");
+        fs::write(&junk, "not a certificate").unwrap();
+        let configs = [
+            RpcConfig {
+                ca_file: Some(dir.path().join("missing.pem")),
+                ..tls_config()
+            },
+            RpcConfig {
+                client_cert: Some(junk.clone()),
+                ..tls_config()
+            },
+            RpcConfig {
+                client_cert: Some(junk),
+                client_key: Some(dir.path().join("missing.key")),
+                ..tls_config()
+            },
+        ];
+        for config in configs {
+            let err = TransmissionClient::new(config).err().unwrap();
+            assert!(
+                matches!(
+                    err.downcast_ref::<TransmissionError>(),
+                    Some(TransmissionError::Certificate(_))
+                ),
+                "{err:#}"
+            );
+        }
+    }
+}
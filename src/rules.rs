@@ -0,0 +1,607 @@
+use std::{collections::HashSet, time::SystemTime};
+
+use serde::Deserialize;
+
+use crate::{
+    model::{format_bytes, Snapshot, TorrentSummary},
+    rpc::{RpcResult, TransmissionClient},
+};
+
+/// The `when` table of a `[[rules]]` entry. Every field that is set must
+/// match; an empty condition matches every torrent.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleCondition {
+    pub status: Option<String>,
+    /// Case-insensitive name fragment.
+    pub name: Option<String>,
+    pub label: Option<String>,
+    /// Case-insensitive fragment of any announce URL.
+    pub tracker: Option<String>,
+    pub min_ratio: Option<f64>,
+    pub max_ratio: Option<f64>,
+    /// Seconds spent seeding.
+    pub min_seeding_time: Option<i64>,
+    /// Bytes wanted, as in the Size column.
+    pub min_size: Option<i64>,
+    pub max_size: Option<i64>,
+    /// `true` for torrents with an error, `false` for those without.
+    pub error: Option<bool>,
+}
+
+impl RuleCondition {
+    pub fn matches(&self, torrent: &TorrentSummary) -> bool {
+        let contains =
+            |haystack: &str, needle: &str| haystack.to_lowercase().contains(&needle.to_lowercase());
+        // Transmission reports -1 (no data) and -2 (infinite) as ratios;
+        // neither satisfies a ratio bound.
+        let ratio = (torrent.upload_ratio >= 0.0).then_some(torrent.upload_ratio);
+        self.status
+            .as_ref()
+            .is_none_or(|status| &torrent.status == status)
+            && self
+                .name
+                .as_ref()
+                .is_none_or(|name| contains(&torrent.name, name))
+            && self.label.as_ref().is_none_or(|label| {
+                torrent
+                    .labels
+                    .iter()
+                    .any(|have| have.eq_ignore_ascii_case(label))
+            })
+            && self.tracker.as_ref().is_none_or(|tracker| {
+                torrent
+                    .trackers
+                    .iter()
+                    .any(|announce| contains(announce, tracker))
+            })
+            && self
+                .min_ratio
+                .is_none_or(|min| ratio.is_some_and(|ratio| ratio >= min))
+            && self
+                .max_ratio
+                .is_none_or(|max| ratio.is_some_and(|ratio| ratio <= max))
+            && self
+                .min_seeding_time
+                .is_none_or(|min| torrent.seconds_seeding >= min)
+            && self
+                .min_size
+                .is_none_or(|min| torrent.size_when_done >= min)
+            && self
+                .max_size
+                .is_none_or(|max| torrent.size_when_done <= max)
+            && self
+                .error
+                .is_none_or(|error| torrent.error.is_some() == error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleAction {
+    Stop,
+    Remove,
+    /// Remove the torrent and delete its data.
+    RemoveWithData,
+    /// Add a label, keeping the existing ones.
+    SetLabel(String),
+    /// Move the data to another directory on the daemon.
+    Move(String),
+    /// Per-torrent limits in KiB/s; `None` means unlimited.
+    SpeedLimit {
+        download: Option<i64>,
+        upload: Option<i64>,
+    },
+}
+
+impl RuleAction {
+    pub fn describe(&self) -> String {
+        let limit = |value: &Option<i64>| match value {
+            Some(kib) => format!("{kib} KiB/s"),
+            None => "unlimited".to_string(),
+        };
+        match self {
+            RuleAction::Stop => "stop".to_string(),
+            RuleAction::Remove => "remove".to_string(),
+            RuleAction::RemoveWithData => "remove + delete data".to_string(),
+            RuleAction::SetLabel(label) => format!("label {label}"),
+            RuleAction::Move(location) => format!("move to {location}"),
+            RuleAction::SpeedLimit { download, upload } => {
+                format!("limit ↓{} ↑{}", limit(download), limit(upload))
+            }
+        }
+    }
+
+    fn removes(&self) -> bool {
+        matches!(self, RuleAction::Remove | RuleAction::RemoveWithData)
+    }
+
+    /// Whether the torrent already looks the way this action would leave it.
+    /// Speed limits are not part of the snapshot, so they never are.
+    fn satisfied_by(&self, torrent: &TorrentSummary) -> bool {
+        match self {
+            RuleAction::Stop => torrent.status == "stopped",
+            RuleAction::SetLabel(label) => torrent
+                .labels
+                .iter()
+                .any(|have| have.eq_ignore_ascii_case(label)),
+            RuleAction::Move(location) => {
+                torrent.download_dir.trim_end_matches('/') == location.trim_end_matches('/')
+            }
+            RuleAction::Remove | RuleAction::RemoveWithData | RuleAction::SpeedLimit { .. } => {
+                false
+            }
+        }
+    }
+
+    fn apply(&self, client: &TransmissionClient, torrent: &TorrentSummary) -> RpcResult<()> {
+        let ids = [torrent.torrent_id];
+        match self {
+            RuleAction::Stop => client.stop_torrents(&ids),
+            RuleAction::Remove => client.remove_torrents(&ids, false),
+            RuleAction::RemoveWithData => client.remove_torrents(&ids, true),
+            RuleAction::SetLabel(label) => {
+                let mut labels = torrent.labels.clone();
+                labels.push(label.clone());
+                client.set_labels(&ids, &labels)
+            }
+            RuleAction::Move(location) => client.move_torrents(&ids, location),
+            RuleAction::SpeedLimit { download, upload } => {
+                client.set_speed_limits(&ids, *download, *upload)
+            }
+        }
+    }
+}
+
+/// One `[[rules]]` entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub name: String,
+    /// Only apply to torrents of this profile.
+    pub profile: Option<String>,
+    pub when: RuleCondition,
+    pub action: RuleAction,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlannedAction {
+    /// Index into the rule list the plan was made from.
+    pub rule: usize,
+    pub torrent: TorrentSummary,
+    pub action: RuleAction,
+}
+
+/// Everything `rules` would do to `snapshot` (one daemon's torrents, from
+/// `profile`) if nothing had been applied yet. Rules run in order; once one
+/// removes a torrent, later rules skip it.
+pub fn plan(rules: &[Rule], profile: &str, snapshot: &Snapshot) -> Vec<PlannedAction> {
+    let mut planned = Vec::new();
+    for torrent in &snapshot.torrents {
+        for (index, rule) in rules.iter().enumerate() {
+            if rule.profile.as_deref().is_some_and(|name| name != profile)
+                || !rule.when.matches(torrent)
+                || rule.action.satisfied_by(torrent)
+            {
+                continue;
+            }
+            planned.push(PlannedAction {
+                rule: index,
+                torrent: torrent.clone(),
+                action: rule.action.clone(),
+            });
+            if rule.action.removes() {
+                break;
+            }
+        }
+    }
+    planned
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditResult {
+    Applied,
+    /// `rules_dry_run` is set, so nothing was sent to the daemon.
+    DryRun,
+    Failed(String),
+}
+
+/// One entry in the rules audit log.
+#[derive(Debug, Clone)]
+pub struct RuleAudit {
+    pub at: SystemTime,
+    pub rule: String,
+    pub profile: String,
+    pub torrent: String,
+    pub action: String,
+    pub result: AuditResult,
+}
+
+impl RuleAudit {
+    pub fn message(&self) -> String {
+        let outcome = match &self.result {
+            AuditResult::Applied => String::new(),
+            AuditResult::DryRun => " (dry run)".to_string(),
+            AuditResult::Failed(reason) => format!(" failed: {reason}"),
+        };
+        format!(
+            "Rule '{}': {} {}{outcome}",
+            self.rule, self.action, self.torrent
+        )
+    }
+}
+
+/// Applies rules to one profile's snapshots. An action fires once when its
+/// rule starts matching a torrent and again only after the rule has stopped
+/// matching it in between, so manual changes are not fought on every poll.
+/// An action that failed because the daemon could not be reached is retried
+/// on the next poll; one the daemon rejected counts as fired, so a rule that
+/// keeps failing reports the failure once instead of on every poll.
+pub struct RuleEngine {
+    rules: Vec<Rule>,
+    profile: String,
+    dry_run: bool,
+    /// `(rule index, torrent id)` pairs acted on (or rejected by the daemon)
+    /// and still matching.
+    applied: HashSet<(usize, i64)>,
+}
+
+impl RuleEngine {
+    pub fn new(rules: Vec<Rule>, profile: impl Into<String>, dry_run: bool) -> Self {
+        Self {
+            rules,
+            profile: profile.into(),
+            dry_run,
+            applied: HashSet::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Actions due for `snapshot` that have not fired yet. They count as
+    /// fired once passed to `mark_applied`.
+    pub fn evaluate(&mut self, snapshot: &Snapshot) -> Vec<PlannedAction> {
+        let planned = plan(&self.rules, &self.profile, snapshot);
+        // A satisfied action counts as still matching until the condition
+        // itself stops holding.
+        let holding: HashSet<(usize, i64)> = snapshot
+            .torrents
+            .iter()
+            .flat_map(|torrent| {
+                self.rules
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, rule)| rule.when.matches(torrent))
+                    .map(|(index, _)| (index, torrent.torrent_id))
+            })
+            .collect();
+        self.applied.retain(|key| holding.contains(key));
+        planned
+            .into_iter()
+            .filter(|action| {
+                !self
+                    .applied
+                    .contains(&(action.rule, action.torrent.torrent_id))
+            })
+            .collect()
+    }
+
+    /// Keeps `action` from firing again while its rule keeps matching.
+    pub fn mark_applied(&mut self, action: &PlannedAction) {
+        self.applied
+            .insert((action.rule, action.torrent.torrent_id));
+    }
+
+    /// Evaluates `snapshot` and carries out whatever is due, unless this is
+    /// a dry run.
+    pub fn run(&mut self, client: &TransmissionClient, snapshot: &Snapshot) -> Vec<RuleAudit> {
+        self.run_with(snapshot, |planned| {
+            planned.action.apply(client, &planned.torrent)
+        })
+    }
+
+    fn run_with(
+        &mut self,
+        snapshot: &Snapshot,
+        mut apply: impl FnMut(&PlannedAction) -> RpcResult<()>,
+    ) -> Vec<RuleAudit> {
+        self.evaluate(snapshot)
+            .into_iter()
+            .map(|planned| {
+                let (result, retry) = if self.dry_run {
+                    (AuditResult::DryRun, false)
+                } else {
+                    match apply(&planned) {
+                        Ok(()) => (AuditResult::Applied, false),
+                        Err(err) => (
+                            AuditResult::Failed(err.to_string()),
+                            err.is_connection_failure(),
+                        ),
+                    }
+                };
+                if !retry {
+                    self.mark_applied(&planned);
+                }
+                RuleAudit {
+                    at: SystemTime::now(),
+                    rule: self.rules[planned.rule].name.clone(),
+                    profile: self.profile.clone(),
+                    torrent: planned.torrent.name.clone(),
+                    action: planned.action.describe(),
+                    result,
+                }
+            })
+            .collect()
+    }
+}
+
+/// One-line summary of a condition for the rules overlay.
+pub fn describe_condition(when: &RuleCondition) -> String {
+    let mut parts = Vec::new();
+    if let Some(status) = &when.status {
+        parts.push(format!("status {status}"));
+    }
+    if let Some(name) = &when.name {
+        parts.push(format!("name ~ {name}"));
+    }
+    if let Some(label) = &when.label {
+        parts.push(format!("label {label}"));
+    }
+    if let Some(tracker) = &when.tracker {
+        parts.push(format!("tracker ~ {tracker}"));
+    }
+    if let Some(min) = when.min_ratio {
+        parts.push(format!("ratio ≥ {min}"));
+    }
+    if let Some(max) = when.max_ratio {
+        parts.push(format!("ratio ≤ {max}"));
+    }
+    if let Some(min) = when.min_seeding_time {
+        parts.push(format!("seeding ≥ {:.1}h", min as f64 / 3600.0));
+    }
+    if let Some(min) = when.min_size {
+        parts.push(format!("size ≥ {}", format_bytes(min).trim()));
+    }
+    if let Some(max) = when.max_size {
+        parts.push(format!("size ≤ {}", format_bytes(max).trim()));
+    }
+    match when.error {
+        Some(true) => parts.push("has error".to_string()),
+        Some(false) => parts.push("no error".to_string()),
+        None => {}
+    }
+    if parts.is_empty() {
+        "every torrent".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rpc::TransmissionError,
+        transitions::{self, tests::snapshot},
+    };
+
+    /// A finished, seeding torrent with one tracker.
+    fn torrent(id: i64, name: &str) -> TorrentSummary {
+        TorrentSummary {
+            status: "seeding".to_string(),
+            percent_done: 1.0,
+            upload_ratio: 1.0,
+            left_until_done: 0,
+            trackers: vec!["https://tracker.example.org/announce".to_string()],
+            ..transitions::tests::torrent(id, name)
+        }
+    }
+
+    fn rule(name: &str, when: RuleCondition, action: RuleAction) -> Rule {
+        Rule {
+            name: name.to_string(),
+            profile: None,
+            when,
+            action,
+        }
+    }
+
+    #[test]
+    fn condition_fields_must_all_match() {
+        let mut seed = torrent(1, "Debian ISO");
+        seed.upload_ratio = 2.5;
+        seed.seconds_seeding = 7_200;
+        seed.labels = vec!["Linux".to_string()];
+        let when = RuleCondition {
+            status: Some("seeding".to_string()),
+            name: Some("debian".to_string()),
+            label: Some("linux".to_string()),
+            tracker: Some("EXAMPLE.org".to_string()),
+            min_ratio: Some(2.0),
+            min_seeding_time: Some(3_600),
+            max_size: Some(1_000),
+            error: Some(false),
+            ..RuleCondition::default()
+        };
+        assert!(when.matches(&seed));
+
+        let mut other = seed.clone();
+        other.seconds_seeding = 60;
+        assert!(!when.matches(&other));
+        let mut other = seed.clone();
+        other.trackers = vec!["udp://elsewhere.net:6969".to_string()];
+        assert!(!when.matches(&other));
+        let mut other = seed.clone();
+        other.error = Some("tracker gave HTTP 404".to_string());
+        assert!(!when.matches(&other));
+        assert!(RuleCondition::default().matches(&other));
+    }
+
+    #[test]
+    fn unknown_ratios_never_satisfy_ratio_bounds() {
+        let mut seed = torrent(1, "a");
+        seed.upload_ratio = -1.0;
+        let at_least = RuleCondition {
+            min_ratio: Some(0.5),
+            ..RuleCondition::default()
+        };
+        let at_most = RuleCondition {
+            max_ratio: Some(0.5),
+            ..RuleCondition::default()
+        };
+        assert!(!at_least.matches(&seed));
+        assert!(!at_most.matches(&seed));
+    }
+
+    #[test]
+    fn plan_skips_satisfied_actions_and_stops_after_a_removal() {
+        let mut stopped = torrent(1, "stopped");
+        stopped.status = "stopped".to_string();
+        let labelled = {
+            let mut t = torrent(2, "labelled");
+            t.labels = vec!["done".to_string()];
+            t
+        };
+        let rules = vec![
+            rule("stop", RuleCondition::default(), RuleAction::Stop),
+            rule(
+                "label",
+                RuleCondition::default(),
+                RuleAction::SetLabel("done".to_string()),
+            ),
+            rule(
+                "purge",
+                RuleCondition {
+                    name: Some("fresh".to_string()),
+                    ..RuleCondition::default()
+                },
+                RuleAction::Remove,
+            ),
+            rule("after purge", RuleCondition::default(), RuleAction::Stop),
+        ];
+        let snap = snapshot(vec![stopped, labelled, torrent(3, "fresh")]);
+        let planned: Vec<(usize, i64)> = plan(&rules, "default", &snap)
+            .iter()
+            .map(|action| (action.rule, action.torrent.torrent_id))
+            .collect();
+        assert_eq!(planned, [(1, 1), (0, 2), (3, 2), (0, 3), (1, 3), (2, 3)]);
+    }
+
+    #[test]
+    fn rules_for_another_profile_are_ignored() {
+        let mut only_seedbox = rule("stop", RuleCondition::default(), RuleAction::Stop);
+        only_seedbox.profile = Some("seedbox".to_string());
+        let snap = snapshot(vec![torrent(1, "a")]);
+        assert!(plan(&[only_seedbox.clone()], "default", &snap).is_empty());
+        assert_eq!(plan(&[only_seedbox], "seedbox", &snap).len(), 1);
+    }
+
+    /// Evaluates `snapshot` as if every due action went through.
+    fn fire(engine: &mut RuleEngine, snapshot: &Snapshot) -> usize {
+        let due = engine.evaluate(snapshot);
+        for action in &due {
+            engine.mark_applied(action);
+        }
+        due.len()
+    }
+
+    #[test]
+    fn engine_fires_once_until_the_condition_stops_holding() {
+        let limit = rule(
+            "throttle big",
+            RuleCondition {
+                min_size: Some(500),
+                ..RuleCondition::default()
+            },
+            RuleAction::SpeedLimit {
+                download: None,
+                upload: Some(50),
+            },
+        );
+        let mut engine = RuleEngine::new(vec![limit], "default", false);
+        let big = torrent(1, "big");
+        assert_eq!(fire(&mut engine, &snapshot(vec![big.clone()])), 1);
+        assert_eq!(fire(&mut engine, &snapshot(vec![big.clone()])), 0);
+
+        let mut shrunk = big.clone();
+        shrunk.size_when_done = 100;
+        assert_eq!(fire(&mut engine, &snapshot(vec![shrunk])), 0);
+        assert_eq!(fire(&mut engine, &snapshot(vec![big])), 1);
+    }
+
+    #[test]
+    fn actions_that_did_not_go_through_are_due_again() {
+        let stop = rule("stop all", RuleCondition::default(), RuleAction::Stop);
+        let mut engine = RuleEngine::new(vec![stop], "default", false);
+        let snap = snapshot(vec![torrent(1, "a")]);
+        assert_eq!(engine.evaluate(&snap).len(), 1);
+        assert_eq!(engine.evaluate(&snap).len(), 1);
+        assert_eq!(fire(&mut engine, &snap), 1);
+        assert!(engine.evaluate(&snap).is_empty());
+    }
+
+    #[test]
+    fn rejected_actions_are_reported_once_and_unreachable_daemons_retried() {
+        let stop = rule("stop all", RuleCondition::default(), RuleAction::Stop);
+        let mut engine = RuleEngine::new(vec![stop], "default", false);
+        let snap = snapshot(vec![torrent(1, "a")]);
+        let offline = engine.run_with(&snap, |_| Err(TransmissionError::Offline));
+        assert!(matches!(offline[0].result, AuditResult::Failed(_)));
+        let rejected = |_: &PlannedAction| {
+            Err(TransmissionError::Rpc {
+                code: 0,
+                message: "permission denied".to_string(),
+                context: String::new(),
+            })
+        };
+        let audits = engine.run_with(&snap, rejected);
+        assert_eq!(audits.len(), 1);
+        assert!(
+            matches!(&audits[0].result, AuditResult::Failed(reason) if reason.contains("permission denied"))
+        );
+        for _ in 0..3 {
+            assert!(engine.run_with(&snap, rejected).is_empty());
+        }
+        // Once the rule stops matching and matches again it is tried anew.
+        assert!(engine.run_with(&snapshot(Vec::new()), rejected).is_empty());
+        assert_eq!(engine.run_with(&snap, rejected).len(), 1);
+    }
+
+    #[test]
+    fn labels_already_present_in_another_case_satisfy_set_label() {
+        let mut seed = torrent(1, "a");
+        seed.labels = vec!["Done".to_string()];
+        assert!(RuleAction::SetLabel("done".to_string()).satisfied_by(&seed));
+        assert!(!RuleAction::SetLabel("old".to_string()).satisfied_by(&seed));
+    }
+
+    #[test]
+    fn satisfied_actions_do_not_refire_after_manual_changes() {
+        let stop = rule(
+            "stop seeded",
+            RuleCondition {
+                min_ratio: Some(2.0),
+                ..RuleCondition::default()
+            },
+            RuleAction::Stop,
+        );
+        let mut engine = RuleEngine::new(vec![stop], "default", false);
+        let mut seed = torrent(1, "a");
+        seed.upload_ratio = 3.0;
+        assert_eq!(fire(&mut engine, &snapshot(vec![seed.clone()])), 1);
+        seed.status = "stopped".to_string();
+        assert_eq!(fire(&mut engine, &snapshot(vec![seed.clone()])), 0);
+        // Resumed by hand while the ratio still qualifies: left alone.
+        seed.status = "seeding".to_string();
+        assert_eq!(fire(&mut engine, &snapshot(vec![seed])), 0);
+    }
+
+    #[test]
+    fn conditions_reject_unknown_keys() {
+        let parsed: Result<RuleCondition, _> = toml::from_str("min_ratoi = 2.0");
+        assert!(parsed.is_err());
+        let parsed: RuleCondition =
+            toml::from_str("status = \"seeding\"\nmin_ratio = 2.0").unwrap();
+        assert_eq!(parsed.min_ratio, Some(2.0));
+    }
+}
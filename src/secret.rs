@@ -0,0 +1,148 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+/// Service name used for system keyring entries; the account is the profile name.
+const KEYRING_SERVICE: &str = "transmission-tui";
+
+/// A credential that never shows up in `Debug` output or logs. Call
+/// [`Secret::expose`] at the point where the value is actually sent.
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+/// Where a password comes from, in the order `build_config` consults them
+/// within one configuration layer.
+#[derive(Debug, Clone)]
+pub enum SecretSource {
+    Inline(Secret),
+    Command(String),
+    File(String),
+    Keyring { account: String },
+}
+
+impl SecretSource {
+    pub fn resolve(&self) -> Result<Secret> {
+        match self {
+            SecretSource::Inline(secret) => Ok(secret.clone()),
+            SecretSource::Command(command) => run_password_command(command),
+            SecretSource::File(path) => read_password_file(Path::new(path)),
+            SecretSource::Keyring { account } => keyring_lookup(account),
+        }
+    }
+}
+
+/// Runs `command` through the shell and uses the first line of its stdout, so
+/// `pass show seedbox` works even when the entry has trailing metadata lines.
+/// Its stderr is captured rather than inherited, since the TUI may own the
+/// terminal, and its last line is included when the command fails.
+fn run_password_command(command: &str) -> Result<Secret> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .with_context(|| format!("failed to run password_command `{command}`"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        match stderr.lines().rev().find(|line| !line.trim().is_empty()) {
+            Some(line) => bail!(
+                "password_command `{command}` exited with {}: {}",
+                output.status,
+                line.trim()
+            ),
+            None => bail!("password_command `{command}` exited with {}", output.status),
+        }
+    }
+    let stdout =
+        String::from_utf8(output.stdout).context("password_command printed invalid UTF-8")?;
+    let password = stdout.lines().next().unwrap_or_default();
+    if password.is_empty() {
+        bail!("password_command `{command}` printed nothing");
+    }
+    Ok(Secret::new(password))
+}
+
+/// Reads a password file, dropping the trailing newline editors tend to add.
+fn read_password_file(path: &Path) -> Result<Secret> {
+    let path = expand_home(path);
+    let contents = fs::read_to_string(&path)
+        .with_context(|| format!("failed to read password_file {}", path.display()))?;
+    let password = contents.trim_end_matches(['\r', '\n']);
+    if password.is_empty() {
+        bail!("password_file {} is empty", path.display());
+    }
+    Ok(Secret::new(password))
+}
+
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(feature = "keyring")]
+fn keyring_lookup(account: &str) -> Result<Secret> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, account)
+        .with_context(|| format!("failed to open keyring entry for '{account}'"))?;
+    let password = entry.get_password().with_context(|| {
+        format!("no keyring password for service '{KEYRING_SERVICE}', account '{account}'")
+    })?;
+    Ok(Secret::new(password))
+}
+
+#[cfg(not(feature = "keyring"))]
+fn keyring_lookup(account: &str) -> Result<Secret> {
+    bail!(
+        "keyring lookup for service '{KEYRING_SERVICE}', account '{account}' requested, \
+         but this build lacks the `keyring` feature"
+    )
+}
+
+// The password commands below run through `sh`.
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn password_command_uses_the_first_stdout_line() {
+        let secret = run_password_command("printf 'hunter2\\nurl: example\\n'").unwrap();
+        assert_eq!(secret.expose(), "hunter2");
+    }
+
+    #[test]
+    fn failing_password_command_reports_its_stderr() {
+        let err = run_password_command("echo 'gpg: decryption failed' >&2; exit 2").unwrap_err();
+        let message = err.to_string();
+        assert!(message.ends_with(": gpg: decryption failed"), "{message}");
+    }
+}
@@ -0,0 +1,304 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use anyhow::{bail, Context, Result};
+use ratatui::style::{Color, Modifier, Style};
+
+use crate::connection::ConnectionState;
+
+/// Names accepted by `theme` / `--theme`.
+pub const BUILTIN_THEMES: [&str; 5] = ["dark", "light", "solarized", "high-contrast", "monochrome"];
+
+/// Every style the UI draws with. Slots are named after what they mark, not
+/// after a color, so a theme can restyle them freely.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub info: Style,
+    pub success: Style,
+    pub warning: Style,
+    pub error: Style,
+    pub downloading: Style,
+    pub seeding: Style,
+    pub stopped: Style,
+    pub checking: Style,
+    /// Waiting in the download, seed or check queue.
+    pub queued: Style,
+    /// Status cell of a torrent that reports an error.
+    pub errored: Style,
+    /// Highlighted row or field.
+    pub selection: Style,
+    pub border: Style,
+    /// Hints, disabled entries and secondary text.
+    pub muted: Style,
+    /// Download rates; also the download series of any graph.
+    pub download: Style,
+    /// Upload rates; also the upload series of any graph.
+    pub upload: Style,
+    /// Chars of a fuzzy match, in completions and filtered names.
+    pub matched: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+fn fg(color: Color) -> Style {
+    Style::default().fg(color)
+}
+
+fn bold(color: Color) -> Style {
+    fg(color).add_modifier(Modifier::BOLD)
+}
+
+fn with(modifier: Modifier) -> Style {
+    Style::default().add_modifier(modifier)
+}
+
+impl Theme {
+    /// The original palette, for dark terminals.
+    pub fn dark() -> Self {
+        Self {
+            info: fg(Color::Blue),
+            success: fg(Color::Green),
+            warning: fg(Color::Yellow),
+            error: bold(Color::Red),
+            downloading: fg(Color::Cyan),
+            seeding: fg(Color::Green),
+            stopped: fg(Color::DarkGray),
+            checking: fg(Color::Magenta),
+            queued: fg(Color::Blue),
+            errored: fg(Color::Red),
+            selection: fg(Color::Yellow),
+            border: Style::default(),
+            muted: fg(Color::DarkGray),
+            download: fg(Color::Cyan),
+            upload: fg(Color::Magenta),
+            matched: bold(Color::LightYellow),
+        }
+    }
+
+    /// Darker 256-color shades that stay readable on white backgrounds.
+    pub fn light() -> Self {
+        Self {
+            info: fg(Color::Indexed(25)),
+            success: fg(Color::Indexed(28)),
+            warning: fg(Color::Indexed(130)),
+            error: bold(Color::Indexed(160)),
+            downloading: fg(Color::Indexed(25)),
+            seeding: fg(Color::Indexed(28)),
+            stopped: fg(Color::Indexed(244)),
+            checking: fg(Color::Indexed(90)),
+            queued: fg(Color::Indexed(94)),
+            errored: fg(Color::Indexed(160)),
+            selection: Style::default().fg(Color::Black).bg(Color::Indexed(153)),
+            border: fg(Color::Indexed(245)),
+            muted: fg(Color::Indexed(244)),
+            download: fg(Color::Indexed(25)),
+            upload: fg(Color::Indexed(90)),
+            matched: bold(Color::Indexed(166)),
+        }
+    }
+
+    /// Ethan Schoonover's Solarized accents; works on either Solarized
+    /// background.
+    pub fn solarized() -> Self {
+        let yellow = Color::Rgb(0xb5, 0x89, 0x00);
+        let orange = Color::Rgb(0xcb, 0x4b, 0x16);
+        let red = Color::Rgb(0xdc, 0x32, 0x2f);
+        let magenta = Color::Rgb(0xd3, 0x36, 0x82);
+        let violet = Color::Rgb(0x6c, 0x71, 0xc4);
+        let blue = Color::Rgb(0x26, 0x8b, 0xd2);
+        let cyan = Color::Rgb(0x2a, 0xa1, 0x98);
+        let green = Color::Rgb(0x85, 0x99, 0x00);
+        let base01 = Color::Rgb(0x58, 0x6e, 0x75);
+        Self {
+            info: fg(blue),
+            success: fg(green),
+            warning: fg(orange),
+            error: bold(red),
+            downloading: fg(blue),
+            seeding: fg(green),
+            stopped: fg(base01),
+            checking: fg(violet),
+            queued: fg(cyan),
+            errored: fg(red),
+            selection: bold(yellow),
+            border: fg(base01),
+            muted: fg(base01),
+            download: fg(cyan),
+            upload: fg(magenta),
+            matched: bold(orange),
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            info: bold(Color::LightCyan),
+            success: bold(Color::LightGreen),
+            warning: bold(Color::LightYellow),
+            error: bold(Color::LightRed),
+            downloading: fg(Color::LightCyan),
+            seeding: fg(Color::LightGreen),
+            stopped: fg(Color::White),
+            checking: fg(Color::LightMagenta),
+            queued: fg(Color::LightBlue),
+            errored: bold(Color::LightRed),
+            selection: Style::default()
+                .fg(Color::Black)
+                .bg(Color::LightYellow)
+                .add_modifier(Modifier::BOLD),
+            border: fg(Color::White),
+            muted: fg(Color::Gray),
+            download: bold(Color::LightCyan),
+            upload: bold(Color::LightMagenta),
+            matched: bold(Color::LightYellow).add_modifier(Modifier::UNDERLINED),
+        }
+    }
+
+    /// Attributes only; used whenever `NO_COLOR` is set.
+    pub fn monochrome() -> Self {
+        Self {
+            info: Style::default(),
+            success: Style::default(),
+            warning: with(Modifier::BOLD),
+            error: with(Modifier::BOLD | Modifier::UNDERLINED),
+            downloading: with(Modifier::BOLD),
+            seeding: Style::default(),
+            stopped: with(Modifier::DIM),
+            checking: with(Modifier::ITALIC),
+            queued: with(Modifier::DIM),
+            errored: with(Modifier::BOLD | Modifier::UNDERLINED),
+            selection: with(Modifier::REVERSED),
+            border: Style::default(),
+            muted: with(Modifier::DIM),
+            download: Style::default(),
+            upload: Style::default(),
+            matched: with(Modifier::BOLD | Modifier::UNDERLINED),
+        }
+    }
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "solarized" => Some(Self::solarized()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "monochrome" => Some(Self::monochrome()),
+            _ => None,
+        }
+    }
+
+    /// Starts from the built-in `name` and replaces the slots in `overrides`,
+    /// each given as a style spec such as `"bold red"` or `"black on #b58900"`.
+    /// With `no_color` the monochrome theme is used and overrides keep only
+    /// their attributes.
+    pub fn resolve(
+        name: &str,
+        overrides: &BTreeMap<String, String>,
+        no_color: bool,
+    ) -> Result<Self> {
+        let base = Self::builtin(name).with_context(|| {
+            format!(
+                "unknown theme '{name}' (expected one of {})",
+                BUILTIN_THEMES.join(", ")
+            )
+        })?;
+        let mut theme = if no_color { Self::monochrome() } else { base };
+        for (slot, spec) in overrides {
+            let mut style =
+                parse_style(spec).with_context(|| format!("theme.{slot} = \"{spec}\""))?;
+            if no_color {
+                style.fg = None;
+                style.bg = None;
+            }
+            *theme
+                .slot_mut(slot)
+                .with_context(|| format!("unknown theme slot `{slot}`"))? = style;
+        }
+        Ok(theme)
+    }
+
+    fn slot_mut(&mut self, slot: &str) -> Option<&mut Style> {
+        Some(match slot {
+            "info" => &mut self.info,
+            "success" => &mut self.success,
+            "warning" => &mut self.warning,
+            "error" => &mut self.error,
+            "downloading" => &mut self.downloading,
+            "seeding" => &mut self.seeding,
+            "stopped" => &mut self.stopped,
+            "checking" => &mut self.checking,
+            "queued" => &mut self.queued,
+            "errored" => &mut self.errored,
+            "selection" => &mut self.selection,
+            "border" => &mut self.border,
+            "muted" => &mut self.muted,
+            "download" => &mut self.download,
+            "upload" => &mut self.upload,
+            "matched" => &mut self.matched,
+            _ => return None,
+        })
+    }
+
+    /// Style for a torrent's status cell; errors win over the status.
+    pub fn torrent_status(&self, status: &str, has_error: bool) -> Style {
+        if has_error {
+            return self.errored;
+        }
+        match status {
+            "downloading" => self.downloading,
+            "seeding" => self.seeding,
+            "stopped" => self.stopped,
+            "checking" => self.checking,
+            "download-wait" | "seed-wait" | "check-wait" => self.queued,
+            _ => Style::default(),
+        }
+    }
+
+    pub fn connection(&self, state: ConnectionState) -> Style {
+        match state {
+            ConnectionState::Connecting => self.info,
+            ConnectionState::Online => self.success,
+            ConnectionState::Degraded => self.warning,
+            ConnectionState::Offline => self.error,
+        }
+    }
+}
+
+/// Parses `[modifiers…] [color] [on color]`, e.g. `bold yellow`,
+/// `black on light-blue`, `#268bd2`, `reversed`.
+pub fn parse_style(spec: &str) -> Result<Style> {
+    let mut style = Style::default();
+    let mut words = spec.split_whitespace();
+    while let Some(word) = words.next() {
+        let modifier = match word.to_ascii_lowercase().as_str() {
+            "bold" => Some(Modifier::BOLD),
+            "dim" => Some(Modifier::DIM),
+            "italic" => Some(Modifier::ITALIC),
+            "underlined" | "underline" => Some(Modifier::UNDERLINED),
+            "reversed" | "reverse" => Some(Modifier::REVERSED),
+            "crossed-out" | "strikethrough" => Some(Modifier::CROSSED_OUT),
+            _ => None,
+        };
+        if let Some(modifier) = modifier {
+            style = style.add_modifier(modifier);
+        } else if word.eq_ignore_ascii_case("on") {
+            let color = words.next().context("`on` needs a background color")?;
+            style = style.bg(parse_color(color)?);
+        } else {
+            style = style.fg(parse_color(word)?);
+        }
+    }
+    Ok(style)
+}
+
+fn parse_color(text: &str) -> Result<Color> {
+    if text.eq_ignore_ascii_case("default") || text.eq_ignore_ascii_case("reset") {
+        return Ok(Color::Reset);
+    }
+    match Color::from_str(text) {
+        Ok(color) => Ok(color),
+        Err(_) => bail!("unknown color `{text}`"),
+    }
+}
@@ -0,0 +1,292 @@
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    fs::{self, File},
+    io::{BufRead, BufReader, LineWriter, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Keys whose values never leave the process when traffic is written to disk.
+const REDACTED_KEYS: &[&str] = &[
+    "password",
+    "username",
+    "rpc_password",
+    "rpc-password",
+    "rpc_username",
+    "rpc-username",
+    "cookies",
+];
+
+const REDACTED: &str = "<redacted>";
+
+type SharedWriter = Arc<Mutex<LineWriter<File>>>;
+
+/// Record files opened by this process. Every profile gets its own client, so
+/// they share one writer per path instead of truncating each other's capture.
+static RECORD_FILES: Mutex<BTreeMap<PathBuf, SharedWriter>> = Mutex::new(BTreeMap::new());
+
+/// One request/response exchange as written by `--record` and read by `--replay`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrafficEntry {
+    pub at_ms: u64,
+    /// Profile whose client made the request; several share one capture in
+    /// the aggregated view.
+    pub profile: String,
+    pub method: String,
+    /// Request arguments (`arguments` or `params`, depending on the dialect).
+    pub arguments: Value,
+    pub request: Value,
+    #[serde(flatten)]
+    pub outcome: TrafficOutcome,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrafficOutcome {
+    /// Decoded JSON body of a successful HTTP exchange.
+    Response(Value),
+    /// Non-success HTTP status that ended the exchange.
+    Status(u16),
+    /// Transport or decoding failure, stored as its display text.
+    Error(String),
+}
+
+pub struct TrafficRecorder {
+    writer: SharedWriter,
+    profile: String,
+}
+
+impl TrafficRecorder {
+    /// Starts a fresh capture at `path`, replacing any earlier one. Clients
+    /// created later in the same run append to the same capture.
+    pub fn create(path: &Path, profile: &str) -> Result<Self> {
+        let profile = profile.to_string();
+        let mut files = RECORD_FILES.lock().unwrap_or_else(|err| err.into_inner());
+        if let Some(writer) = files.get(path) {
+            return Ok(Self {
+                writer: Arc::clone(writer),
+                profile,
+            });
+        }
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        let file = File::create(path)
+            .with_context(|| format!("failed to open record file {}", path.display()))?;
+        let writer = Arc::new(Mutex::new(LineWriter::new(file)));
+        files.insert(path.to_path_buf(), Arc::clone(&writer));
+        Ok(Self { writer, profile })
+    }
+
+    pub fn record(&self, request: &Value, outcome: TrafficOutcome) {
+        let mut request = request.clone();
+        redact(&mut request);
+        let outcome = match outcome {
+            TrafficOutcome::Response(mut body) => {
+                redact(&mut body);
+                TrafficOutcome::Response(body)
+            }
+            other => other,
+        };
+        let entry = TrafficEntry {
+            at_ms: now_ms(),
+            profile: self.profile.clone(),
+            method: request_method(&request),
+            arguments: request_arguments(&request),
+            request,
+            outcome,
+        };
+        let line = match serde_json::to_string(&entry) {
+            Ok(line) => line,
+            Err(err) => {
+                warn!("failed to encode recorded rpc exchange: {err}");
+                return;
+            }
+        };
+        if let Ok(mut writer) = self.writer.lock() {
+            if let Err(err) = writeln!(writer, "{line}") {
+                warn!("failed to write recorded rpc exchange: {err}");
+            }
+        }
+    }
+}
+
+/// Serves one profile's recorded responses in order per RPC method and
+/// arguments, so calls that share a method but ask for different fields (the
+/// handshake, snapshot and preferences `session-get`s) each get their own
+/// replies. Once a request's queue is drained the last response keeps being
+/// served so the UI settles on the final recorded state instead of erroring
+/// on every poll.
+pub struct TrafficReplay {
+    state: Mutex<ReplayState>,
+}
+
+/// Method name and serialized arguments of a request.
+type ReplayKey = (String, String);
+
+#[derive(Default)]
+struct ReplayState {
+    pending: HashMap<ReplayKey, VecDeque<TrafficOutcome>>,
+    last: HashMap<ReplayKey, TrafficOutcome>,
+}
+
+impl TrafficReplay {
+    pub fn open(path: &Path, profile: &str) -> Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("failed to open replay file {}", path.display()))?;
+        let mut state = ReplayState::default();
+        for (idx, line) in BufReader::new(file).lines().enumerate() {
+            let line =
+                line.with_context(|| format!("failed to read replay file {}", path.display()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: TrafficEntry = serde_json::from_str(&line).with_context(|| {
+                format!(
+                    "invalid replay entry on line {} of {}",
+                    idx + 1,
+                    path.display()
+                )
+            })?;
+            if entry.profile != profile {
+                continue;
+            }
+            state
+                .pending
+                .entry((entry.method, entry.arguments.to_string()))
+                .or_default()
+                .push_back(entry.outcome);
+        }
+        Ok(Self {
+            state: Mutex::new(state),
+        })
+    }
+
+    pub fn next(&self, request: &Value) -> Option<TrafficOutcome> {
+        let mut arguments = request_arguments(request);
+        redact(&mut arguments);
+        let key = (request_method(request), arguments.to_string());
+        let mut state = self.state.lock().ok()?;
+        if let Some(outcome) = state.pending.get_mut(&key).and_then(VecDeque::pop_front) {
+            state.last.insert(key, outcome.clone());
+            return Some(outcome);
+        }
+        state.last.get(&key).cloned()
+    }
+}
+
+fn request_method(request: &Value) -> String {
+    request
+        .get("method")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+fn request_arguments(request: &Value) -> Value {
+    request
+        .get("arguments")
+        .or_else(|| request.get("params"))
+        .cloned()
+        .unwrap_or(Value::Null)
+}
+
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, val) in map.iter_mut() {
+                if REDACTED_KEYS.contains(&key.as_str()) {
+                    *val = Value::String(REDACTED.to_string());
+                } else {
+                    redact(val);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn recording_replaces_an_old_capture_once_per_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("capture.jsonl");
+        fs::write(&path, "stale\n").unwrap();
+        let first = TrafficRecorder::create(&path, "default").unwrap();
+        first.record(
+            &json!({"method": "session-get"}),
+            TrafficOutcome::Status(409),
+        );
+        let second = TrafficRecorder::create(&path, "seedbox").unwrap();
+        second.record(
+            &json!({"method": "torrent-get", "arguments": {"password": "hunter2"}}),
+            TrafficOutcome::Status(401),
+        );
+        let text = fs::read_to_string(&path).unwrap();
+        let methods: Vec<String> = text
+            .lines()
+            .map(|line| serde_json::from_str::<TrafficEntry>(line).unwrap().method)
+            .collect();
+        assert_eq!(methods, ["session-get", "torrent-get"]);
+        assert!(!text.contains("hunter2"));
+    }
+
+    #[test]
+    fn replay_matches_requests_by_arguments_and_profile() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("capture.jsonl");
+        let session_get =
+            |fields: Value| json!({"method": "session-get", "arguments": {"fields": fields}});
+        let body = |key: &str, n: u64| {
+            TrafficOutcome::Response(json!({"result": "success", "arguments": {key: n}}))
+        };
+        let home = TrafficRecorder::create(&path, "home").unwrap();
+        let seedbox = TrafficRecorder::create(&path, "seedbox").unwrap();
+        home.record(
+            &session_get(json!(["rpc-version"])),
+            body("rpc-version", 17),
+        );
+        home.record(&session_get(json!(["version"])), body("version", 1));
+        seedbox.record(&session_get(json!(["version"])), body("version", 99));
+        home.record(
+            &session_get(json!(["speed-limit-down"])),
+            body("speed-limit-down", 100),
+        );
+        home.record(&session_get(json!(["version"])), body("version", 2));
+
+        let replay = TrafficReplay::open(&path, "home").unwrap();
+        let reply = |fields: Value| match replay.next(&session_get(fields)) {
+            Some(TrafficOutcome::Response(body)) => body["arguments"].clone(),
+            other => panic!("unexpected replay outcome {other:?}"),
+        };
+        // Preferences opened before the first poll, unlike in the recording.
+        assert_eq!(
+            reply(json!(["speed-limit-down"])),
+            json!({"speed-limit-down": 100})
+        );
+        assert_eq!(reply(json!(["version"])), json!({"version": 1}));
+        assert_eq!(reply(json!(["rpc-version"])), json!({"rpc-version": 17}));
+        assert_eq!(reply(json!(["version"])), json!({"version": 2}));
+        assert_eq!(reply(json!(["version"])), json!({"version": 2}));
+        assert!(replay.next(&session_get(json!(["encryption"]))).is_none());
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Snapshot, TorrentSummary};
+
+/// A change in one torrent's state between two polls of the same daemon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransitionKind {
+    /// Every wanted piece is now downloaded.
+    Finished,
+    /// The daemon started reporting an error.
+    Errored,
+    /// The torrent is gone from the daemon.
+    Removed,
+    /// The torrent entered the seeding state.
+    Seeding,
+    /// The upload ratio crossed one of the watched targets.
+    RatioReached,
+}
+
+impl TransitionKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            TransitionKind::Finished => "finished",
+            TransitionKind::Errored => "errored",
+            TransitionKind::Removed => "removed",
+            TransitionKind::Seeding => "seeding",
+            TransitionKind::RatioReached => "ratio_reached",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Transition {
+    pub kind: TransitionKind,
+    /// State after the change; the last known state for `Removed`.
+    pub torrent: TorrentSummary,
+    /// The target crossed, for `RatioReached`.
+    pub ratio: Option<f64>,
+}
+
+/// Compares two snapshots of one daemon. Torrents that only appear in
+/// `after` were added, not changed, and produce nothing.
+pub fn diff(before: &Snapshot, after: &Snapshot, ratio_targets: &[f64]) -> Vec<Transition> {
+    let previous: HashMap<i64, &TorrentSummary> = before
+        .torrents
+        .iter()
+        .map(|torrent| (torrent.torrent_id, torrent))
+        .collect();
+    let mut transitions = Vec::new();
+    let mut push = |kind, torrent: &TorrentSummary, ratio| {
+        transitions.push(Transition {
+            kind,
+            torrent: torrent.clone(),
+            ratio,
+        });
+    };
+    for torrent in &after.torrents {
+        let Some(old) = previous.get(&torrent.torrent_id) else {
+            continue;
+        };
+        if old.percent_done < 1.0 && torrent.percent_done >= 1.0 {
+            push(TransitionKind::Finished, torrent, None);
+        }
+        if old.error.is_none() && torrent.error.is_some() {
+            push(TransitionKind::Errored, torrent, None);
+        }
+        if old.status != "seeding" && torrent.status == "seeding" {
+            push(TransitionKind::Seeding, torrent, None);
+        }
+        // Negative ratios are Transmission's "not available" / "infinite".
+        if old.upload_ratio >= 0.0 && torrent.upload_ratio >= 0.0 {
+            for &target in ratio_targets {
+                if old.upload_ratio < target && torrent.upload_ratio >= target {
+                    push(TransitionKind::RatioReached, torrent, Some(target));
+                }
+            }
+        }
+    }
+    let current: HashSet<i64> = after
+        .torrents
+        .iter()
+        .map(|torrent| torrent.torrent_id)
+        .collect();
+    for torrent in &before.torrents {
+        if !current.contains(&torrent.torrent_id) {
+            push(TransitionKind::Removed, torrent, None);
+        }
+    }
+    transitions
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A torrent halfway through its download, for building snapshots.
+    pub(crate) fn torrent(id: i64, name: &str) -> TorrentSummary {
+        TorrentSummary {
+            torrent_id: id,
+            name: name.to_string(),
+            status: "downloading".to_string(),
+            percent_done: 0.5,
+            rate_download: 0,
+            rate_upload: 0,
+            eta: None,
+            upload_ratio: 0.0,
+            size_when_done: 1_000,
+            left_until_done: 500,
+            download_dir: "/data".to_string(),
+            peers_connected: 0,
+            peers_sending: 0,
+            peers_receiving: 0,
+            error: None,
+            peers: Vec::new(),
+            labels: Vec::new(),
+            file_count: None,
+            bandwidth_group: None,
+            seconds_seeding: 0,
+            trackers: Vec::new(),
+            daemon: "default".to_string(),
+            downloaded_ever: 0,
+            uploaded_ever: 0,
+            added_date: 0,
+            done_date: 0,
+            queue_position: 0,
+            hash: String::new(),
+        }
+    }
+
+    pub(crate) fn snapshot(torrents: Vec<TorrentSummary>) -> Snapshot {
+        Snapshot {
+            version: "4.0.0".to_string(),
+            download_speed: 0,
+            upload_speed: 0,
+            active_torrents: 0,
+            paused_torrents: 0,
+            total_torrents: torrents.len() as i64,
+            torrents,
+        }
+    }
+
+    fn kinds(transitions: &[Transition]) -> Vec<(TransitionKind, i64, Option<f64>)> {
+        transitions
+            .iter()
+            .map(|transition| {
+                (
+                    transition.kind,
+                    transition.torrent.torrent_id,
+                    transition.ratio,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn state_edges_fire_once() {
+        let before = torrent(1, "Debian ISO");
+        let mut after = before.clone();
+        after.percent_done = 1.0;
+        after.status = "seeding".to_string();
+        after.error = Some("tracker gave HTTP 404".to_string());
+        let (before, after) = (snapshot(vec![before]), snapshot(vec![after]));
+        assert_eq!(
+            kinds(&diff(&before, &after, &[])),
+            [
+                (TransitionKind::Finished, 1, None),
+                (TransitionKind::Errored, 1, None),
+                (TransitionKind::Seeding, 1, None),
+            ]
+        );
+        assert!(diff(&after, &after, &[]).is_empty());
+        // Leaving the states again is not a transition either.
+        assert!(diff(&after, &before, &[]).is_empty());
+    }
+
+    #[test]
+    fn ratio_targets_fire_when_crossed() {
+        let ratios = |old: f64, new: f64| {
+            let mut before = torrent(1, "Debian ISO");
+            before.upload_ratio = old;
+            let mut after = before.clone();
+            after.upload_ratio = new;
+            kinds(&diff(
+                &snapshot(vec![before]),
+                &snapshot(vec![after]),
+                &[1.0, 2.0],
+            ))
+        };
+        assert_eq!(
+            ratios(0.5, 2.5),
+            [
+                (TransitionKind::RatioReached, 1, Some(1.0)),
+                (TransitionKind::RatioReached, 1, Some(2.0)),
+            ]
+        );
+        assert_eq!(
+            ratios(1.5, 2.0),
+            [(TransitionKind::RatioReached, 1, Some(2.0))]
+        );
+        assert!(ratios(1.0, 1.5).is_empty());
+        // -1 is "not available" and -2 "infinite"; neither crosses anything.
+        assert!(ratios(-1.0, 3.0).is_empty());
+        assert!(ratios(0.5, -2.0).is_empty());
+        assert!(ratios(-2.0, -1.0).is_empty());
+    }
+
+    #[test]
+    fn removed_torrents_fire_and_new_ones_do_not() {
+        let mut added = torrent(3, "Fedora ISO");
+        added.percent_done = 1.0;
+        added.status = "seeding".to_string();
+        added.upload_ratio = 5.0;
+        let before = snapshot(vec![torrent(1, "Debian ISO"), torrent(2, "Arch ISO")]);
+        let after = snapshot(vec![torrent(2, "Arch ISO"), added]);
+        let transitions = diff(&before, &after, &[1.0]);
+        assert_eq!(kinds(&transitions), [(TransitionKind::Removed, 1, None)]);
+        assert_eq!(transitions[0].torrent.name, "Debian ISO");
+    }
+}
@@ -1,66 +1,160 @@
 use std::{
+    collections::{HashMap, VecDeque},
+    fs,
     io::{self, Stdout},
+    path::PathBuf,
     thread,
     time::{Duration, Instant},
 };
 
 use anyhow::{Context, Result};
-use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender};
+use crossbeam_channel::{never, unbounded, Receiver, RecvTimeoutError, Select, SendError, Sender};
 use crossterm::{
     event::{
-        self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyCode, KeyEvent, KeyEventKind,
-        KeyModifiers,
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
+        MouseEventKind,
     },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState, Wrap},
     Frame, Terminal,
 };
 
 use crate::{
-    config::AppConfig,
+    capabilities::Capabilities,
+    cmdline::{self, Command, LabelOp, LimitDirection, SortOrder},
+    columns::{self, Column},
+    config::{AppConfig, RpcConfig},
+    connection::{ConnectionState, ConnectionStatus, ConnectionTracker},
+    export::{self, ExportFormat, EXPORT_COLUMNS},
+    fuzzy,
+    hooks::{HookRunner, HookStatus},
+    keymap::{self, Action, KeyChord, KeyLookup, KeyMode, Keymap},
     model::{
-        format_bytes, format_eta, format_progress, format_speed, PeerSummary, Snapshot,
-        TorrentSummary,
+        format_bytes, format_eta, format_progress, format_speed, merge_snapshots, PeerSummary,
+        Snapshot, TorrentKey, TorrentSummary,
     },
+    notify::Notifier,
     preferences::{DaemonPreferences, EncryptionMode},
-    rpc::{RpcResult, TransmissionClient},
+    rpc::{AddOptions, RpcResult, TransmissionClient, TransmissionError},
+    rules::{self, AuditResult, Rule, RuleAudit, RuleEngine},
+    theme::Theme,
+    transitions,
+    tunnel::{free_local_port, SshTunnel},
+    views::{self, View},
+    watch::{self, WatchFolder, WatchOutcome, WatchProblems},
 };
 
+struct ProfileEntry {
+    name: String,
+    endpoint: String,
+    proxy: Option<String>,
+    /// The daemon shares this machine's filesystem.
+    local: bool,
+}
+
+/// Which daemons the UI is attached to: a single profile or every profile at
+/// once with their torrent lists merged.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum ProfileTarget {
+    Single(usize),
+    All,
+}
+
+/// Filter and selection remembered for a view while another one is active.
+#[derive(Default)]
+struct ProfileView {
+    filter_text: String,
+    daemon_filter: Option<String>,
+    selected: Option<TorrentKey>,
+}
+
+/// Per-daemon state for each worker the UI is attached to.
+struct DaemonState {
+    profile: usize,
+    connection: ConnectionStatus,
+    capabilities: Option<Capabilities>,
+    snapshot: Option<Snapshot>,
+    preferences_cache: Option<DaemonPreferences>,
+}
+
+impl DaemonState {
+    fn new(profile: usize) -> Self {
+        Self {
+            profile,
+            connection: ConnectionStatus::default(),
+            capabilities: None,
+            snapshot: None,
+            preferences_cache: None,
+        }
+    }
+}
+
+/// Profile indices backing a view. The startup profile leads the aggregated
+/// view so that commands without an obvious daemon go there.
+fn target_profiles(target: ProfileTarget, primary: usize, count: usize) -> Vec<usize> {
+    match target {
+        ProfileTarget::Single(index) => vec![index],
+        ProfileTarget::All => std::iter::once(primary)
+            .chain((0..count).filter(|&idx| idx != primary))
+            .collect(),
+    }
+}
+
 type Backend = ratatui::backend::CrosstermBackend<Stdout>;
 
 pub fn run(config: AppConfig) -> Result<()> {
-    let client = TransmissionClient::new(config.rpc.clone())
-        .context("failed to construct Transmission RPC client")?;
-    let mut terminal = setup_terminal()?;
     let (event_tx, event_rx) = unbounded();
-    let (rpc_tx, rpc_rx) = unbounded();
+    let hook_events = event_tx.clone();
+    let hooks = HookRunner::start(config.hooks.clone(), move |run| {
+        if let HookStatus::Failed(reason) = &run.status {
+            let _ = hook_events.send(AppEvent::ProfileStatus(
+                run.profile.clone(),
+                StatusUpdate::error(format!(
+                    "Hook for {} ({}) failed: {reason}",
+                    run.torrent,
+                    run.event.label()
+                )),
+            ));
+        }
+    });
+    let notify_events = event_tx.clone();
+    let notifier = Notifier::new(config.notifications.clone(), move |profile, message| {
+        let _ = notify_events.send(AppEvent::ProfileStatus(
+            profile.to_string(),
+            StatusUpdate::error(message),
+        ));
+    });
+    let mut app = App::new(&config, hooks, notifier);
+    let mut workers = spawn_workers(&config, app.view)?;
+    let mut terminal = setup_terminal(config.mouse)?;
 
     let input_handle = spawn_input_thread(event_tx.clone());
-    let worker_handle = spawn_rpc_worker(client, rpc_rx, event_tx.clone(), config.poll_interval);
 
-    let mut app = App::new(&config);
     app.set_status(StatusUpdate::info("Connecting to transmission…"));
 
-    if rpc_tx.send(RpcCommand::Refresh).is_err() {
+    if !RpcRouter::new(&workers).refresh_all() {
         app.set_status(StatusUpdate::error(
             "RPC worker not available; shutting down",
         ));
     }
 
-    let loop_result = run_loop(&mut terminal, &mut app, event_rx, rpc_tx.clone());
+    let loop_result = run_loop(&mut terminal, &mut app, &config, event_rx, &mut workers);
 
-    drop(rpc_tx);
     drop(event_tx);
 
-    restore_terminal(&mut terminal)?;
+    app.notifier.reset_terminal(terminal.backend_mut())?;
+    restore_terminal(&mut terminal, config.mouse)?;
     input_handle.join().ok();
-    worker_handle.join().ok();
+    for worker in workers {
+        worker.shutdown();
+    }
 
     loop_result
 }
@@ -68,19 +162,46 @@ pub fn run(config: AppConfig) -> Result<()> {
 fn run_loop(
     terminal: &mut Terminal<Backend>,
     app: &mut App,
+    config: &AppConfig,
     events: Receiver<AppEvent>,
-    rpc_tx: Sender<RpcCommand>,
+    workers: &mut Vec<RpcWorker>,
 ) -> Result<()> {
+    let mut router = RpcRouter::new(workers);
     terminal.draw(|f| app.render(f))?;
     loop {
-        let event = match events.recv() {
-            Ok(event) => event,
-            Err(_) => break,
+        let (source, received) = {
+            let mut select = Select::new();
+            select.recv(&events);
+            for worker in workers.iter() {
+                select.recv(&worker.events);
+            }
+            let operation = select.select();
+            match operation.index() {
+                0 => (None, operation.recv(&events)),
+                index => (Some(index - 1), operation.recv(&workers[index - 1].events)),
+            }
         };
-        if app.process_event(event, &rpc_tx)? {
+        let quit = match (source, received) {
+            (None, Ok(event)) => app.process_event(event, &router)?,
+            (None, Err(_)) => break,
+            (Some(daemon), Ok(event)) => {
+                app.apply_daemon_event(daemon, event);
+                false
+            }
+            (Some(daemon), Err(_)) => {
+                workers[daemon].events = never();
+                continue;
+            }
+        };
+        if quit {
             break;
         }
+        if let Some(target) = app.pending_profile_switch.take() {
+            switch_profile(app, config, workers, target);
+            router = RpcRouter::new(workers);
+        }
         terminal.draw(|f| app.render(f))?;
+        app.notifier.flush_terminal(terminal.backend_mut())?;
         if app.should_quit {
             break;
         }
@@ -88,17 +209,104 @@ fn run_loop(
     Ok(())
 }
 
-fn setup_terminal() -> Result<Terminal<Backend>> {
+fn spawn_workers(config: &AppConfig, target: ProfileTarget) -> Result<Vec<RpcWorker>> {
+    target_profiles(target, config.active_profile, config.profiles.len())
+        .into_iter()
+        .map(|index| {
+            let profile = &config.profiles[index];
+            let watch = config
+                .watch
+                .iter()
+                .filter(|folder| folder.profile == profile.name)
+                .cloned()
+                .collect();
+            let rules = config
+                .rules
+                .iter()
+                .filter(|rule| {
+                    rule.profile
+                        .as_ref()
+                        .is_none_or(|name| name == &profile.name)
+                })
+                .cloned()
+                .collect();
+            let rules = RuleEngine::new(rules, profile.name.clone(), config.rules_dry_run);
+            RpcWorker::spawn(&profile.rpc, config.poll_interval, watch, rules)
+                .with_context(|| format!("profile {}", profile.name))
+        })
+        .collect()
+}
+
+/// Replaces the running RPC workers with ones for another view. The old
+/// workers are detached: dropping their command senders ends their loops, and
+/// dropping their event receivers discards anything they were still sending.
+fn switch_profile(
+    app: &mut App,
+    config: &AppConfig,
+    workers: &mut Vec<RpcWorker>,
+    target: ProfileTarget,
+) {
+    match spawn_workers(config, target) {
+        Ok(next) => {
+            *workers = next;
+            app.activate_view(target);
+            if !RpcRouter::new(workers).refresh_all() {
+                app.set_status(StatusUpdate::error("RPC worker not available"));
+            }
+        }
+        Err(err) => {
+            app.set_status(StatusUpdate::error(format!("Cannot switch: {err:#}")));
+        }
+    }
+}
+
+/// Command senders for the running workers, indexed like `App::daemons`.
+struct RpcRouter {
+    senders: Vec<Sender<RpcCommand>>,
+}
+
+impl RpcRouter {
+    fn new(workers: &[RpcWorker]) -> Self {
+        Self {
+            senders: workers
+                .iter()
+                .map(|worker| worker.commands.clone())
+                .collect(),
+        }
+    }
+
+    fn send(&self, daemon: usize, cmd: RpcCommand) -> Result<(), SendError<RpcCommand>> {
+        match self.senders.get(daemon) {
+            Some(tx) => tx.send(cmd),
+            None => Err(SendError(cmd)),
+        }
+    }
+
+    /// Asks every worker to poll now; false if any of them is gone.
+    fn refresh_all(&self) -> bool {
+        self.senders
+            .iter()
+            .all(|tx| tx.send(RpcCommand::Refresh).is_ok())
+    }
+}
+
+fn setup_terminal(mouse: bool) -> Result<Terminal<Backend>> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableBracketedPaste)?;
+    if mouse {
+        execute!(stdout, EnableMouseCapture)?;
+    }
     let backend = ratatui::backend::CrosstermBackend::new(stdout);
     Ok(Terminal::new(backend)?)
 }
 
-fn restore_terminal(terminal: &mut Terminal<Backend>) -> Result<()> {
+fn restore_terminal(terminal: &mut Terminal<Backend>, mouse: bool) -> Result<()> {
     disable_raw_mode()?;
     let mut stdout = io::stdout();
+    if mouse {
+        execute!(stdout, DisableMouseCapture)?;
+    }
     execute!(stdout, DisableBracketedPaste, LeaveAlternateScreen)?;
     terminal.show_cursor()?;
     Ok(())
@@ -136,37 +344,246 @@ fn spawn_input_thread(tx: Sender<AppEvent>) -> thread::JoinHandle<()> {
     })
 }
 
-fn spawn_rpc_worker(
-    client: TransmissionClient,
-    rx: Receiver<RpcCommand>,
-    tx: Sender<AppEvent>,
-    poll_interval: Duration,
-) -> thread::JoinHandle<()> {
-    thread::spawn(move || rpc_worker_loop(client, rx, tx, poll_interval))
+struct RpcWorker {
+    commands: Sender<RpcCommand>,
+    events: Receiver<AppEvent>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl RpcWorker {
+    fn spawn(
+        rpc: &RpcConfig,
+        poll_interval: Duration,
+        watch: Vec<WatchFolder>,
+        rules: RuleEngine,
+    ) -> Result<Self> {
+        // The worker thread owns the tunnel, so the ssh child dies with it.
+        let (rpc, tunnel) = match &rpc.ssh {
+            Some(ssh) => {
+                let port = free_local_port().context("no free local port for the ssh tunnel")?;
+                (
+                    rpc.through_tunnel(port),
+                    Some(SshTunnel::start(ssh.clone(), port)),
+                )
+            }
+            None => (rpc.clone(), None),
+        };
+        let (commands, rx) = unbounded();
+        let (tx, events) = unbounded();
+        // Building the client may run a password command or keyring lookup,
+        // which must not block the UI thread.
+        let handle = thread::spawn(move || match TransmissionClient::new(rpc) {
+            Ok(client) => rpc_worker_loop(client, tunnel, watch, rules, rx, tx, poll_interval),
+            Err(err) => {
+                let err = err.context("failed to construct Transmission RPC client");
+                client_failed_loop(format!("{err:#}"), rx, tx);
+            }
+        });
+        Ok(Self {
+            commands,
+            events,
+            handle: Some(handle),
+        })
+    }
+
+    fn shutdown(mut self) {
+        let (closed, _) = unbounded();
+        self.commands = closed;
+        if let Some(handle) = self.handle.take() {
+            handle.join().ok();
+        }
+    }
 }
 
+/// User commands held back while the daemon is offline; beyond this they are
+/// rejected outright.
+const MAX_QUEUED_COMMANDS: usize = 32;
+
+/// How long the first poll waits for a fresh SSH tunnel to start forwarding.
+const TUNNEL_STARTUP: Duration = Duration::from_secs(5);
+
 fn rpc_worker_loop(
     client: TransmissionClient,
+    tunnel: Option<SshTunnel>,
+    watch: Vec<WatchFolder>,
+    mut rules: RuleEngine,
     rx: Receiver<RpcCommand>,
     tx: Sender<AppEvent>,
     poll_interval: Duration,
 ) {
-    let poll_enabled = poll_interval > Duration::ZERO;
-    if !poll_enabled {
-        while let Ok(cmd) = rx.recv() {
-            handle_command(&client, cmd, &tx);
-        }
-        return;
+    if let Some(tunnel) = &tunnel {
+        tunnel.wait_ready(TUNNEL_STARTUP);
     }
+    let tunnel = tunnel.as_ref();
+    let mut link = ConnectionTracker::new(poll_interval);
+    let mut queued: VecDeque<RpcCommand> = VecDeque::new();
+    let mut watch_problems = WatchProblems::default();
     loop {
-        match rx.recv_timeout(poll_interval) {
+        let received = match link.next_poll_in() {
+            Some(wait) => rx.recv_timeout(wait),
+            None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match received {
+            Ok(RpcCommand::Refresh) | Err(RecvTimeoutError::Timeout) => {
+                let polled = poll_snapshot(&client, tunnel, &tx, &mut link);
+                if !link.is_offline() {
+                    while let Some(cmd) = queued.pop_front() {
+                        handle_command(&client, cmd, &tx);
+                    }
+                    scan_watch_folders(&client, &watch, &mut watch_problems, &tx);
+                }
+                if let Some(snapshot) = polled {
+                    apply_rules(&client, &mut rules, &snapshot, &tx);
+                }
+            }
+            Ok(cmd) if link.is_offline() => defer_command(cmd, &mut queued, &tx),
             Ok(cmd) => handle_command(&client, cmd, &tx),
-            Err(RecvTimeoutError::Timeout) => send_snapshot(&client, &tx),
             Err(RecvTimeoutError::Disconnected) => break,
         }
     }
 }
 
+/// Stands in for a worker whose client could not be built: reports why
+/// once, then answers every command with the same reason.
+fn client_failed_loop(reason: String, rx: Receiver<RpcCommand>, tx: Sender<AppEvent>) {
+    let _ = tx.send(AppEvent::Status(StatusUpdate::error(format!(
+        "Cannot connect: {reason}"
+    ))));
+    for cmd in rx {
+        match cmd {
+            RpcCommand::Refresh => {}
+            RpcCommand::FetchPreferences | RpcCommand::UpdatePreferences(_) => {
+                let _ = tx.send(AppEvent::Preferences(Err(TransmissionError::Offline)));
+            }
+            cmd => {
+                let _ = tx.send(AppEvent::Status(StatusUpdate::error(format!(
+                    "Dropped {}: {reason}",
+                    cmd.label()
+                ))));
+            }
+        }
+    }
+}
+
+fn poll_snapshot(
+    client: &TransmissionClient,
+    tunnel: Option<&SshTunnel>,
+    tx: &Sender<AppEvent>,
+    link: &mut ConnectionTracker,
+) -> Option<Snapshot> {
+    if client.capabilities().is_none() {
+        match client.handshake() {
+            Ok(caps) => {
+                let _ = tx.send(AppEvent::Capabilities(caps));
+            }
+            Err(err) => {
+                link.record_failure(failure_reason(&err, tunnel));
+                let _ = tx.send(AppEvent::Connection(link.status().clone()));
+                let _ = tx.send(AppEvent::Snapshot(Err(err)));
+                return None;
+            }
+        }
+    }
+    let result = client.fetch_snapshot();
+    match &result {
+        Ok(_) => link.record_success(),
+        Err(err) => link.record_failure(failure_reason(err, tunnel)),
+    }
+    let _ = tx.send(AppEvent::Connection(link.status().clone()));
+    let polled = result.as_ref().ok().cloned();
+    let _ = tx.send(AppEvent::Snapshot(result));
+    polled
+}
+
+/// Runs the profile's rules against a fresh poll and refreshes the list if
+/// any of them changed something.
+fn apply_rules(
+    client: &TransmissionClient,
+    rules: &mut RuleEngine,
+    snapshot: &Snapshot,
+    tx: &Sender<AppEvent>,
+) {
+    if rules.is_empty() {
+        return;
+    }
+    let audits = rules.run(client, snapshot);
+    if audits.is_empty() {
+        return;
+    }
+    let changed = audits
+        .iter()
+        .any(|audit| audit.result == AuditResult::Applied);
+    let _ = tx.send(AppEvent::RuleAudit(audits));
+    if changed {
+        send_snapshot(client, tx);
+    }
+}
+
+/// Adds whatever landed in this profile's watch folders since the last poll.
+/// Deferred files are covered by the offline banner, so only results are
+/// reported, and a folder problem only when it first appears or changes.
+fn scan_watch_folders(
+    client: &TransmissionClient,
+    watch: &[WatchFolder],
+    problems: &mut WatchProblems,
+    tx: &Sender<AppEvent>,
+) {
+    let mut added = false;
+    for folder in watch {
+        let scan = watch::scan_with_client(folder, client);
+        for problem in problems.fresh(folder, &scan) {
+            let _ = tx.send(AppEvent::Status(StatusUpdate::warning(format!(
+                "Watch: {problem}"
+            ))));
+        }
+        for outcome in scan.outcomes {
+            let status = match &outcome {
+                WatchOutcome::Added { .. } => {
+                    added = true;
+                    StatusUpdate::success(outcome.message()).toast()
+                }
+                WatchOutcome::Failed { .. } => StatusUpdate::error(outcome.message()),
+                WatchOutcome::Deferred { .. } => continue,
+            };
+            let _ = tx.send(AppEvent::Status(status));
+        }
+    }
+    if added {
+        send_snapshot(client, tx);
+    }
+}
+
+/// A refused connection to a tunnel's local port says little; the reason ssh
+/// last exited usually says more.
+fn failure_reason(err: &TransmissionError, tunnel: Option<&SshTunnel>) -> String {
+    match tunnel.and_then(SshTunnel::last_error) {
+        Some(ssh) => format!("{ssh}; {err}"),
+        None => err.to_string(),
+    }
+}
+
+fn defer_command(cmd: RpcCommand, queued: &mut VecDeque<RpcCommand>, tx: &Sender<AppEvent>) {
+    match cmd {
+        RpcCommand::FetchPreferences | RpcCommand::UpdatePreferences(_) => {
+            let _ = tx.send(AppEvent::Preferences(Err(TransmissionError::Offline)));
+        }
+        cmd if queued.len() < MAX_QUEUED_COMMANDS => {
+            let label = cmd.label();
+            queued.push_back(cmd);
+            let _ = tx.send(AppEvent::Status(StatusUpdate::warning(format!(
+                "Daemon offline; queued {label} ({} pending)",
+                queued.len()
+            ))));
+        }
+        cmd => {
+            let _ = tx.send(AppEvent::Status(StatusUpdate::error(format!(
+                "Daemon offline; dropped {}",
+                cmd.label()
+            ))));
+        }
+    }
+}
+
 fn handle_command(client: &TransmissionClient, cmd: RpcCommand, tx: &Sender<AppEvent>) {
     match cmd {
         RpcCommand::Refresh => send_snapshot(client, tx),
@@ -178,8 +595,19 @@ fn handle_command(client: &TransmissionClient, cmd: RpcCommand, tx: &Sender<AppE
         } => handle_remove(client, id, name, delete_data, tx),
         RpcCommand::ResumeTorrent { id, name } => handle_resume(client, id, name, tx),
         RpcCommand::PauseTorrent { id, name } => handle_pause(client, id, name, tx),
+        RpcCommand::ReannounceTorrent { id, name } => handle_reannounce(client, id, name, tx),
         RpcCommand::FetchPreferences => handle_fetch_preferences(client, tx),
         RpcCommand::UpdatePreferences(prefs) => handle_update_preferences(client, prefs, tx),
+        RpcCommand::MoveTorrent { id, name, location } => {
+            handle_move(client, id, name, location, tx)
+        }
+        RpcCommand::SetSpeedLimit {
+            id,
+            name,
+            direction,
+            limit,
+        } => handle_speed_limit(client, id, name, direction, limit, tx),
+        RpcCommand::SetLabels { id, name, labels } => handle_labels(client, id, name, labels, tx),
     }
 }
 
@@ -196,7 +624,7 @@ fn handle_add(client: &TransmissionClient, magnet: String, tx: &Sender<AppEvent>
         )));
         return;
     }
-    match client.add_magnet(trimmed) {
+    match client.add_magnet(trimmed, &AddOptions::default()) {
         Ok(outcome) => {
             let label = outcome
                 .name
@@ -277,6 +705,98 @@ fn handle_pause(client: &TransmissionClient, id: i64, name: String, tx: &Sender<
     }
 }
 
+fn handle_reannounce(client: &TransmissionClient, id: i64, name: String, tx: &Sender<AppEvent>) {
+    match client.reannounce_torrents(&[id]) {
+        Ok(()) => {
+            let _ = tx.send(AppEvent::Status(StatusUpdate::success(format!(
+                "Reannounced {name}"
+            ))));
+        }
+        Err(err) => {
+            let _ = tx.send(AppEvent::Status(StatusUpdate::error(format!(
+                "Reannounce failed: {err}"
+            ))));
+        }
+    }
+}
+
+fn handle_move(
+    client: &TransmissionClient,
+    id: i64,
+    name: String,
+    location: String,
+    tx: &Sender<AppEvent>,
+) {
+    match client.move_torrents(&[id], &location) {
+        Ok(()) => {
+            let _ = tx.send(AppEvent::Status(StatusUpdate::success(format!(
+                "Moving {name} to {location}"
+            ))));
+            send_snapshot(client, tx);
+        }
+        Err(err) => {
+            let _ = tx.send(AppEvent::Status(StatusUpdate::error(format!(
+                "Move failed: {err}"
+            ))));
+        }
+    }
+}
+
+fn handle_speed_limit(
+    client: &TransmissionClient,
+    id: i64,
+    name: String,
+    direction: LimitDirection,
+    limit: Option<i64>,
+    tx: &Sender<AppEvent>,
+) {
+    let (result, label) = match direction {
+        LimitDirection::Down => (client.set_download_limit(&[id], limit), "Download"),
+        LimitDirection::Up => (client.set_upload_limit(&[id], limit), "Upload"),
+        LimitDirection::Both => (client.set_speed_limits(&[id], limit, limit), "Speed"),
+    };
+    match result {
+        Ok(()) => {
+            let value = limit.map_or("unlimited".to_string(), |kib| format!("{kib} KiB/s"));
+            let _ = tx.send(AppEvent::Status(StatusUpdate::success(format!(
+                "{label} limit for {name}: {value}"
+            ))));
+        }
+        Err(err) => {
+            let _ = tx.send(AppEvent::Status(StatusUpdate::error(format!(
+                "Limit failed: {err}"
+            ))));
+        }
+    }
+}
+
+fn handle_labels(
+    client: &TransmissionClient,
+    id: i64,
+    name: String,
+    labels: Vec<String>,
+    tx: &Sender<AppEvent>,
+) {
+    match client.set_labels(&[id], &labels) {
+        Ok(()) => {
+            let list = if labels.is_empty() {
+                "(none)".to_string()
+            } else {
+                labels.join(", ")
+            };
+            let _ = tx.send(AppEvent::Status(StatusUpdate::success(format!(
+                "Labels for {name}: {list}"
+            ))));
+            send_snapshot(client, tx);
+        }
+        Err(err) => {
+            let _ = tx.send(AppEvent::Status(StatusUpdate::error(format!(
+                "Label change failed: {err}"
+            ))));
+        }
+    }
+}
+
 fn handle_fetch_preferences(client: &TransmissionClient, tx: &Sender<AppEvent>) {
     let result = client.fetch_preferences();
     let _ = tx.send(AppEvent::Preferences(result));
@@ -308,14 +828,22 @@ enum AppEvent {
     Tick,
     Snapshot(RpcResult<Snapshot>),
     Status(StatusUpdate),
+    /// App-level message about a profile, such as a failed hook, tagged by
+    /// profile name rather than by the worker that sent it.
+    ProfileStatus(String, StatusUpdate),
     FocusTorrent(Option<i64>),
     Preferences(RpcResult<DaemonPreferences>),
+    Connection(ConnectionStatus),
+    Capabilities(Capabilities),
+    RuleAudit(Vec<RuleAudit>),
 }
 
 #[derive(Clone)]
 struct StatusUpdate {
     text: String,
     level: StatusLevel,
+    /// Pop up as a toast even below warning level.
+    toast: bool,
 }
 
 impl StatusUpdate {
@@ -323,6 +851,7 @@ impl StatusUpdate {
         Self {
             text: message.into(),
             level: StatusLevel::Info,
+            toast: false,
         }
     }
 
@@ -330,6 +859,7 @@ impl StatusUpdate {
         Self {
             text: message.into(),
             level: StatusLevel::Success,
+            toast: false,
         }
     }
 
@@ -337,6 +867,7 @@ impl StatusUpdate {
         Self {
             text: message.into(),
             level: StatusLevel::Warning,
+            toast: false,
         }
     }
 
@@ -344,8 +875,14 @@ impl StatusUpdate {
         Self {
             text: message.into(),
             level: StatusLevel::Error,
+            toast: false,
         }
     }
+
+    fn toast(mut self) -> Self {
+        self.toast = true;
+        self
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -379,48 +916,218 @@ impl StatusMessage {
     }
 }
 
+/// How long a partly typed key sequence waits for its next key.
+const KEY_SEQUENCE_TIMEOUT: Duration = Duration::from_secs(2);
+/// Completions listed above the command line at once.
+const COMMAND_SUGGESTIONS: usize = 8;
+/// Oldest `:` command lines are dropped beyond this many.
+const COMMAND_HISTORY_LIMIT: usize = 100;
+/// Two left clicks on the same cell within this count as a double click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// Gap between torrent list columns.
+const LIST_COLUMN_SPACING: u16 = 2;
+
+/// Entries kept for the rules overlay.
+const RULE_LOG_LIMIT: usize = 100;
+
 struct App {
+    profiles: Vec<ProfileEntry>,
+    primary_profile: usize,
+    view: ProfileTarget,
+    profile_views: HashMap<ProfileTarget, ProfileView>,
+    pending_profile_switch: Option<ProfileTarget>,
     connection_label: String,
+    daemons: Vec<DaemonState>,
+    /// Merged torrent list across `daemons`, rebuilt on every poll.
     snapshot: Option<Snapshot>,
-    preferences_cache: Option<DaemonPreferences>,
+    preferences_daemon: usize,
     list_state: TableState,
     filtered_indices: Vec<usize>,
     filter_text: String,
     filter_lower: String,
-    pending_focus: Option<i64>,
-    selected_id: Option<i64>,
+    /// Filter by fuzzy match, ranked by score, instead of by substring.
+    fuzzy_filter: bool,
+    /// Matched name chars per snapshot index, while a fuzzy filter is set.
+    name_matches: HashMap<usize, Vec<usize>>,
+    daemon_filter: Option<String>,
+    pending_focus: Option<TorrentKey>,
+    selected_id: Option<TorrentKey>,
     status: Option<StatusMessage>,
     toast: Option<StatusMessage>,
     mode: InputMode,
+    /// Export dialog settings from the last successful export.
+    last_export: Option<ExportState>,
+    hooks: HookRunner,
+    notifier: Notifier,
+    rules: Vec<Rule>,
+    /// Rule actions taken or attempted, newest first.
+    rule_log: VecDeque<RuleAudit>,
     should_quit: bool,
     pending_manual_refresh: bool,
-    delete_armed: bool,
-    delete_armed_until: Option<Instant>,
-    trash_armed: bool,
-    trash_armed_until: Option<Instant>,
+    keymap: Keymap,
+    theme: Theme,
+    columns: Vec<Column>,
+    /// Keys typed so far towards a multi-key binding such as `dd`.
+    pending_keys: Vec<KeyChord>,
+    pending_until: Option<Instant>,
+    /// Clickable regions from the last draw, topmost last.
+    hits: Vec<Hit>,
+    /// Time and cell of the last left click, for double-click detection.
+    last_click: Option<(Instant, u16, u16)>,
+    /// Details pane drawn full height instead of below the list.
+    details_zoomed: bool,
+    peer_focus: Option<PeerFocus>,
+    /// `:` command lines run so far, oldest first.
+    command_history: Vec<String>,
+    /// List order from `:sort`; `None` keeps the daemon's order.
+    sort: Option<(Column, SortOrder)>,
+    views: Vec<View>,
+    /// Where `:view save` appends new views.
+    config_path: Option<PathBuf>,
 }
 
 impl App {
-    fn new(config: &AppConfig) -> Self {
-        Self {
-            connection_label: config.rpc.endpoint(),
+    fn new(config: &AppConfig, hooks: HookRunner, notifier: Notifier) -> Self {
+        let view = if config.aggregate && config.profiles.len() > 1 {
+            ProfileTarget::All
+        } else {
+            ProfileTarget::Single(config.active_profile)
+        };
+        let mut app = Self {
+            profiles: config
+                .profiles
+                .iter()
+                .map(|profile| ProfileEntry {
+                    name: profile.name.clone(),
+                    endpoint: profile.rpc.endpoint(),
+                    proxy: profile.rpc.proxy_label(),
+                    local: profile.rpc.is_local(),
+                })
+                .collect(),
+            primary_profile: config.active_profile,
+            view,
+            profile_views: HashMap::new(),
+            pending_profile_switch: None,
+            connection_label: String::new(),
+            daemons: Vec::new(),
             snapshot: None,
-            preferences_cache: None,
+            preferences_daemon: 0,
             list_state: TableState::default(),
             filtered_indices: Vec::new(),
             filter_text: String::new(),
             filter_lower: String::new(),
+            fuzzy_filter: config.fuzzy_filter,
+            name_matches: HashMap::new(),
+            daemon_filter: None,
             pending_focus: None,
             selected_id: None,
             status: None,
             toast: None,
             mode: InputMode::Normal,
+            last_export: None,
+            hooks,
+            notifier,
+            rules: config.rules.clone(),
+            rule_log: VecDeque::new(),
             should_quit: false,
             pending_manual_refresh: false,
-            delete_armed: false,
-            delete_armed_until: None,
-            trash_armed: false,
-            trash_armed_until: None,
+            keymap: config.keymap.clone(),
+            theme: config.theme.clone(),
+            columns: config.columns.clone(),
+            pending_keys: Vec::new(),
+            pending_until: None,
+            hits: Vec::new(),
+            last_click: None,
+            details_zoomed: false,
+            peer_focus: None,
+            command_history: Vec::new(),
+            sort: None,
+            views: config.views.clone(),
+            config_path: config.config_path.clone(),
+        };
+        app.attach_daemons();
+        app
+    }
+
+    /// Resets per-daemon state for the profiles behind `self.view`, in the
+    /// same order as the workers spawned for it.
+    fn attach_daemons(&mut self) {
+        self.daemons = target_profiles(self.view, self.primary_profile, self.profiles.len())
+            .into_iter()
+            .map(DaemonState::new)
+            .collect();
+        self.connection_label = match self.view {
+            ProfileTarget::Single(index) => self.profiles[index].endpoint.clone(),
+            ProfileTarget::All => format!("All profiles ({})", self.daemons.len()),
+        };
+    }
+
+    /// The configured columns for the current view: Daemon only appears in
+    /// the merged view, where it is added after Name if not configured.
+    fn visible_columns(&self) -> Vec<Column> {
+        let mut columns = self.columns.clone();
+        if !self.aggregated() {
+            columns.retain(|&column| column != Column::Daemon);
+        } else if !columns.contains(&Column::Daemon) {
+            let name = columns.iter().position(|&column| column == Column::Name);
+            columns.insert(name.map_or(0, |idx| idx + 1), Column::Daemon);
+        }
+        columns
+    }
+
+    fn aggregated(&self) -> bool {
+        self.view == ProfileTarget::All
+    }
+
+    fn daemon_name(&self, daemon: usize) -> &str {
+        self.daemons
+            .get(daemon)
+            .map(|state| self.profiles[state.profile].name.as_str())
+            .unwrap_or_default()
+    }
+
+    fn daemon_index(&self, name: &str) -> Option<usize> {
+        (0..self.daemons.len()).find(|&idx| self.daemon_name(idx) == name)
+    }
+
+    /// Daemon that receives commands not tied to a torrent, such as magnet
+    /// adds and preference edits: the daemon filter if one is set, else the
+    /// startup profile.
+    fn target_daemon(&self) -> usize {
+        self.daemon_filter
+            .as_deref()
+            .and_then(|name| self.daemon_index(name))
+            .unwrap_or(0)
+    }
+
+    /// Daemon whose capabilities apply to the current selection.
+    fn focused_daemon(&self) -> usize {
+        self.current_torrent()
+            .and_then(|torrent| self.daemon_index(&torrent.daemon))
+            .unwrap_or_else(|| self.target_daemon())
+    }
+
+    fn focused_capabilities(&self) -> Option<&Capabilities> {
+        self.daemons
+            .get(self.focused_daemon())?
+            .capabilities
+            .as_ref()
+    }
+
+    /// Prefixes worker messages with their daemon when several are shown.
+    fn tag_status(&self, daemon: usize, update: StatusUpdate) -> StatusUpdate {
+        self.tag_profile_status(self.daemon_name(daemon), update)
+    }
+
+    fn tag_profile_status(&self, profile: &str, update: StatusUpdate) -> StatusUpdate {
+        if self.aggregated() {
+            StatusUpdate {
+                text: format!("[{profile}] {}", update.text),
+                ..update
+            }
+        } else {
+            update
         }
     }
 
@@ -433,151 +1140,485 @@ impl App {
                 Constraint::Length(1),
             ])
             .split(frame.size());
+        let mut hits = Vec::new();
         self.render_header(frame, chunks[0]);
-        self.render_body(frame, chunks[1]);
-        self.render_footer(frame, chunks[2]);
+        self.render_body(frame, chunks[1], &mut hits);
+        self.render_footer(frame, chunks[2], &mut hits);
+        self.render_command_suggestions(frame, chunks[2]);
         self.render_toast(frame);
+        let Some(area) = self.overlay_area(frame.size()) else {
+            self.hits = hits;
+            return;
+        };
+        frame.render_widget(Clear, area);
+        hits.push(Hit::new(area, MouseTarget::Overlay));
         match &self.mode {
             InputMode::Prompt(prompt) => {
-                let area = centered_rect(60, 30, frame.size());
                 let block = Block::default()
                     .title(Span::raw(format!(" {} ", prompt.title)))
-                    .borders(Borders::ALL);
-                let text = vec![
+                    .borders(Borders::ALL)
+                    .border_style(self.theme.border);
+                let mut text = vec![
                     Line::from("Enter a magnet URL and press Enter (Esc to cancel)"),
                     Line::from(format!("> {}", prompt.buffer)),
                 ];
+                if self.aggregated() {
+                    text.insert(
+                        1,
+                        Line::from(format!(
+                            "Adds to {}",
+                            self.daemon_name(self.target_daemon())
+                        )),
+                    );
+                }
                 let paragraph = Paragraph::new(text).block(block).wrap(Wrap { trim: true });
-                frame.render_widget(Clear, area);
                 frame.render_widget(paragraph, area);
+                self.render_buttons(
+                    frame,
+                    area,
+                    &[
+                        ("Add", MouseTarget::Key(KeyCode::Enter)),
+                        ("Cancel", MouseTarget::Key(KeyCode::Esc)),
+                    ],
+                    &mut hits,
+                );
             }
             InputMode::Confirm(confirm) => {
-                let area = centered_rect(50, 30, frame.size());
-                let block = Block::default().title(confirm.title).borders(Borders::ALL);
+                let block = Block::default()
+                    .title(confirm.title)
+                    .borders(Borders::ALL)
+                    .border_style(self.theme.border);
                 let text = vec![
                     Line::from(confirm.message.clone()),
                     Line::from(Span::styled(
                         "Press y to confirm, n or Esc to cancel",
-                        Style::default().fg(Color::Yellow),
+                        self.theme.warning,
                     )),
                 ];
                 let paragraph = Paragraph::new(text).block(block).wrap(Wrap { trim: true });
-                frame.render_widget(Clear, area);
                 frame.render_widget(paragraph, area);
+                self.render_buttons(
+                    frame,
+                    area,
+                    &[
+                        ("Yes", MouseTarget::Action(KeyMode::Confirm, Action::Accept)),
+                        ("No", MouseTarget::Action(KeyMode::Confirm, Action::Cancel)),
+                    ],
+                    &mut hits,
+                );
             }
             InputMode::Help => {
-                let area = centered_rect(70, 70, frame.size());
-                frame.render_widget(Clear, area);
                 self.render_help(frame, area);
+                self.render_buttons(
+                    frame,
+                    area,
+                    &[("Close", MouseTarget::Action(KeyMode::Help, Action::Close))],
+                    &mut hits,
+                );
             }
             InputMode::Preferences(state) => {
-                let area = centered_rect(80, 80, frame.size());
-                frame.render_widget(Clear, area);
-                self.render_preferences(frame, area, state);
+                self.render_preferences(frame, area, state, &mut hits);
+                let buttons: &[(&str, MouseTarget)] = match &state.view {
+                    PreferencesView::Ready(_) => &[
+                        (
+                            "Save",
+                            MouseTarget::Action(KeyMode::Preferences, Action::Save),
+                        ),
+                        (
+                            "Close",
+                            MouseTarget::Action(KeyMode::Preferences, Action::Close),
+                        ),
+                    ],
+                    _ => &[
+                        (
+                            "Retry",
+                            MouseTarget::Action(KeyMode::Preferences, Action::Refresh),
+                        ),
+                        (
+                            "Close",
+                            MouseTarget::Action(KeyMode::Preferences, Action::Close),
+                        ),
+                    ],
+                };
+                self.render_buttons(frame, area, buttons, &mut hits);
             }
-            _ => {}
-        }
+            InputMode::Profiles(picker) => {
+                self.render_profiles(frame, area, picker, &mut hits);
+                self.render_buttons(
+                    frame,
+                    area,
+                    &[
+                        (
+                            "Switch",
+                            MouseTarget::Action(KeyMode::Profiles, Action::Select),
+                        ),
+                        (
+                            "Close",
+                            MouseTarget::Action(KeyMode::Profiles, Action::Close),
+                        ),
+                    ],
+                    &mut hits,
+                );
+            }
+            InputMode::Export(state) => {
+                self.render_export(frame, area, state, &mut hits);
+                self.render_buttons(
+                    frame,
+                    area,
+                    &[
+                        ("Export", MouseTarget::Key(KeyCode::Enter)),
+                        ("Cancel", MouseTarget::Key(KeyCode::Esc)),
+                    ],
+                    &mut hits,
+                );
+            }
+            InputMode::Hooks { scroll } => {
+                self.render_hooks(frame, area, *scroll);
+                self.render_buttons(
+                    frame,
+                    area,
+                    &[("Close", MouseTarget::Action(KeyMode::Hooks, Action::Close))],
+                    &mut hits,
+                );
+            }
+            InputMode::Rules { scroll } => {
+                self.render_rules(frame, area, *scroll);
+                self.render_buttons(
+                    frame,
+                    area,
+                    &[("Close", MouseTarget::Action(KeyMode::Rules, Action::Close))],
+                    &mut hits,
+                );
+            }
+            InputMode::Columns(picker) => {
+                self.render_columns(frame, area, picker, &mut hits);
+                self.render_buttons(
+                    frame,
+                    area,
+                    &[
+                        (
+                            "Apply",
+                            MouseTarget::Action(KeyMode::Columns, Action::Accept),
+                        ),
+                        (
+                            "Cancel",
+                            MouseTarget::Action(KeyMode::Columns, Action::Cancel),
+                        ),
+                    ],
+                    &mut hits,
+                );
+            }
+            InputMode::Views(picker) => {
+                self.render_views(frame, area, picker, &mut hits);
+                self.render_buttons(
+                    frame,
+                    area,
+                    &[
+                        ("Apply", MouseTarget::Action(KeyMode::Views, Action::Select)),
+                        (
+                            "Save current",
+                            MouseTarget::Action(KeyMode::Views, Action::SaveView),
+                        ),
+                        ("Close", MouseTarget::Action(KeyMode::Views, Action::Close)),
+                    ],
+                    &mut hits,
+                );
+            }
+            InputMode::Normal | InputMode::Filter { .. } | InputMode::Command(_) => {}
+        }
+        self.hits = hits;
+    }
+
+    /// Where the current mode's dialog is drawn, if it has one.
+    fn overlay_area(&self, screen: Rect) -> Option<Rect> {
+        let (width, height) = match self.mode {
+            InputMode::Normal | InputMode::Filter { .. } | InputMode::Command(_) => return None,
+            InputMode::Prompt(_) => (60, 30),
+            InputMode::Confirm(_) => (50, 30),
+            InputMode::Help => (70, 85),
+            InputMode::Preferences(_) => (80, 80),
+            InputMode::Profiles(_) => (60, 50),
+            InputMode::Export(_) => (60, 80),
+            InputMode::Hooks { .. } | InputMode::Rules { .. } => (80, 70),
+            InputMode::Columns(_) => (60, 80),
+            InputMode::Views(_) => (70, 50),
+        };
+        Some(centered_rect(width, height, screen))
+    }
+
+    /// Draws clickable `[ label ]` buttons on the bottom border of `area`,
+    /// right-aligned.
+    fn render_buttons(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        buttons: &[(&str, MouseTarget)],
+        hits: &mut Vec<Hit>,
+    ) {
+        if area.height < 2 {
+            return;
+        }
+        let y = area.bottom() - 1;
+        let mut x = area.right().saturating_sub(2);
+        for (label, target) in buttons.iter().rev() {
+            let text = format!("[ {label} ]");
+            let width = text.len() as u16;
+            if x < area.x + 2 + width {
+                break;
+            }
+            x -= width;
+            let button = Rect::new(x, y, width, 1);
+            frame.render_widget(
+                Paragraph::new(Span::styled(
+                    text,
+                    Style::default().add_modifier(Modifier::BOLD),
+                )),
+                button,
+            );
+            hits.push(Hit::new(button, target.clone()));
+            x = x.saturating_sub(1);
+        }
     }
 
     fn render_header(&self, frame: &mut Frame, area: Rect) {
         let mut lines = Vec::new();
-        lines.push(Line::from(vec![
-            Span::styled(
-                "Transmission",
-                Style::default().add_modifier(Modifier::BOLD),
-            ),
-            Span::raw("  |  "),
-            Span::raw(&self.connection_label),
-        ]));
-        if let Some(snapshot) = &self.snapshot {
-            lines.push(Line::from(vec![Span::raw(format!(
-                "DL {}  UL {}  | Active {}  Paused {}  Total {}  | Version {}",
-                format_speed(snapshot.download_speed),
-                format_speed(snapshot.upload_speed),
-                snapshot.active_torrents,
-                snapshot.paused_torrents,
-                snapshot.total_torrents,
-                snapshot.version
-            ))]));
+        if self.aggregated() {
+            lines.extend(self.aggregate_header_lines());
         } else {
-            lines.push(Line::from("Waiting for session stats…"));
+            lines.extend(self.single_header_lines());
         }
         if let Some(status) = &self.status {
             lines.push(Line::from(Span::styled(
                 status.text.clone(),
-                status_style(status.level),
+                status_style(&self.theme, status.level),
             )));
         }
         let paragraph = Paragraph::new(lines).block(
             Block::default()
                 .borders(Borders::ALL)
+                .border_style(self.theme.border)
                 .title(Span::raw(" Session ")),
         );
         frame.render_widget(paragraph, area);
     }
 
-    fn render_body(&mut self, frame: &mut Frame, area: Rect) {
+    fn single_header_lines(&self) -> Vec<Line<'static>> {
+        let daemon = &self.daemons[0];
+        let mut lines = vec![Line::from(vec![
+            Span::styled(
+                "Transmission",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  |  "),
+            Span::raw(self.profile_prefix()),
+            Span::raw(self.connection_label.clone()),
+            Span::styled(
+                self.proxy_label()
+                    .map(|label| format!(" ({label})"))
+                    .unwrap_or_default(),
+                self.theme.muted,
+            ),
+            Span::raw("  |  "),
+            Span::styled(
+                daemon.connection.state.label(),
+                self.theme.connection(daemon.connection.state),
+            ),
+        ])];
+        if !matches!(daemon.connection.state, ConnectionState::Online)
+            && daemon.connection.failures > 0
+        {
+            lines.push(Line::from(Span::styled(
+                connection_banner(
+                    &daemon.connection,
+                    self.keymap.first_key(KeyMode::Normal, Action::Refresh),
+                ),
+                self.theme.connection(daemon.connection.state),
+            )));
+        } else if let Some(snapshot) = &self.snapshot {
+            let rpc = daemon
+                .capabilities
+                .as_ref()
+                .map(|caps| format!(" {}", caps.label()))
+                .unwrap_or_default();
+            let mut spans = self.rate_spans(snapshot.download_speed, snapshot.upload_speed);
+            spans.push(Span::raw(format!(
+                "  | Active {}  Paused {}  Total {}  | Version {}{}",
+                snapshot.active_torrents,
+                snapshot.paused_torrents,
+                snapshot.total_torrents,
+                snapshot.version,
+                rpc
+            )));
+            lines.push(Line::from(spans));
+        } else {
+            lines.push(Line::from("Waiting for session stats…"));
+        }
+        lines
+    }
+
+    /// Combined totals on the first line, then one segment per daemon with
+    /// its link state and its own speeds.
+    fn aggregate_header_lines(&self) -> Vec<Line<'static>> {
+        let online = self
+            .daemons
+            .iter()
+            .filter(|daemon| daemon.connection.state == ConnectionState::Online)
+            .count();
+        let overall = if online == self.daemons.len() {
+            ConnectionState::Online
+        } else if online == 0 {
+            ConnectionState::Offline
+        } else {
+            ConnectionState::Degraded
+        };
+        let totals = match &self.snapshot {
+            Some(snapshot) => {
+                let mut spans = self.rate_spans(snapshot.download_speed, snapshot.upload_speed);
+                spans.push(Span::raw(format!(
+                    "  | Active {}  Paused {}  Total {}",
+                    snapshot.active_torrents, snapshot.paused_torrents, snapshot.total_torrents,
+                )));
+                spans
+            }
+            None => vec![Span::raw("Waiting for session stats…")],
+        };
+        let mut daemons = Vec::new();
+        for (idx, daemon) in self.daemons.iter().enumerate() {
+            if idx > 0 {
+                daemons.push(Span::raw("  |  "));
+            }
+            daemons.push(Span::styled(
+                self.daemon_name(idx).to_string(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            daemons.push(Span::raw(" "));
+            daemons.push(Span::styled(
+                daemon.connection.state.label(),
+                self.theme.connection(daemon.connection.state),
+            ));
+            if let Some(snapshot) = &daemon.snapshot {
+                daemons.push(Span::raw(format!(
+                    " DL {} UL {}",
+                    format_speed(snapshot.download_speed).trim_start(),
+                    format_speed(snapshot.upload_speed).trim_start()
+                )));
+            }
+        }
+        let mut title = vec![
+            Span::styled(
+                "Transmission",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  |  "),
+            Span::raw(self.connection_label.clone()),
+            Span::raw("  |  "),
+            Span::styled(
+                format!("{online}/{} online", self.daemons.len()),
+                self.theme.connection(overall),
+            ),
+            Span::raw("  |  "),
+        ];
+        title.extend(totals);
+        vec![Line::from(title), Line::from(daemons)]
+    }
+
+    fn rate_spans(&self, download: i64, upload: i64) -> Vec<Span<'static>> {
+        vec![
+            Span::raw("DL "),
+            Span::styled(format_speed(download), self.theme.download),
+            Span::raw("  UL "),
+            Span::styled(format_speed(upload), self.theme.upload),
+        ]
+    }
+
+    fn render_body(&mut self, frame: &mut Frame, area: Rect, hits: &mut Vec<Hit>) {
+        if self.details_zoomed {
+            self.render_detail(frame, area, hits);
+            return;
+        }
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
             .split(area);
-        self.render_list(frame, chunks[0]);
-        self.render_detail(frame, chunks[1]);
+        self.render_list(frame, chunks[0], hits);
+        self.render_detail(frame, chunks[1], hits);
     }
 
-    fn render_list(&mut self, frame: &mut Frame, area: Rect) {
-        let header = Row::new(vec![
-            Cell::from("Name"),
-            Cell::from("Status"),
-            Cell::from(format!("{:>12}", "DL")),
-            Cell::from(format!("{:>12}", "UL")),
-            Cell::from(format!("{:>9}", "Progress")),
-            Cell::from(format!("{:>10}", "ETA")),
-            Cell::from(format!("{:>8}", "Ratio")),
-        ])
-        .style(Style::default().add_modifier(Modifier::BOLD));
+    fn render_list(&mut self, frame: &mut Frame, area: Rect, hits: &mut Vec<Hit>) {
+        let layout = columns::layout(
+            &self.visible_columns(),
+            // Borders and the highlight symbol.
+            area.width.saturating_sub(4),
+            LIST_COLUMN_SPACING,
+        );
+        let header = layout
+            .iter()
+            .map(|&(column, width)| {
+                let title = match self.sort {
+                    Some((sorted, SortOrder::Ascending)) if sorted == column => {
+                        format!("{} ▲", column.title())
+                    }
+                    Some((sorted, SortOrder::Descending)) if sorted == column => {
+                        format!("{} ▼", column.title())
+                    }
+                    _ => column.title().to_string(),
+                };
+                Cell::from(pad_cell(column, &title, width))
+            })
+            .collect::<Vec<_>>();
+        let header = Row::new(header).style(Style::default().add_modifier(Modifier::BOLD));
 
         let mut rows = self
             .filtered_indices
             .iter()
-            .filter_map(|&idx| self.snapshot.as_ref()?.torrents.get(idx))
-            .map(torrent_row)
+            .filter_map(|&idx| Some((idx, self.snapshot.as_ref()?.torrents.get(idx)?)))
+            .map(|(idx, summary)| {
+                let matched = self.name_matches.get(&idx).map_or(&[][..], Vec::as_slice);
+                torrent_row(summary, &layout, &self.theme, matched)
+            })
             .collect::<Vec<_>>();
         if rows.is_empty() {
-            rows.push(Row::new(vec![
-                Cell::from("No torrents loaded"),
-                Cell::from(""),
-                Cell::from(""),
-                Cell::from(""),
-                Cell::from(""),
-                Cell::from(""),
-                Cell::from(""),
-            ]));
+            let mut cells = vec![Cell::from("No torrents loaded")];
+            cells.resize(layout.len(), Cell::from(""));
+            rows.push(Row::new(cells));
         }
         let block = Block::default()
             .borders(Borders::ALL)
+            .border_style(self.theme.border)
             .title(Span::raw(" Torrents "));
-        let widths = [
-            Constraint::Percentage(50),
-            Constraint::Length(12),
-            Constraint::Length(12),
-            Constraint::Length(12),
-            Constraint::Length(9),
-            Constraint::Length(10),
-            Constraint::Length(8),
-        ];
+        let widths = layout
+            .iter()
+            .map(|&(_, width)| Constraint::Length(width))
+            .collect::<Vec<_>>();
         let table = Table::new(rows, widths)
             .header(header)
             .block(block)
-            .column_spacing(2)
-            .highlight_style(Style::default().fg(Color::Yellow))
+            .column_spacing(LIST_COLUMN_SPACING)
+            .highlight_style(self.theme.selection)
             .highlight_symbol("> ");
         frame.render_stateful_widget(table, area, &mut self.list_state);
+
+        hits.push(Hit::new(area, MouseTarget::List));
+        // Inside the borders, below the header row.
+        let rows_area = Rect {
+            x: area.x + 1,
+            y: area.y + 2,
+            width: area.width.saturating_sub(2),
+            height: area.height.saturating_sub(3),
+        };
+        let offset = self.list_state.offset();
+        let visible = self.filtered_indices.len().saturating_sub(offset);
+        for line in 0..visible.min(rows_area.height as usize) {
+            let row = Rect::new(rows_area.x, rows_area.y + line as u16, rows_area.width, 1);
+            hits.push(Hit::new(row, MouseTarget::Torrent(offset + line)));
+        }
     }
 
-    fn render_detail(&self, frame: &mut Frame, area: Rect) {
+    fn render_detail(&self, frame: &mut Frame, area: Rect, hits: &mut Vec<Hit>) {
+        hits.push(Hit::new(area, MouseTarget::Details));
         let block = Block::default()
             .borders(Borders::ALL)
+            .border_style(self.theme.border)
             .title(Span::raw(" Details "));
         if let Some(torrent) = self.current_torrent() {
             let inner = block.inner(area);
@@ -644,10 +1685,44 @@ impl App {
                     Cell::from(torrent.download_dir.clone()),
                 ]),
             ];
+            if self.aggregated() {
+                rows.insert(
+                    1,
+                    Row::new(vec![
+                        label_cell("Daemon"),
+                        Cell::from(torrent.daemon.clone()),
+                    ]),
+                );
+            }
+            let caps = self
+                .daemon_index(&torrent.daemon)
+                .and_then(|idx| self.daemons[idx].capabilities.as_ref());
+            if let Some(caps) = caps {
+                if caps.labels {
+                    let labels = if torrent.labels.is_empty() {
+                        "(none)".to_string()
+                    } else {
+                        torrent.labels.join(", ")
+                    };
+                    rows.push(Row::new(vec![label_cell("Labels"), Cell::from(labels)]));
+                }
+                if let (true, Some(count)) = (caps.file_count, torrent.file_count) {
+                    rows.push(Row::new(vec![
+                        label_cell("Files"),
+                        Cell::from(count.to_string()),
+                    ]));
+                }
+                if let (true, Some(group)) = (caps.bandwidth_groups, &torrent.bandwidth_group) {
+                    rows.push(Row::new(vec![
+                        label_cell("Group"),
+                        Cell::from(group.clone()),
+                    ]));
+                }
+            }
             if let Some(error) = &torrent.error {
                 rows.push(
                     Row::new(vec![label_cell("Error"), Cell::from(error.clone())])
-                        .style(Style::default().fg(Color::Red)),
+                        .style(self.theme.errored),
                 );
             }
             let info_height = rows.len() as u16;
@@ -671,7 +1746,7 @@ impl App {
                     width: inner.width,
                     height: remaining_height - 1,
                 };
-                self.render_peers(frame, peers_area, torrent);
+                self.render_peers(frame, peers_area, torrent, hits);
             }
         } else {
             let paragraph = Paragraph::new("No torrent selected")
@@ -681,7 +1756,13 @@ impl App {
         }
     }
 
-    fn render_peers(&self, frame: &mut Frame, area: Rect, torrent: &TorrentSummary) {
+    fn render_peers(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        torrent: &TorrentSummary,
+        hits: &mut Vec<Hit>,
+    ) {
         if area.height < 2 {
             return;
         }
@@ -714,13 +1795,26 @@ impl App {
             Cell::from(format!("{:>4}", "Enc")),
         ])
         .style(Style::default().add_modifier(Modifier::BOLD));
-        let mut peers = torrent.peers.iter().collect::<Vec<_>>();
-        peers.sort_by(|a, b| {
-            b.rate_down
-                .cmp(&a.rate_down)
-                .then_with(|| b.rate_up.cmp(&a.rate_up))
-        });
-        let mut rows: Vec<Row> = peers.into_iter().map(peer_row).collect();
+        let peers = sorted_peers(torrent);
+        let (selected, offset) = self.peer_focus(torrent);
+        let offset = offset.min(peers.len().saturating_sub(1));
+        let body_height = table_area.height.saturating_sub(1) as usize;
+        let mut rows: Vec<Row> = Vec::new();
+        for (line, peer) in peers.iter().skip(offset).take(body_height).enumerate() {
+            let row = peer_row(peer, &self.theme);
+            rows.push(if selected == Some(peer.address.as_str()) {
+                row.style(self.theme.selection)
+            } else {
+                row
+            });
+            let hit = Rect::new(
+                table_area.x,
+                table_area.y + 1 + line as u16,
+                table_area.width,
+                1,
+            );
+            hits.push(Hit::new(hit, MouseTarget::Peer(peer.address.clone())));
+        }
         if rows.is_empty() {
             rows.push(Row::new(vec![
                 Cell::from("No connected peers"),
@@ -746,48 +1840,75 @@ impl App {
     fn render_help(&self, frame: &mut Frame, area: Rect) {
         let block = Block::default()
             .title(Span::raw(" Key Bindings "))
-            .borders(Borders::ALL);
+            .borders(Borders::ALL)
+            .border_style(self.theme.border);
         let inner = block.inner(area);
         frame.render_widget(block, area);
         if inner.height == 0 {
             return;
         }
-        let rows = help_rows();
+        let rows = help_rows(self.focused_capabilities(), &self.keymap, &self.theme);
         let widths = [Constraint::Length(22), Constraint::Min(20)];
         let table = Table::new(rows, widths).column_spacing(2);
         frame.render_widget(table, inner);
     }
 
-    fn render_preferences(&self, frame: &mut Frame, area: Rect, state: &PreferencesState) {
+    fn render_preferences(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        state: &PreferencesState,
+        hits: &mut Vec<Hit>,
+    ) {
+        let title = if self.aggregated() {
+            format!(
+                " Preferences · {} ",
+                self.daemon_name(self.preferences_daemon)
+            )
+        } else {
+            " Preferences ".to_string()
+        };
         let block = Block::default()
-            .title(Span::raw(" Preferences "))
-            .borders(Borders::ALL);
+            .title(Span::raw(title))
+            .borders(Borders::ALL)
+            .border_style(self.theme.border);
         let paragraph = match &state.view {
             PreferencesView::Loading => Paragraph::new("Loading preferences…").block(block),
             PreferencesView::Error(message) => {
+                let hints = self.key_hints(
+                    KeyMode::Preferences,
+                    &[(&[Action::Refresh], "retry"), (&[Action::Close], "close")],
+                );
                 let lines = vec![
                     Line::from("Failed to load daemon preferences."),
                     Line::from(message.as_str()),
-                    Line::from("Press r to retry or Esc to close."),
+                    Line::from(hints.join("  ·  ")),
                 ];
                 Paragraph::new(lines).block(block).wrap(Wrap { trim: true })
             }
             PreferencesView::Ready(form) => {
                 let mut lines = Vec::new();
-                let mut instructions = vec![
-                    "j/k move",
-                    "Space toggle",
-                    "Enter edit",
-                    "s save",
-                    "r reload",
-                    "Esc close",
-                ];
-                if form.editing.is_some() {
-                    instructions = vec!["Type to edit", "Enter apply", "Esc cancel"];
-                }
+                let instructions = if form.editing.is_some() {
+                    ["Type to edit", "Enter apply", "Esc cancel"]
+                        .map(String::from)
+                        .to_vec()
+                } else {
+                    self.key_hints(
+                        KeyMode::Preferences,
+                        &[
+                            (&[Action::Down, Action::Up], "move"),
+                            (&[Action::Toggle], "toggle"),
+                            (&[Action::Select], "edit"),
+                            (&[Action::Save], "save"),
+                            (&[Action::Refresh], "reload"),
+                            (&[Action::Close], "close"),
+                        ],
+                    )
+                };
                 lines.push(Line::from(instructions.join("  ·  ")));
                 lines.push(Line::from(""));
                 let mut idx = 0usize;
+                let mut field_lines = Vec::new();
                 for (section_idx, section) in PREFERENCE_SECTIONS.iter().enumerate() {
                     if section_idx > 0 {
                         lines.push(Line::from(""));
@@ -797,10 +1918,7 @@ impl App {
                         Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
                     )));
                     if let Some(note) = section.note {
-                        lines.push(Line::from(Span::styled(
-                            note,
-                            Style::default().fg(Color::DarkGray),
-                        )));
+                        lines.push(Line::from(Span::styled(note, self.theme.muted)));
                     }
                     if section.fields.is_empty() {
                         continue;
@@ -808,7 +1926,7 @@ impl App {
                     for field in section.fields.iter() {
                         let mut spans = Vec::new();
                         if idx == form.selected {
-                            spans.push(Span::styled("> ", Style::default().fg(Color::Yellow)));
+                            spans.push(Span::styled("> ", self.theme.selection));
                         } else {
                             spans.push(Span::raw("  "));
                         }
@@ -821,6 +1939,7 @@ impl App {
                             }),
                         ));
                         spans.push(Span::raw(field.display_value(&form.prefs)));
+                        field_lines.push(lines.len());
                         lines.push(Line::from(spans));
                         idx += 1;
                     }
@@ -833,16 +1952,13 @@ impl App {
                         editor.buffer
                     )));
                     if let Some(msg) = &form.message {
-                        lines.push(Line::from(Span::styled(
-                            msg.as_str(),
-                            Style::default().fg(Color::Yellow),
-                        )));
+                        lines.push(Line::from(Span::styled(msg.as_str(), self.theme.warning)));
                     }
                 } else if let Some(msg) = &form.message {
                     lines.push(Line::from(Span::styled(
                         msg.as_str(),
                         if form.saving {
-                            Style::default().fg(Color::Yellow)
+                            self.theme.warning
                         } else {
                             Style::default()
                         },
@@ -851,9 +1967,26 @@ impl App {
                 if form.saving {
                     lines.push(Line::from(Span::styled(
                         "Saving preferences…",
-                        Style::default().fg(Color::Yellow),
+                        self.theme.warning,
                     )));
                 }
+                let inner = block.inner(area);
+                // Screen row of each line, allowing for wrapped lines above it.
+                let mut y = inner.y;
+                let mut next_field = field_lines.iter().enumerate().peekable();
+                for (line_idx, line) in lines.iter().enumerate() {
+                    if y >= inner.bottom() {
+                        break;
+                    }
+                    if let Some((field, _)) = next_field.next_if(|(_, &at)| at == line_idx) {
+                        hits.push(Hit::new(
+                            Rect::new(inner.x, y, inner.width, 1),
+                            MouseTarget::Item(field),
+                        ));
+                    }
+                    let width = line.width().max(1) as u16;
+                    y += width.div_ceil(inner.width.max(1));
+                }
                 Paragraph::new(lines)
                     .block(block)
                     .wrap(Wrap { trim: false })
@@ -862,122 +1995,832 @@ impl App {
         frame.render_widget(paragraph, area);
     }
 
-    fn render_footer(&self, frame: &mut Frame, area: Rect) {
-        let mode_label = match &self.mode {
-            InputMode::Normal => "NORMAL",
-            InputMode::Filter { .. } => "FILTER",
-            InputMode::Prompt(_) => "PROMPT",
-            InputMode::Confirm(_) => "CONFIRM",
-            InputMode::Help => "HELP",
-            InputMode::Preferences(_) => "PREFS",
-        };
-        let filter_display = match &self.mode {
-            InputMode::Filter { buffer } => format!("/{}", buffer),
-            _ => {
-                if self.filter_text.is_empty() {
-                    "(no filter)".to_string()
-                } else {
-                    format!("/{}", self.filter_text)
-                }
-            }
-        };
-        let summary = Line::from(format!("Mode {mode_label} | Filter {filter_display}"));
-        let sections = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Min(0), Constraint::Length(14)])
-            .split(area);
-        let left = Paragraph::new(summary).wrap(Wrap { trim: true });
-        frame.render_widget(left, sections[0]);
-        let help_label =
-            Paragraph::new(Line::from(Span::raw("Help [?]"))).alignment(Alignment::Right);
-        frame.render_widget(help_label, sections[1]);
-    }
-
-    fn render_toast(&self, frame: &mut Frame) {
-        if !matches!(self.mode, InputMode::Normal | InputMode::Filter { .. }) {
+    fn render_hooks(&self, frame: &mut Frame, area: Rect, scroll: usize) {
+        let block = Block::default()
+            .title(Span::raw(format!(
+                " Hook runs ({}) ",
+                self.key_hints(
+                    KeyMode::Hooks,
+                    &[
+                        (&[Action::Down, Action::Up], "scroll"),
+                        (&[Action::Close], "close")
+                    ],
+                )
+                .join(", ")
+            )))
+            .borders(Borders::ALL)
+            .border_style(self.theme.border);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        if inner.height == 0 {
             return;
         }
-        let Some(toast) = &self.toast else {
-            return;
-        };
-        let frame_area = frame.size();
-        if frame_area.width < 20 || frame_area.height < 5 {
+        let runs = self.hooks.recent();
+        if runs.is_empty() {
+            frame.render_widget(Paragraph::new("No hooks have run yet."), inner);
             return;
         }
-        let padding = 2;
-        let max_width = frame_area.width.saturating_sub(padding * 2);
-        let width = max_width.clamp(20, 60);
-        let height = 3;
-        let x = frame_area
-            .x
-            .saturating_add(frame_area.width.saturating_sub(width + padding));
-        let y = frame_area
-            .y
-            .saturating_add(frame_area.height.saturating_sub(height + padding));
-        let area = Rect::new(x, y, width, height);
-        let text = Line::from(Span::styled(toast.text.clone(), status_style(toast.level)));
-        let paragraph = Paragraph::new(text).alignment(Alignment::Center).block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(Span::raw(" Notice ")),
-        );
-        frame.render_widget(Clear, area);
-        frame.render_widget(paragraph, area);
+        let rows = runs.iter().skip(scroll).map(|run| {
+            let (status, style) = match &run.status {
+                HookStatus::Queued => ("queued".to_string(), Style::default()),
+                HookStatus::Running => ("running".to_string(), self.theme.warning),
+                HookStatus::Succeeded => ("ok".to_string(), self.theme.success),
+                HookStatus::Failed(reason) => (reason.clone(), self.theme.error),
+            };
+            let duration = run
+                .duration
+                .map(|elapsed| format!("{:.1}s", elapsed.as_secs_f64()))
+                .unwrap_or_default();
+            Row::new(vec![
+                Cell::from(format_eta(Some(run.queued_at.elapsed().as_secs() as i64))),
+                Cell::from(run.event.label()),
+                Cell::from(format!("[{}] {}", run.profile, run.torrent)),
+                Cell::from(run.command.clone()),
+                Cell::from(duration),
+                Cell::from(Span::styled(status, style)),
+            ])
+        });
+        let widths = [
+            Constraint::Length(6),
+            Constraint::Length(13),
+            Constraint::Percentage(25),
+            Constraint::Percentage(25),
+            Constraint::Length(7),
+            Constraint::Min(10),
+        ];
+        let table = Table::new(rows, widths)
+            .header(
+                Row::new(vec!["Ago", "Event", "Torrent", "Command", "Took", "Result"])
+                    .style(Style::default().add_modifier(Modifier::BOLD)),
+            )
+            .column_spacing(1);
+        frame.render_widget(table, inner);
     }
 
-    fn process_event(&mut self, event: AppEvent, rpc_tx: &Sender<RpcCommand>) -> Result<bool> {
-        match event {
-            AppEvent::Input(event) => self.handle_input(event, rpc_tx),
-            AppEvent::Tick => {
-                self.expire_status();
-                Ok(false)
-            }
-            AppEvent::Snapshot(result) => {
-                self.apply_snapshot(result);
-                Ok(false)
-            }
-            AppEvent::Status(update) => {
-                self.set_status(update);
-                Ok(false)
-            }
-            AppEvent::FocusTorrent(target) => {
-                self.pending_focus = target;
-                Ok(false)
-            }
-            AppEvent::Preferences(result) => {
-                self.apply_preferences_event(result);
-                Ok(false)
+    /// Lines of the rules overlay: what the rules would do to the current
+    /// torrents, then the audit log.
+    fn rule_lines(&self) -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+        let heading = |text: &str| {
+            Line::from(Span::styled(
+                text.to_string(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ))
+        };
+        lines.push(heading("Rules"));
+        if self.rules.is_empty() {
+            lines.push(Line::from("  No [[rules]] configured."));
+        }
+        for rule in &self.rules {
+            let scope = rule
+                .profile
+                .as_ref()
+                .map(|name| format!(" [{name}]"))
+                .unwrap_or_default();
+            lines.push(Line::from(format!(
+                "  {}{scope}: {} → {}",
+                rule.name,
+                rules::describe_condition(&rule.when),
+                rule.action.describe()
+            )));
+        }
+        lines.push(Line::from(""));
+        lines.push(heading("Matching now (dry run)"));
+        let mut matching = 0;
+        for (index, state) in self.daemons.iter().enumerate() {
+            let Some(snapshot) = &state.snapshot else {
+                continue;
+            };
+            let profile = self.daemon_name(index);
+            for planned in rules::plan(&self.rules, profile, snapshot) {
+                matching += 1;
+                lines.push(Line::from(format!(
+                    "  {}: {} [{profile}] {}",
+                    self.rules[planned.rule].name,
+                    planned.action.describe(),
+                    planned.torrent.name
+                )));
             }
         }
+        if matching == 0 {
+            lines.push(Line::from("  Nothing."));
+        }
+        lines.push(Line::from(""));
+        lines.push(heading("Audit log"));
+        if self.rule_log.is_empty() {
+            lines.push(Line::from("  No rule has fired yet."));
+        }
+        for audit in &self.rule_log {
+            let ago = audit.at.elapsed().unwrap_or_default().as_secs() as i64;
+            let style = match audit.result {
+                AuditResult::Applied => self.theme.success,
+                AuditResult::DryRun => self.theme.warning,
+                AuditResult::Failed(_) => self.theme.error,
+            };
+            lines.push(Line::from(vec![
+                Span::raw(format!(
+                    "  {:>6} ago  [{}] ",
+                    format_eta(Some(ago)),
+                    audit.profile
+                )),
+                Span::styled(audit.message(), style),
+            ]));
+        }
+        lines
     }
 
-    fn handle_input(&mut self, event: Event, rpc_tx: &Sender<RpcCommand>) -> Result<bool> {
-        match event {
-            Event::Key(key) if key.kind == KeyEventKind::Press => {
-                if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
-                    self.should_quit = true;
-                    return Ok(true);
-                }
-                if matches!(self.mode, InputMode::Normal) {
-                    return self.handle_normal_key(key, rpc_tx);
+    fn render_rules(&self, frame: &mut Frame, area: Rect, scroll: usize) {
+        let block = Block::default()
+            .title(Span::raw(format!(
+                " Rules ({}) ",
+                self.key_hints(
+                    KeyMode::Rules,
+                    &[
+                        (&[Action::Down, Action::Up], "scroll"),
+                        (&[Action::Close], "close")
+                    ],
+                )
+                .join(", ")
+            )))
+            .borders(Borders::ALL)
+            .border_style(self.theme.border);
+        let lines: Vec<Line> = self.rule_lines().into_iter().skip(scroll).collect();
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn render_profiles(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        picker: &ProfilePicker,
+        hits: &mut Vec<Hit>,
+    ) {
+        let block = Block::default()
+            .title(Span::raw(" Profiles "))
+            .borders(Borders::ALL)
+            .border_style(self.theme.border);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        if inner.height == 0 {
+            return;
+        }
+        let rows = picker
+            .targets
+            .iter()
+            .copied()
+            .enumerate()
+            .map(|(idx, target)| {
+                let marker = if target == self.view { "●" } else { " " };
+                let (name, endpoint) = match target {
+                    ProfileTarget::Single(index) => (
+                        self.profiles[index].name.clone(),
+                        self.profiles[index].endpoint.clone(),
+                    ),
+                    ProfileTarget::All => (
+                        "All profiles".to_string(),
+                        format!("{} daemons merged", self.profiles.len()),
+                    ),
+                };
+                let row = Row::new(vec![
+                    Cell::from(marker),
+                    Cell::from(name),
+                    Cell::from(endpoint),
+                ]);
+                if idx == picker.selected {
+                    row.style(self.theme.selection)
+                } else {
+                    row
                 }
-                match &mut self.mode {
-                    InputMode::Filter { buffer } => {
-                        let mut action = FilterAction::None;
-                        match key.code {
-                            KeyCode::Enter => {
-                                let value = buffer.trim().to_string();
-                                action = FilterAction::Apply(value);
-                            }
+            });
+        let widths = [
+            Constraint::Length(1),
+            Constraint::Length(16),
+            Constraint::Min(10),
+        ];
+        let table = Table::new(rows, widths)
+            .header(
+                Row::new(vec![
+                    Cell::from(""),
+                    Cell::from("Name"),
+                    Cell::from("Endpoint"),
+                ])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+            )
+            .column_spacing(2);
+        frame.render_widget(table, inner);
+        // Rows start below the header.
+        for idx in 0..picker
+            .targets
+            .len()
+            .min(inner.height.saturating_sub(1) as usize)
+        {
+            let row = Rect::new(inner.x, inner.y + 1 + idx as u16, inner.width, 1);
+            hits.push(Hit::new(row, MouseTarget::Item(idx)));
+        }
+    }
+
+    fn render_views(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        picker: &ViewPicker,
+        hits: &mut Vec<Hit>,
+    ) {
+        let block = Block::default()
+            .title(Span::raw(format!(
+                " Views ({}) ",
+                self.key_hints(
+                    KeyMode::Views,
+                    &[
+                        (&[Action::Select], "apply"),
+                        (&[Action::SaveView], "save current"),
+                        (&[Action::Close], "close"),
+                    ],
+                )
+                .join(", ")
+            )))
+            .borders(Borders::ALL)
+            .border_style(self.theme.border);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        if inner.height == 0 {
+            return;
+        }
+        if self.views.is_empty() {
+            let text = "No saved views yet. Press s to save the current filter, sort and \
+                        columns, or add [[views]] tables to the config file.";
+            frame.render_widget(Paragraph::new(text).wrap(Wrap { trim: true }), inner);
+            return;
+        }
+        let active = self.active_view();
+        let rows = self.views.iter().enumerate().map(|(idx, view)| {
+            let key = if idx < 9 {
+                (idx + 1).to_string()
+            } else {
+                String::new()
+            };
+            let marker = if active == Some(view) { "●" } else { " " };
+            let filter = match (view.filter.is_empty(), view.fuzzy) {
+                (true, _) => "(no filter)".to_string(),
+                (false, true) => format!("~{}", view.filter),
+                (false, false) => format!("/{}", view.filter),
+            };
+            let sort = match view.sort {
+                Some((column, SortOrder::Ascending)) => format!("{} ▲", column.title()),
+                Some((column, SortOrder::Descending)) => format!("{} ▼", column.title()),
+                None => String::new(),
+            };
+            let row = Row::new(vec![
+                Cell::from(key),
+                Cell::from(marker),
+                Cell::from(view.name.clone()),
+                Cell::from(self.view_match_count(view).to_string()),
+                Cell::from(filter),
+                Cell::from(sort),
+            ]);
+            if idx == picker.selected {
+                row.style(self.theme.selection)
+            } else {
+                row
+            }
+        });
+        let widths = [
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(16),
+            Constraint::Length(7),
+            Constraint::Min(10),
+            Constraint::Length(12),
+        ];
+        let table = Table::new(rows, widths)
+            .header(
+                Row::new(vec![
+                    Cell::from(""),
+                    Cell::from(""),
+                    Cell::from("Name"),
+                    Cell::from("Matches"),
+                    Cell::from("Filter"),
+                    Cell::from("Sort"),
+                ])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+            )
+            .column_spacing(2);
+        frame.render_widget(table, inner);
+        // Rows start below the header.
+        for idx in 0..self
+            .views
+            .len()
+            .min(inner.height.saturating_sub(1) as usize)
+        {
+            let row = Rect::new(inner.x, inner.y + 1 + idx as u16, inner.width, 1);
+            hits.push(Hit::new(row, MouseTarget::Item(idx)));
+        }
+    }
+
+    fn render_columns(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        picker: &ColumnPicker,
+        hits: &mut Vec<Hit>,
+    ) {
+        let block = Block::default()
+            .title(Span::raw(format!(
+                " Columns ({}) ",
+                self.key_hints(
+                    KeyMode::Columns,
+                    &[
+                        (&[Action::Toggle], "show/hide"),
+                        (&[Action::MoveDown, Action::MoveUp], "reorder"),
+                        (&[Action::Accept], "apply"),
+                        (&[Action::Cancel], "cancel"),
+                    ],
+                )
+                .join(", ")
+            )))
+            .borders(Borders::ALL)
+            .border_style(self.theme.border);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        if inner.height == 0 {
+            return;
+        }
+        let rows = picker
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(idx, &(column, shown))| {
+                let row = Row::new(vec![
+                    Cell::from(if shown { "[x]" } else { "[ ]" }),
+                    Cell::from(column.title()),
+                    Cell::from(column.description()),
+                ]);
+                if idx == picker.cursor {
+                    row.style(self.theme.selection)
+                } else if shown {
+                    row
+                } else {
+                    row.style(self.theme.muted)
+                }
+            })
+            .collect::<Vec<_>>();
+        let widths = [
+            Constraint::Length(3),
+            Constraint::Length(10),
+            Constraint::Min(10),
+        ];
+        let mut state = TableState::default().with_selected(Some(picker.cursor));
+        let table = Table::new(rows, widths).column_spacing(2);
+        frame.render_stateful_widget(table, inner, &mut state);
+        let offset = state.offset();
+        let visible = picker.entries.len().saturating_sub(offset);
+        for line in 0..visible.min(inner.height as usize) {
+            let y = inner.y + line as u16;
+            hits.push(Hit::new(
+                Rect::new(inner.x, y, inner.width, 1),
+                MouseTarget::Item(offset + line),
+            ));
+            hits.push(Hit::new(
+                Rect::new(inner.x, y, 3, 1),
+                MouseTarget::Check(offset + line),
+            ));
+        }
+    }
+
+    fn render_export(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        state: &ExportState,
+        hits: &mut Vec<Hit>,
+    ) {
+        let block = Block::default()
+            .title(Span::raw(format!(
+                " Export {} torrents ",
+                self.filtered_indices.len()
+            )))
+            .borders(Borders::ALL)
+            .border_style(self.theme.border);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        if inner.height == 0 {
+            return;
+        }
+        let highlight = |row: usize| {
+            if row == state.cursor {
+                self.theme.selection
+            } else {
+                Style::default()
+            }
+        };
+        let mut lines = vec![
+            Line::styled(
+                format!("Format  < {} >", state.format.label()),
+                highlight(0),
+            ),
+            Line::styled(
+                format!(
+                    "Path    {}{}",
+                    state.path,
+                    if state.cursor == 1 { "▏" } else { "" }
+                ),
+                highlight(1),
+            ),
+            Line::from(""),
+            Line::styled("Columns", Style::default().add_modifier(Modifier::BOLD)),
+        ];
+        let header_lines = lines.len();
+        for (idx, (column, enabled)) in EXPORT_COLUMNS.iter().zip(&state.columns).enumerate() {
+            let mark = if *enabled { "[x]" } else { "[ ]" };
+            lines.push(Line::styled(
+                format!("  {mark} {column}"),
+                highlight(idx + 2),
+            ));
+        }
+        lines.push(Line::from(""));
+        if let Some(message) = &state.message {
+            lines.push(Line::styled(message.clone(), self.theme.error));
+        }
+        lines.push(Line::styled(
+            "↑/↓ move · Space toggle / cycle · a all columns · Enter export · Esc cancel",
+            self.theme.muted,
+        ));
+        // Keep the cursor row in view on short terminals.
+        let cursor_line = match state.cursor {
+            0 | 1 => state.cursor,
+            row => header_lines + row - 2,
+        };
+        let scroll = cursor_line.saturating_sub(inner.height.saturating_sub(1) as usize);
+        let paragraph = Paragraph::new(lines).scroll((scroll as u16, 0));
+        frame.render_widget(paragraph, inner);
+        let row_lines = (0..2).chain((0..state.columns.len()).map(|idx| header_lines + idx));
+        for (row, line) in row_lines.enumerate() {
+            let Some(line) = line
+                .checked_sub(scroll)
+                .filter(|&line| line < inner.height as usize)
+            else {
+                continue;
+            };
+            let y = inner.y + line as u16;
+            hits.push(Hit::new(
+                Rect::new(inner.x, y, inner.width, 1),
+                MouseTarget::Item(row),
+            ));
+            if row >= 2 {
+                hits.push(Hit::new(
+                    Rect::new(inner.x, y, 5, 1),
+                    MouseTarget::Check(row),
+                ));
+            }
+        }
+    }
+
+    fn render_footer(&self, frame: &mut Frame, area: Rect, hits: &mut Vec<Hit>) {
+        let mode_label = match &self.mode {
+            InputMode::Normal => "NORMAL",
+            InputMode::Filter { .. } => "FILTER",
+            InputMode::Prompt(_) => "PROMPT",
+            InputMode::Confirm(_) => "CONFIRM",
+            InputMode::Help => "HELP",
+            InputMode::Preferences(_) => "PREFS",
+            InputMode::Profiles(_) => "PROFILES",
+            InputMode::Export(_) => "EXPORT",
+            InputMode::Hooks { .. } => "HOOKS",
+            InputMode::Rules { .. } => "RULES",
+            InputMode::Columns(_) => "COLUMNS",
+            InputMode::Views(_) => "VIEWS",
+            InputMode::Command(_) => "COMMAND",
+        };
+        let marker = if self.fuzzy_filter { "~" } else { "/" };
+        let filter_display = match &self.mode {
+            InputMode::Filter { buffer, .. } => format!("{marker}{buffer}"),
+            _ => {
+                if self.filter_text.is_empty() {
+                    "(no filter)".to_string()
+                } else {
+                    format!("{marker}{}", self.filter_text)
+                }
+            }
+        };
+        let mut summary = format!("Mode {mode_label} | Filter {filter_display}");
+        if let Some(daemon) = &self.daemon_filter {
+            summary.push_str(&format!(" | Daemon {daemon}"));
+        }
+        if let Some(view) = self.active_view() {
+            summary.push_str(&format!(" | View {}", view.name));
+        }
+        let summary = match &self.mode {
+            InputMode::Command(line) => {
+                let typed = line.buffer.chars().count() as u16;
+                frame.set_cursor(
+                    (area.x + 1 + typed).min(area.right().saturating_sub(1)),
+                    area.y,
+                );
+                Line::from(format!(":{}", line.buffer))
+            }
+            _ => Line::from(summary),
+        };
+        let hints = self.footer_hints();
+        let hints_width = hints
+            .iter()
+            .map(|(_, text)| text.chars().count() as u16 + 2)
+            .sum::<u16>();
+        let sections = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(hints_width)])
+            .split(area);
+        let left = Paragraph::new(summary).wrap(Wrap { trim: true });
+        frame.render_widget(left, sections[0]);
+        let mut x = sections[1].x;
+        let mut spans = Vec::new();
+        for (action, text) in hints {
+            let width = text.chars().count() as u16;
+            spans.push(Span::raw("  "));
+            spans.push(Span::raw(text));
+            x += 2;
+            if self.key_mode() == Some(KeyMode::Normal) {
+                let hint = Rect::new(x, area.y, width, 1).intersection(sections[1]);
+                hits.push(Hit::new(hint, MouseTarget::Action(KeyMode::Normal, action)));
+            }
+            x += width;
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)), sections[1]);
+    }
+
+    /// Completions for the word being typed on the command line, in a box
+    /// just above the footer. While Tab cycles, the cycled list is shown.
+    fn render_command_suggestions(&self, frame: &mut Frame, footer: Rect) {
+        let InputMode::Command(line) = &self.mode else {
+            return;
+        };
+        let fresh;
+        let (candidates, chosen) = match &line.tab {
+            Some(tab) => (&tab.candidates, Some(tab.index)),
+            None => {
+                fresh = cmdline::complete(&line.buffer, &self.command_context()).candidates;
+                (&fresh, None)
+            }
+        };
+        if candidates.is_empty() {
+            return;
+        }
+        let first = chosen.map_or(0, |index| (index + 1).saturating_sub(COMMAND_SUGGESTIONS));
+        let shown = &candidates[first..candidates.len().min(first + COMMAND_SUGGESTIONS)];
+        let text_width = shown
+            .iter()
+            .map(|candidate| candidate.text.chars().count())
+            .max()
+            .unwrap_or(0);
+        let lines: Vec<Line> = shown
+            .iter()
+            .enumerate()
+            .map(|(offset, candidate)| {
+                let base = if chosen == Some(first + offset) {
+                    self.theme.selection
+                } else {
+                    Style::default()
+                };
+                let mut spans = highlight_spans(
+                    &candidate.text,
+                    &candidate.positions,
+                    base,
+                    self.theme.matched,
+                );
+                let pad = text_width - candidate.text.chars().count();
+                if !candidate.detail.is_empty() {
+                    spans.push(Span::raw(" ".repeat(pad + 2)));
+                    spans.push(Span::styled(candidate.detail, self.theme.muted));
+                }
+                Line::from(spans)
+            })
+            .collect();
+        let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 2;
+        let height = lines.len() as u16 + 2;
+        let area = Rect::new(
+            footer.x,
+            footer.y.saturating_sub(height),
+            width.min(footer.width),
+            height.min(footer.y),
+        );
+        let title = if candidates.len() > shown.len() {
+            format!(" {} of {} ", shown.len(), candidates.len())
+        } else {
+            String::new()
+        };
+        let block = Block::default()
+            .title(Span::styled(title, self.theme.muted))
+            .borders(Borders::ALL)
+            .border_style(self.theme.border);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    /// Clickable footer hints, labelled with each action's first key.
+    fn footer_hints(&self) -> Vec<(Action, String)> {
+        let labels = [
+            (Action::AddMagnet, "Add"),
+            (Action::Filter, "Filter"),
+            (Action::Columns, "Columns"),
+            (Action::Help, "Help"),
+        ];
+        labels
+            .into_iter()
+            .filter_map(|(action, label)| {
+                let key = self.keymap.first_key(KeyMode::Normal, action)?;
+                Some((action, format!("{label} [{key}]")))
+            })
+            .collect()
+    }
+
+    /// Key hints for a dialog title or instruction line, built from the
+    /// active bindings of `mode`. Hints for unbound actions are left out.
+    fn key_hints(&self, mode: KeyMode, hints: &[(&[Action], &str)]) -> Vec<String> {
+        hints
+            .iter()
+            .filter_map(|(actions, label)| self.keymap.hint(mode, actions, label))
+            .collect()
+    }
+
+    fn render_toast(&self, frame: &mut Frame) {
+        if !matches!(self.mode, InputMode::Normal | InputMode::Filter { .. }) {
+            return;
+        }
+        let Some(toast) = &self.toast else {
+            return;
+        };
+        let frame_area = frame.size();
+        if frame_area.width < 20 || frame_area.height < 5 {
+            return;
+        }
+        let padding = 2;
+        let max_width = frame_area.width.saturating_sub(padding * 2);
+        let width = max_width.clamp(20, 60);
+        let height = 3;
+        let x = frame_area
+            .x
+            .saturating_add(frame_area.width.saturating_sub(width + padding));
+        let y = frame_area
+            .y
+            .saturating_add(frame_area.height.saturating_sub(height + padding));
+        let area = Rect::new(x, y, width, height);
+        let text = Line::from(Span::styled(
+            toast.text.clone(),
+            status_style(&self.theme, toast.level),
+        ));
+        let paragraph = Paragraph::new(text).alignment(Alignment::Center).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(self.theme.border)
+                .title(Span::raw(" Notice ")),
+        );
+        frame.render_widget(Clear, area);
+        frame.render_widget(paragraph, area);
+    }
+
+    fn process_event(&mut self, event: AppEvent, router: &RpcRouter) -> Result<bool> {
+        match event {
+            AppEvent::Input(event) => self.handle_input(event, router),
+            AppEvent::Tick => {
+                self.expire_status();
+                Ok(false)
+            }
+            AppEvent::Status(update) => {
+                self.set_status(update);
+                Ok(false)
+            }
+            AppEvent::ProfileStatus(profile, update) => {
+                let update = self.tag_profile_status(&profile, update);
+                self.set_status(update);
+                Ok(false)
+            }
+            event => {
+                self.apply_daemon_event(0, event);
+                Ok(false)
+            }
+        }
+    }
+
+    /// Applies an event reported by the worker for `self.daemons[daemon]`.
+    fn apply_daemon_event(&mut self, daemon: usize, event: AppEvent) {
+        if daemon >= self.daemons.len() {
+            return;
+        }
+        match event {
+            AppEvent::Input(_) | AppEvent::Tick => {}
+            AppEvent::Snapshot(result) => self.apply_snapshot(daemon, result),
+            AppEvent::Status(update) => {
+                let update = self.tag_status(daemon, update);
+                self.set_status(update);
+            }
+            AppEvent::ProfileStatus(profile, update) => {
+                let update = self.tag_profile_status(&profile, update);
+                self.set_status(update);
+            }
+            AppEvent::FocusTorrent(target) => {
+                self.pending_focus = target.map(|id| TorrentKey {
+                    daemon: self.daemon_name(daemon).to_string(),
+                    id,
+                });
+            }
+            AppEvent::Preferences(result) => self.apply_preferences_event(daemon, result),
+            AppEvent::Connection(status) => self.apply_connection(daemon, status),
+            AppEvent::Capabilities(caps) => {
+                if let Some(warning) = caps.compatibility_warning() {
+                    let update = self.tag_status(daemon, StatusUpdate::warning(warning));
+                    self.set_status(update);
+                }
+                self.daemons[daemon].capabilities = Some(caps);
+            }
+            AppEvent::RuleAudit(audits) => self.record_rule_audits(daemon, audits),
+        }
+    }
+
+    fn record_rule_audits(&mut self, daemon: usize, audits: Vec<RuleAudit>) {
+        for audit in audits {
+            let update = match &audit.result {
+                AuditResult::Applied => StatusUpdate::success(audit.message()).toast(),
+                AuditResult::DryRun => StatusUpdate::info(audit.message()),
+                AuditResult::Failed(_) => StatusUpdate::error(audit.message()),
+            };
+            let update = self.tag_status(daemon, update);
+            self.set_status(update);
+            if self.rule_log.len() == RULE_LOG_LIMIT {
+                self.rule_log.pop_back();
+            }
+            self.rule_log.push_front(audit);
+        }
+    }
+
+    fn supports(&self, check: impl Fn(&Capabilities) -> bool) -> bool {
+        self.focused_capabilities().is_none_or(check)
+    }
+
+    fn apply_connection(&mut self, daemon: usize, status: ConnectionStatus) {
+        let previous = self.daemons[daemon].connection.state;
+        if previous != status.state {
+            let reason = status.last_error.clone().unwrap_or_default();
+            let update = match status.state {
+                ConnectionState::Online if previous != ConnectionState::Connecting => {
+                    Some(StatusUpdate::success("Reconnected to daemon"))
+                }
+                ConnectionState::Degraded => Some(StatusUpdate::warning(format!(
+                    "Connection degraded: {reason}"
+                ))),
+                ConnectionState::Offline => {
+                    Some(StatusUpdate::error(format!("Daemon offline: {reason}")))
+                }
+                _ => None,
+            };
+            if let Some(update) = update {
+                let update = self.tag_status(daemon, update);
+                self.set_status(update);
+            }
+        }
+        self.daemons[daemon].connection = status;
+    }
+
+    fn handle_input(&mut self, event: Event, router: &RpcRouter) -> Result<bool> {
+        match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                    self.should_quit = true;
+                    return Ok(true);
+                }
+                if let Some(mode) = self.key_mode() {
+                    return match self.resolve_key(mode, KeyChord::from(key)) {
+                        Some(action) => self.handle_action(mode, action, router),
+                        // Unbound digits pick saved views.
+                        None if mode == KeyMode::Normal && self.pending_keys.is_empty() => {
+                            if let (KeyCode::Char(digit @ '1'..='9'), KeyModifiers::NONE) =
+                                (key.code, key.modifiers)
+                            {
+                                self.apply_view(digit as usize - '1' as usize);
+                            }
+                            Ok(false)
+                        }
+                        None => Ok(false),
+                    };
+                }
+                match &mut self.mode {
+                    InputMode::Filter { buffer, previous } => {
+                        let mut action = FilterAction::None;
+                        match key.code {
+                            KeyCode::Enter => {
+                                let value = buffer.trim().to_string();
+                                action = FilterAction::Apply(value);
+                            }
                             KeyCode::Esc => {
-                                action = FilterAction::Cancel;
+                                action = FilterAction::Cancel(std::mem::take(previous));
                             }
                             KeyCode::Backspace => {
                                 buffer.pop();
+                                action = FilterAction::Preview(buffer.trim().to_string());
                             }
                             KeyCode::Char(c) => {
                                 buffer.push(c);
+                                action = FilterAction::Preview(buffer.trim().to_string());
                             }
+                            KeyCode::Tab => action = FilterAction::ToggleFuzzy,
                             _ => {}
                         }
                         match action {
@@ -985,8 +2828,14 @@ impl App {
                                 self.mode = InputMode::Normal;
                                 self.apply_filter_text(value);
                             }
-                            FilterAction::Cancel => {
+                            FilterAction::Cancel(previous) => {
                                 self.mode = InputMode::Normal;
+                                self.apply_filter_text(previous);
+                            }
+                            FilterAction::Preview(value) => self.apply_filter_text(value),
+                            FilterAction::ToggleFuzzy => {
+                                self.fuzzy_filter = !self.fuzzy_filter;
+                                self.rebuild_indices();
                             }
                             FilterAction::None => {}
                         }
@@ -1018,7 +2867,8 @@ impl App {
                             PromptAction::Submit(value) => {
                                 self.mode = InputMode::Normal;
                                 self.set_status(StatusUpdate::info("Submitting magnet…"));
-                                if rpc_tx.send(RpcCommand::AddMagnet(value)).is_err() {
+                                let daemon = self.target_daemon();
+                                if router.send(daemon, RpcCommand::AddMagnet(value)).is_err() {
                                     self.set_status(StatusUpdate::error(
                                         "Failed to queue magnet add",
                                     ));
@@ -1031,97 +2881,52 @@ impl App {
                         }
                         Ok(false)
                     }
-                    InputMode::Confirm(confirm) => {
-                        let mut action = ConfirmAction::None;
-                        match key.code {
-                            KeyCode::Char('y') | KeyCode::Enter => {
-                                action = ConfirmAction::Accept;
-                            }
-                            KeyCode::Char('n') | KeyCode::Esc => {
-                                action = ConfirmAction::Cancel;
-                            }
-                            _ => {}
-                        }
-                        match action {
-                            ConfirmAction::Accept => {
-                                let info = format!("Removing {}…", confirm.target_name);
-                                let id = confirm.target_id;
-                                let name = confirm.target_name.clone();
-                                let delete_data = confirm.delete_data;
-                                self.mode = InputMode::Normal;
-                                self.set_status(StatusUpdate::info(info));
-                                if rpc_tx
-                                    .send(RpcCommand::RemoveTorrent {
-                                        id,
-                                        name,
-                                        delete_data,
-                                    })
-                                    .is_err()
-                                {
-                                    self.set_status(StatusUpdate::error(
-                                        "Failed to queue deletion",
-                                    ));
-                                }
-                            }
-                            ConfirmAction::Cancel => {
-                                self.mode = InputMode::Normal;
-                                self.set_status(StatusUpdate::info("Deletion cancelled"));
-                            }
-                            ConfirmAction::None => {}
-                        }
-                        Ok(false)
-                    }
-                    InputMode::Help => {
-                        match key.code {
-                            KeyCode::Char('?')
-                            | KeyCode::Esc
-                            | KeyCode::Enter
-                            | KeyCode::Char('q') => {
-                                self.mode = InputMode::Normal;
-                            }
-                            _ => {}
-                        }
+                    InputMode::Preferences(state) => {
+                        state.handle_edit_key(key);
                         Ok(false)
                     }
-                    InputMode::Preferences(state) => {
-                        let result = state.handle_key(key);
-                        if let Some(cmd) = result.command {
-                            let is_fetch = matches!(&cmd, RpcCommand::FetchPreferences);
-                            if rpc_tx.send(cmd).is_err() {
-                                if is_fetch {
-                                    state.apply_error("Failed to queue preferences refresh".into());
-                                } else if let PreferencesView::Ready(form) = &mut state.view {
-                                    form.saving = false;
-                                    form.message = Some("Failed to queue save".into());
-                                }
-                                self.set_status(StatusUpdate::error(
-                                    "Failed to queue preferences command",
-                                ));
-                            }
-                        }
-                        if result.close {
-                            self.mode = InputMode::Normal;
+                    InputMode::Command(_) => self.handle_command_key(key, router),
+                    InputMode::Export(state) => {
+                        match state.handle_key(key) {
+                            ExportAction::None => {}
+                            ExportAction::Cancel => self.mode = InputMode::Normal,
+                            ExportAction::Submit => self.export_view(),
                         }
                         Ok(false)
                     }
-                    InputMode::Normal => Ok(false),
+                    _ => Ok(false),
                 }
             }
-            Event::Paste(data) => self.handle_paste(data, rpc_tx),
+            Event::Mouse(mouse) => self.handle_mouse(mouse, router),
+            Event::Paste(data) => self.handle_paste(data, router),
             _ => Ok(false),
         }
     }
 
-    fn handle_paste(&mut self, data: String, _rpc_tx: &Sender<RpcCommand>) -> Result<bool> {
+    fn handle_paste(&mut self, data: String, _router: &RpcRouter) -> Result<bool> {
         match &mut self.mode {
-            InputMode::Filter { buffer } => {
+            InputMode::Filter { buffer, .. } => {
                 buffer.push_str(&data);
+                let value = buffer.trim().to_string();
+                self.apply_filter_text(value);
                 Ok(false)
             }
             InputMode::Prompt(prompt) => {
                 prompt.buffer.push_str(&data);
                 Ok(false)
             }
+            InputMode::Command(line) => {
+                line.buffer
+                    .push_str(data.lines().next().unwrap_or_default());
+                line.tab = None;
+                Ok(false)
+            }
+            InputMode::Export(state) => {
+                if state.cursor == 1 {
+                    state.path.push_str(data.trim_end_matches(['\r', '\n']));
+                }
+                Ok(false)
+            }
             _ => {
                 let mut prompt = PromptState::new("Add magnet");
                 prompt.buffer.push_str(&data);
@@ -1131,141 +2936,582 @@ impl App {
         }
     }
 
-    fn open_preferences(&mut self, rpc_tx: &Sender<RpcCommand>) {
-        let mut state = if let Some(cache) = &self.preferences_cache {
+    fn handle_mouse(&mut self, mouse: MouseEvent, router: &RpcRouter) -> Result<bool> {
+        let Some(target) = self
+            .hits
+            .iter()
+            .rev()
+            .find(|hit| hit.contains(mouse.column, mouse.row))
+            .map(|hit| hit.target.clone())
+        else {
+            return Ok(false);
+        };
+        match mouse.kind {
+            MouseEventKind::ScrollDown => self.scroll_at(target, 1, router),
+            MouseEventKind::ScrollUp => self.scroll_at(target, -1, router),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let now = Instant::now();
+                let double = self.last_click.is_some_and(|(at, column, row)| {
+                    now.duration_since(at) <= DOUBLE_CLICK
+                        && column == mouse.column
+                        && row == mouse.row
+                });
+                self.last_click = (!double).then_some((now, mouse.column, mouse.row));
+                self.click(target, double, router)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn click(&mut self, target: MouseTarget, double: bool, router: &RpcRouter) -> Result<bool> {
+        match target {
+            MouseTarget::Torrent(index) if index < self.filtered_indices.len() => {
+                self.list_state.select(Some(index));
+                self.update_selected_id();
+                if double {
+                    self.details_zoomed = true;
+                }
+            }
+            MouseTarget::Details if double => self.details_zoomed = !self.details_zoomed,
+            MouseTarget::Peer(address) => {
+                if let Some(focus) = self.current_peer_focus() {
+                    focus.selected = Some(address);
+                }
+            }
+            MouseTarget::Item(index) => {
+                self.select_item(index);
+                if double {
+                    return self.activate_item(router);
+                }
+            }
+            MouseTarget::Check(index) => {
+                self.select_item(index);
+                return self.activate_item(router);
+            }
+            MouseTarget::Action(mode, action) if self.key_mode() == Some(mode) => {
+                self.clear_pending_keys();
+                return self.handle_action(mode, action, router);
+            }
+            MouseTarget::Key(code) => return self.press(code, router),
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    /// Wheel over a pane scrolls it; over a dialog it moves the cursor.
+    fn scroll_at(&mut self, target: MouseTarget, delta: isize, router: &RpcRouter) -> Result<bool> {
+        match target {
+            MouseTarget::List | MouseTarget::Torrent(_) => self.move_selection(delta),
+            MouseTarget::Details | MouseTarget::Peer(_) => self.scroll_peers(delta),
+            _ => {
+                let (action, code) = if delta > 0 {
+                    (Action::Down, KeyCode::Down)
+                } else {
+                    (Action::Up, KeyCode::Up)
+                };
+                return match self.key_mode() {
+                    Some(KeyMode::Normal) => Ok(false),
+                    Some(mode) => self.handle_action(mode, action, router),
+                    None => self.press(code, router),
+                };
+            }
+        }
+        Ok(false)
+    }
+
+    /// Moves the cursor of the open dialog to row `index`.
+    fn select_item(&mut self, index: usize) {
+        match &mut self.mode {
+            InputMode::Profiles(picker) if index < picker.targets.len() => {
+                picker.selected = index;
+            }
+            InputMode::Columns(picker) if index < picker.entries.len() => picker.cursor = index,
+            InputMode::Views(picker) if index < self.views.len() => picker.selected = index,
+            InputMode::Export(state) if index < EXPORT_COLUMNS.len() + 2 => state.cursor = index,
+            InputMode::Preferences(PreferencesState {
+                view: PreferencesView::Ready(form),
+            }) if form.editing.is_none() && index < PREFERENCE_FORM_FIELDS.len() => {
+                form.selected = index;
+            }
+            _ => {}
+        }
+    }
+
+    /// Does what Enter or Space would on the dialog's current row.
+    fn activate_item(&mut self, router: &RpcRouter) -> Result<bool> {
+        match &self.mode {
+            InputMode::Profiles(_) => self.handle_action(KeyMode::Profiles, Action::Select, router),
+            InputMode::Columns(_) => self.handle_action(KeyMode::Columns, Action::Toggle, router),
+            InputMode::Views(_) => self.handle_action(KeyMode::Views, Action::Select, router),
+            InputMode::Export(state) if state.cursor != 1 => self.press(KeyCode::Char(' '), router),
+            InputMode::Preferences(state) if state.is_editing() => {
+                self.press(KeyCode::Enter, router)
+            }
+            InputMode::Preferences(_) => {
+                self.handle_action(KeyMode::Preferences, Action::Select, router)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Feeds a key press for a dialog button through the normal key path.
+    fn press(&mut self, code: KeyCode, router: &RpcRouter) -> Result<bool> {
+        self.handle_input(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)), router)
+    }
+
+    /// Writes the filtered list with the dialog's format and columns. Errors
+    /// keep the dialog open with the message shown inline.
+    fn export_view(&mut self) {
+        let InputMode::Export(state) = &mut self.mode else {
+            return;
+        };
+        let columns: Vec<String> = EXPORT_COLUMNS
+            .iter()
+            .zip(&state.columns)
+            .filter(|(_, enabled)| **enabled)
+            .map(|(column, _)| column.to_string())
+            .collect();
+        let torrents: Vec<&TorrentSummary> = self
+            .filtered_indices
+            .iter()
+            .filter_map(|&idx| self.snapshot.as_ref()?.torrents.get(idx))
+            .collect();
+        let path = state.path.trim().to_string();
+        let result = if columns.is_empty() {
+            Err(anyhow::anyhow!("Select at least one column"))
+        } else if path.is_empty() {
+            Err(anyhow::anyhow!("Enter a file path"))
+        } else {
+            write_export(&path, &torrents, state.format, &columns)
+        };
+        match result {
+            Ok(()) => {
+                let count = torrents.len();
+                state.message = None;
+                self.last_export = Some(state.clone());
+                self.mode = InputMode::Normal;
+                self.set_status(StatusUpdate::success(format!(
+                    "Exported {count} torrents to {path}"
+                )));
+            }
+            Err(err) => state.message = Some(format!("{err:#}")),
+        }
+    }
+
+    fn open_preferences(&mut self, router: &RpcRouter) {
+        let daemon = self.target_daemon();
+        self.preferences_daemon = daemon;
+        let mut state = if let Some(cache) = &self.daemons[daemon].preferences_cache {
             PreferencesState::from_cache(cache.clone())
         } else {
             PreferencesState::loading()
         };
         state.mark_refreshing();
         self.mode = InputMode::Preferences(state);
-        if rpc_tx.send(RpcCommand::FetchPreferences).is_err() {
+        if router.send(daemon, RpcCommand::FetchPreferences).is_err() {
             self.set_status(StatusUpdate::error("Failed to request preferences"));
         }
     }
 
-    fn apply_preferences_event(&mut self, result: RpcResult<DaemonPreferences>) {
+    fn apply_preferences_event(&mut self, daemon: usize, result: RpcResult<DaemonPreferences>) {
+        let shown = daemon == self.preferences_daemon;
         match result {
             Ok(prefs) => {
-                self.preferences_cache = Some(prefs.clone());
-                if let InputMode::Preferences(state) = &mut self.mode {
+                self.daemons[daemon].preferences_cache = Some(prefs.clone());
+                if let (true, InputMode::Preferences(state)) = (shown, &mut self.mode) {
                     state.apply_loaded(prefs);
                 }
             }
             Err(err) => {
                 let message = format!("Preferences error: {err}");
-                if let InputMode::Preferences(state) = &mut self.mode {
+                if let (true, InputMode::Preferences(state)) = (shown, &mut self.mode) {
                     state.apply_error(format!("{err}"));
                 }
-                self.set_status(StatusUpdate::error(message));
+                let update = self.tag_status(daemon, StatusUpdate::error(message));
+                self.set_status(update);
             }
         }
     }
 
-    fn handle_normal_key(&mut self, key: KeyEvent, rpc_tx: &Sender<RpcCommand>) -> Result<bool> {
-        let plain_d = matches!(key.code, KeyCode::Char('d')) && key.modifiers.is_empty();
-        let destructive_key =
-            matches!(key.code, KeyCode::Char('d') | KeyCode::Char('D')) && key.modifiers.is_empty();
-        if !destructive_key {
-            self.disarm_delete();
+    /// The key map that applies in the current mode, if any.
+    fn key_mode(&self) -> Option<KeyMode> {
+        match self.mode {
+            InputMode::Normal => Some(KeyMode::Normal),
+            InputMode::Confirm(_) => Some(KeyMode::Confirm),
+            InputMode::Help => Some(KeyMode::Help),
+            InputMode::Profiles(_) => Some(KeyMode::Profiles),
+            InputMode::Hooks { .. } => Some(KeyMode::Hooks),
+            InputMode::Rules { .. } => Some(KeyMode::Rules),
+            InputMode::Columns(_) => Some(KeyMode::Columns),
+            InputMode::Views(_) => Some(KeyMode::Views),
+            InputMode::Preferences(ref state) if !state.is_editing() => Some(KeyMode::Preferences),
+            _ => None,
         }
-        match key.code {
-            KeyCode::Char('q') => {
-                self.should_quit = true;
-                Ok(true)
+    }
+
+    /// Adds `chord` to the keys pressed so far. A key that continues no
+    /// binding drops the pending keys and is looked up on its own.
+    fn resolve_key(&mut self, mode: KeyMode, chord: KeyChord) -> Option<Action> {
+        self.pending_keys.push(chord);
+        let mut lookup = self.keymap.lookup(mode, &self.pending_keys);
+        if lookup == KeyLookup::None && self.pending_keys.len() > 1 {
+            self.pending_keys = vec![chord];
+            lookup = self.keymap.lookup(mode, &self.pending_keys);
+        }
+        match lookup {
+            KeyLookup::Action(action) => {
+                self.clear_pending_keys();
+                Some(action)
             }
-            KeyCode::Char('r') => {
-                self.disarm_delete();
-                if key.modifiers.contains(KeyModifiers::SHIFT) {
-                    self.queue_refresh(rpc_tx);
-                } else {
-                    self.resume_selected_torrent(rpc_tx);
-                }
-                Ok(false)
+            KeyLookup::Prefix(candidates) => {
+                self.pending_until = Some(Instant::now() + KEY_SEQUENCE_TIMEOUT);
+                let pressed = self.pending_keys.len();
+                let hint = match candidates.as_slice() {
+                    [(keys, action)] => format!(
+                        "Press {} to {}",
+                        keymap::format_sequence(&keys[pressed..]),
+                        action.description().to_lowercase()
+                    ),
+                    _ => format!("{}…", keymap::format_sequence(&self.pending_keys)),
+                };
+                self.set_status(StatusUpdate::info(hint));
+                None
             }
-            KeyCode::Char('R') => {
-                self.disarm_delete();
-                self.queue_refresh(rpc_tx);
-                Ok(false)
+            KeyLookup::None => {
+                self.clear_pending_keys();
+                None
             }
-            KeyCode::Char('p') => {
-                self.disarm_delete();
-                self.pause_selected_torrent(rpc_tx);
-                Ok(false)
+        }
+    }
+
+    fn clear_pending_keys(&mut self) {
+        self.pending_keys.clear();
+        self.pending_until = None;
+    }
+
+    fn handle_action(&mut self, mode: KeyMode, action: Action, router: &RpcRouter) -> Result<bool> {
+        match mode {
+            KeyMode::Normal => return self.handle_normal_action(action, router),
+            KeyMode::Confirm => self.handle_confirm_action(action, router),
+            KeyMode::Help => {
+                if action == Action::Close {
+                    self.mode = InputMode::Normal;
+                }
             }
-            KeyCode::Char('a') => {
-                self.disarm_delete();
-                self.mode = InputMode::Prompt(PromptState::new("Add magnet"));
-                Ok(false)
+            KeyMode::Profiles => {
+                let InputMode::Profiles(picker) = &mut self.mode else {
+                    return Ok(false);
+                };
+                match action {
+                    Action::Down => {
+                        picker.selected =
+                            (picker.selected + 1).min(picker.targets.len().saturating_sub(1));
+                    }
+                    Action::Up => picker.selected = picker.selected.saturating_sub(1),
+                    Action::Select => {
+                        let target = picker.targets.get(picker.selected).copied();
+                        self.mode = InputMode::Normal;
+                        if let Some(target) = target.filter(|&t| t != self.view) {
+                            self.pending_profile_switch = Some(target);
+                        }
+                    }
+                    Action::Close => self.mode = InputMode::Normal,
+                    _ => {}
+                }
             }
-            KeyCode::Char('/') => {
-                self.disarm_delete();
-                self.mode = InputMode::Filter {
-                    buffer: self.filter_text.clone(),
+            KeyMode::Columns => {
+                let InputMode::Columns(picker) = &mut self.mode else {
+                    return Ok(false);
                 };
-                Ok(false)
+                match action {
+                    Action::Down => {
+                        picker.cursor = (picker.cursor + 1).min(picker.entries.len() - 1);
+                    }
+                    Action::Up => picker.cursor = picker.cursor.saturating_sub(1),
+                    Action::MoveDown => picker.shift(1),
+                    Action::MoveUp => picker.shift(-1),
+                    Action::Toggle => {
+                        let (column, shown) = &mut picker.entries[picker.cursor];
+                        if *column != Column::Name {
+                            *shown = !*shown;
+                        }
+                    }
+                    Action::Accept => {
+                        self.columns = picker.chosen();
+                        self.mode = InputMode::Normal;
+                    }
+                    Action::Cancel => self.mode = InputMode::Normal,
+                    _ => {}
+                }
             }
-            KeyCode::Char('j') => {
-                self.move_selection(1);
-                Ok(false)
+            KeyMode::Views => {
+                let InputMode::Views(picker) = &mut self.mode else {
+                    return Ok(false);
+                };
+                match action {
+                    Action::Down => {
+                        picker.selected =
+                            (picker.selected + 1).min(self.views.len().saturating_sub(1));
+                    }
+                    Action::Up => picker.selected = picker.selected.saturating_sub(1),
+                    Action::Select => {
+                        let selected = picker.selected;
+                        self.mode = InputMode::Normal;
+                        self.apply_view(selected);
+                    }
+                    Action::SaveView => {
+                        let mut line = CommandLine::new();
+                        line.buffer = "view save ".to_string();
+                        self.mode = InputMode::Command(line);
+                    }
+                    Action::Close => self.mode = InputMode::Normal,
+                    _ => {}
+                }
             }
-            KeyCode::Char('k') => {
-                self.move_selection(-1);
-                Ok(false)
+            KeyMode::Preferences => {
+                let InputMode::Preferences(state) = &mut self.mode else {
+                    return Ok(false);
+                };
+                let result = state.handle_action(action);
+                if let Some(cmd) = result.command {
+                    let is_fetch = matches!(&cmd, RpcCommand::FetchPreferences);
+                    if router.send(self.preferences_daemon, cmd).is_err() {
+                        if is_fetch {
+                            state.apply_error("Failed to queue preferences refresh".into());
+                        } else if let PreferencesView::Ready(form) = &mut state.view {
+                            form.saving = false;
+                            form.message = Some("Failed to queue save".into());
+                        }
+                        self.set_status(StatusUpdate::error("Failed to queue preferences command"));
+                    }
+                }
+                if result.close {
+                    self.mode = InputMode::Normal;
+                }
             }
-            KeyCode::Char('g') => {
-                if key.modifiers.contains(KeyModifiers::SHIFT) {
-                    self.goto_bottom();
-                } else {
-                    self.goto_top();
+            KeyMode::Hooks | KeyMode::Rules => {
+                let lines = match mode {
+                    KeyMode::Hooks => self.hooks.recent().len(),
+                    _ => self.rule_lines().len(),
+                };
+                let (InputMode::Hooks { scroll } | InputMode::Rules { scroll }) = &mut self.mode
+                else {
+                    return Ok(false);
+                };
+                match action {
+                    Action::Down => *scroll = (*scroll + 1).min(lines.saturating_sub(1)),
+                    Action::Up => *scroll = scroll.saturating_sub(1),
+                    Action::Close => self.mode = InputMode::Normal,
+                    _ => {}
                 }
-                Ok(false)
             }
-            KeyCode::Char('G') => {
-                self.goto_bottom();
-                Ok(false)
+        }
+        Ok(false)
+    }
+
+    fn handle_confirm_action(&mut self, action: Action, router: &RpcRouter) {
+        let InputMode::Confirm(confirm) = &self.mode else {
+            return;
+        };
+        match action {
+            Action::Accept => {
+                let info = format!("Removing {}…", confirm.target_name);
+                let cmd = RpcCommand::RemoveTorrent {
+                    id: confirm.target_id,
+                    name: confirm.target_name.clone(),
+                    delete_data: confirm.delete_data,
+                };
+                let daemon = confirm.target_daemon;
+                self.mode = InputMode::Normal;
+                self.set_status(StatusUpdate::info(info));
+                if router.send(daemon, cmd).is_err() {
+                    self.set_status(StatusUpdate::error("Failed to queue deletion"));
+                }
             }
-            KeyCode::Char('o') => {
-                self.disarm_delete();
-                self.open_preferences(rpc_tx);
-                Ok(false)
+            Action::Cancel => {
+                self.mode = InputMode::Normal;
+                self.set_status(StatusUpdate::info("Deletion cancelled"));
             }
-            KeyCode::Char('?') => {
-                self.disarm_delete();
-                self.mode = InputMode::Help;
-                Ok(false)
+            _ => {}
+        }
+    }
+
+    fn handle_normal_action(&mut self, action: Action, router: &RpcRouter) -> Result<bool> {
+        match action {
+            Action::Quit => {
+                self.should_quit = true;
+                return Ok(true);
             }
-            KeyCode::Char('d') if plain_d => {
-                if self.delete_armed {
-                    self.disarm_delete();
-                    self.prompt_delete_current(false);
-                } else {
-                    self.arm_delete();
-                }
-                Ok(false)
+            Action::Resume => self.resume_selected_torrent(router),
+            Action::Refresh => self.queue_refresh(router),
+            Action::Pause => self.pause_selected_torrent(router),
+            Action::AddMagnet => self.mode = InputMode::Prompt(PromptState::new("Add magnet")),
+            Action::Reannounce => self.reannounce_selected_torrent(router),
+            Action::Filter => {
+                self.mode = InputMode::Filter {
+                    buffer: self.filter_text.clone(),
+                    previous: self.filter_text.clone(),
+                };
             }
-            KeyCode::Char('D') => {
-                if self.trash_armed {
-                    self.disarm_delete();
-                    self.prompt_delete_current(true);
-                } else {
-                    self.arm_trash();
-                }
-                Ok(false)
+            Action::ClearFilter => self.clear_filter(),
+            Action::Command => self.mode = InputMode::Command(CommandLine::new()),
+            Action::Down => self.move_selection(1),
+            Action::Up => self.move_selection(-1),
+            Action::PageDown => self.move_selection(5),
+            Action::PageUp => self.move_selection(-5),
+            Action::Top => self.goto_top(),
+            Action::Bottom => self.goto_bottom(),
+            Action::Preferences => self.open_preferences(router),
+            Action::CycleDaemon => self.cycle_daemon_filter(),
+            Action::Export => {
+                let state = self.last_export.clone().unwrap_or_else(ExportState::new);
+                self.mode = InputMode::Export(state);
             }
-            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.move_selection(5);
-                Ok(false)
+            Action::Hooks => self.mode = InputMode::Hooks { scroll: 0 },
+            Action::Rules => self.mode = InputMode::Rules { scroll: 0 },
+            Action::Columns => self.mode = InputMode::Columns(ColumnPicker::new(&self.columns)),
+            Action::Views => {
+                let selected = self
+                    .active_view()
+                    .and_then(|active| self.views.iter().position(|view| view == active))
+                    .unwrap_or(0);
+                self.mode = InputMode::Views(ViewPicker { selected });
             }
-            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.move_selection(-5);
-                Ok(false)
+            Action::Profiles => {
+                let targets = self.picker_targets();
+                let selected = targets
+                    .iter()
+                    .position(|&target| target == self.view)
+                    .unwrap_or(0);
+                self.mode = InputMode::Profiles(ProfilePicker { targets, selected });
             }
-            KeyCode::Esc => {
-                self.clear_filter();
-                Ok(false)
+            Action::Help => self.mode = InputMode::Help,
+            Action::Details => self.details_zoomed = !self.details_zoomed,
+            Action::Remove => self.prompt_delete_current(false),
+            Action::Trash => self.prompt_delete_current(true),
+            Action::Accept
+            | Action::Cancel
+            | Action::Select
+            | Action::Close
+            | Action::Toggle
+            | Action::MoveDown
+            | Action::MoveUp
+            | Action::SaveView
+            | Action::Previous
+            | Action::Next
+            | Action::Save => {}
+        }
+        Ok(false)
+    }
+
+    fn proxy_label(&self) -> Option<String> {
+        match self.view {
+            ProfileTarget::Single(index) => self.profiles[index].proxy.clone(),
+            ProfileTarget::All => None,
+        }
+    }
+
+    fn profile_prefix(&self) -> String {
+        match self.view {
+            ProfileTarget::Single(index) if self.profiles.len() > 1 => {
+                format!("[{}] ", self.profiles[index].name)
             }
-            _ => Ok(false),
+            _ => String::new(),
+        }
+    }
+
+    /// Entries offered by the profile picker: every profile, then the merged
+    /// view when there is more than one.
+    fn picker_targets(&self) -> Vec<ProfileTarget> {
+        let mut targets: Vec<ProfileTarget> = (0..self.profiles.len())
+            .map(ProfileTarget::Single)
+            .collect();
+        if self.profiles.len() > 1 {
+            targets.push(ProfileTarget::All);
+        }
+        targets
+    }
+
+    /// Swaps the UI over to another view after its RPC workers are running,
+    /// parking the current filter/selection and restoring the target's.
+    fn activate_view(&mut self, target: ProfileTarget) {
+        self.profile_views.insert(
+            self.view,
+            ProfileView {
+                filter_text: self.filter_text.clone(),
+                daemon_filter: self.daemon_filter.take(),
+                selected: self.selected_id.take(),
+            },
+        );
+        let view = self.profile_views.remove(&target).unwrap_or_default();
+        self.view = target;
+        self.attach_daemons();
+        self.snapshot = None;
+        self.filtered_indices.clear();
+        self.list_state.select(None);
+        self.filter_lower = view.filter_text.to_lowercase();
+        self.filter_text = view.filter_text;
+        self.daemon_filter = view.daemon_filter;
+        self.pending_focus = view.selected;
+        let label = match target {
+            ProfileTarget::Single(index) => format!("profile {}", self.profiles[index].name),
+            ProfileTarget::All => "all profiles".to_string(),
+        };
+        self.set_status(StatusUpdate::info(format!("Switched to {label}")));
+    }
+
+    /// Steps the daemon filter through each daemon of the merged view and
+    /// back to showing all of them.
+    fn cycle_daemon_filter(&mut self) {
+        if !self.aggregated() {
+            self.set_status(StatusUpdate::info(
+                "Daemon filter applies to the all-profiles view",
+            ));
+            return;
+        }
+        let next = match &self.daemon_filter {
+            None => 0,
+            Some(name) => self.daemon_index(name).map_or(0, |idx| idx + 1),
+        };
+        self.daemon_filter =
+            (next < self.daemons.len()).then(|| self.daemon_name(next).to_string());
+        self.rebuild_indices();
+        let label = self.daemon_filter.as_deref().unwrap_or("all daemons");
+        self.set_status(StatusUpdate::info(format!("Showing {label}")));
+    }
+
+    /// Selected peer address and scroll offset of the peer table for
+    /// `torrent`; both reset when another torrent is shown.
+    fn peer_focus(&self, torrent: &TorrentSummary) -> (Option<&str>, usize) {
+        match &self.peer_focus {
+            Some(focus) if focus.torrent == torrent.key() => {
+                (focus.selected.as_deref(), focus.offset)
+            }
+            _ => (None, 0),
+        }
+    }
+
+    fn current_peer_focus(&mut self) -> Option<&mut PeerFocus> {
+        let torrent = self.current_torrent()?.key();
+        if self.peer_focus.as_ref().map(|focus| &focus.torrent) != Some(&torrent) {
+            self.peer_focus = Some(PeerFocus {
+                torrent,
+                selected: None,
+                offset: 0,
+            });
+        }
+        self.peer_focus.as_mut()
+    }
+
+    fn scroll_peers(&mut self, delta: isize) {
+        let Some(count) = self.current_torrent().map(|torrent| torrent.peers.len()) else {
+            return;
+        };
+        if let Some(focus) = self.current_peer_focus() {
+            focus.offset = focus
+                .offset
+                .saturating_add_signed(delta)
+                .min(count.saturating_sub(1));
         }
     }
 
@@ -1298,7 +3544,7 @@ impl App {
     }
 
     fn update_selected_id(&mut self) {
-        self.selected_id = self.current_torrent().map(|t| t.torrent_id);
+        self.selected_id = self.current_torrent().map(TorrentSummary::key);
     }
 
     fn current_torrent(&self) -> Option<&TorrentSummary> {
@@ -1325,11 +3571,40 @@ impl App {
 
     fn rebuild_indices(&mut self) {
         self.filtered_indices.clear();
+        self.name_matches.clear();
         if let Some(snapshot) = &self.snapshot {
+            let fuzzy = self.fuzzy_filter && !self.filter_lower.trim().is_empty();
+            let mut scores = HashMap::new();
             for (idx, torrent) in snapshot.torrents.iter().enumerate() {
-                if self.matches_filter(torrent) {
-                    self.filtered_indices.push(idx);
+                if !self.matches_daemon(torrent) {
+                    continue;
                 }
+                let Some(found) = name_filter_match(
+                    &self.filter_text,
+                    &self.filter_lower,
+                    self.fuzzy_filter,
+                    &torrent.name,
+                ) else {
+                    continue;
+                };
+                if fuzzy {
+                    scores.insert(idx, found.score);
+                    self.name_matches.insert(idx, found.positions);
+                }
+                self.filtered_indices.push(idx);
+            }
+            if fuzzy && self.sort.is_none() {
+                self.filtered_indices
+                    .sort_by_key(|idx| std::cmp::Reverse(scores[idx]));
+            }
+            if let Some((column, order)) = self.sort {
+                self.filtered_indices.sort_by(|&a, &b| {
+                    let ordering = column.compare(&snapshot.torrents[a], &snapshot.torrents[b]);
+                    match order {
+                        SortOrder::Ascending => ordering,
+                        SortOrder::Descending => ordering.reverse(),
+                    }
+                });
             }
         }
         if self.filtered_indices.is_empty() {
@@ -1337,12 +3612,11 @@ impl App {
             self.selected_id = None;
             return;
         }
-        if let Some(target) = self.pending_focus.take().or(self.selected_id) {
-            if let Some(pos) = self
-                .filtered_indices
-                .iter()
-                .position(|&idx| self.snapshot.as_ref().unwrap().torrents[idx].torrent_id == target)
-            {
+        if let Some(target) = self.pending_focus.take().or(self.selected_id.take()) {
+            if let Some(pos) = self.filtered_indices.iter().position(|&idx| {
+                let torrent = &self.snapshot.as_ref().unwrap().torrents[idx];
+                torrent.torrent_id == target.id && torrent.daemon == target.daemon
+            }) {
                 self.list_state.select(Some(pos));
                 self.selected_id = Some(target);
                 return;
@@ -1354,11 +3628,93 @@ impl App {
         self.update_selected_id();
     }
 
-    fn matches_filter(&self, torrent: &TorrentSummary) -> bool {
-        if self.filter_lower.is_empty() {
-            return true;
+    fn matches_daemon(&self, torrent: &TorrentSummary) -> bool {
+        self.daemon_filter
+            .as_ref()
+            .is_none_or(|daemon| *daemon == torrent.daemon)
+    }
+
+    /// Torrents `view` would list right now, under the current daemon filter.
+    fn view_match_count(&self, view: &View) -> usize {
+        let lower = view.filter.to_lowercase();
+        self.snapshot
+            .iter()
+            .flat_map(|snapshot| &snapshot.torrents)
+            .filter(|torrent| {
+                self.matches_daemon(torrent)
+                    && name_filter_match(&view.filter, &lower, view.fuzzy, &torrent.name).is_some()
+            })
+            .count()
+    }
+
+    /// The saved view the list currently shows, if any.
+    fn active_view(&self) -> Option<&View> {
+        self.views.iter().find(|view| {
+            view.filter == self.filter_text
+                && (view.fuzzy == self.fuzzy_filter || view.filter.is_empty())
+                && view.sort == self.sort
+                && view
+                    .columns
+                    .as_ref()
+                    .is_none_or(|columns| *columns == self.columns)
+        })
+    }
+
+    /// Applies the view at `index` in `views`, as number keys and the
+    /// views picker do.
+    fn apply_view(&mut self, index: usize) {
+        let Some(view) = self.views.get(index).cloned() else {
+            self.set_status(StatusUpdate::info(format!("No view {}", index + 1)));
+            return;
+        };
+        self.fuzzy_filter = view.fuzzy;
+        self.sort = view.sort;
+        if let Some(columns) = view.columns {
+            self.columns = columns;
+        }
+        self.apply_filter_text(view.filter);
+        self.set_status(StatusUpdate::info(format!(
+            "View {}: {} torrents",
+            view.name,
+            self.filtered_indices.len()
+        )));
+    }
+
+    /// Saves the current filter, sort and columns as a view, appending it
+    /// to the config file.
+    fn save_view(&mut self, name: String) {
+        if self.views.iter().any(|view| view.name == name) {
+            self.set_status(StatusUpdate::error(format!(
+                "A view named '{name}' already exists"
+            )));
+            return;
+        }
+        let view = View {
+            name: name.clone(),
+            filter: self.filter_text.clone(),
+            fuzzy: self.fuzzy_filter,
+            sort: self.sort,
+            columns: Some(self.columns.clone()),
+        };
+        let saved = match &self.config_path {
+            Some(path) => views::append(path, &view).map(|()| path.display().to_string()),
+            None => Err(anyhow::anyhow!("no config file location")),
+        };
+        self.views.push(view);
+        let number = self.views.len();
+        let key = if number <= 9 {
+            format!(" (key {number})")
+        } else {
+            String::new()
+        };
+        match saved {
+            Ok(path) => self.set_status(StatusUpdate::success(format!(
+                "Saved view {name}{key} to {path}"
+            ))),
+            Err(err) => self.set_status(StatusUpdate::warning(format!(
+                "View {name}{key} kept for this session only: {err:#}"
+            ))),
         }
-        torrent.name.to_lowercase().contains(&self.filter_lower)
     }
 
     fn expire_status(&mut self) {
@@ -1368,74 +3724,235 @@ impl App {
                     self.status = None;
                 }
             }
-        }
-        if let Some(toast) = &self.toast {
-            if let Some(expiry) = toast.expires_at {
-                if Instant::now() >= expiry {
-                    self.toast = None;
+        }
+        if let Some(toast) = &self.toast {
+            if let Some(expiry) = toast.expires_at {
+                if Instant::now() >= expiry {
+                    self.toast = None;
+                }
+            }
+        }
+        if self
+            .pending_until
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.clear_pending_keys();
+        }
+    }
+
+    fn set_status(&mut self, update: StatusUpdate) {
+        let message = StatusMessage::from_update(update.clone());
+        if update.toast || matches!(update.level, StatusLevel::Warning | StatusLevel::Error) {
+            self.toast = Some(message.clone());
+        }
+        self.status = Some(message);
+    }
+
+    fn handle_command_key(&mut self, key: KeyEvent, router: &RpcRouter) -> Result<bool> {
+        let context = self.command_context();
+        let InputMode::Command(line) = &mut self.mode else {
+            return Ok(false);
+        };
+        match line.handle_key(key, &self.command_history, &context) {
+            PromptAction::None => Ok(false),
+            PromptAction::Cancel => {
+                self.mode = InputMode::Normal;
+                Ok(false)
+            }
+            PromptAction::Submit(input) => {
+                self.mode = InputMode::Normal;
+                self.run_command(input, router)
+            }
+        }
+    }
+
+    /// Profiles, labels, views and `move` paths the command line can match
+    /// and complete. Paths are those of the selected torrent's daemon.
+    fn command_context(&self) -> cmdline::Context {
+        let mut profiles: Vec<String> = self.profiles.iter().map(|p| p.name.clone()).collect();
+        if self.profiles.len() > 1 {
+            profiles.push("all".to_string());
+        }
+        let mut labels: Vec<String> = self
+            .snapshot
+            .iter()
+            .flat_map(|snapshot| &snapshot.torrents)
+            .flat_map(|torrent| torrent.labels.iter().cloned())
+            .collect();
+        labels.sort();
+        labels.dedup();
+        let daemon = self.focused_daemon();
+        let mut download_dirs: Vec<String> = self
+            .snapshot
+            .iter()
+            .flat_map(|snapshot| &snapshot.torrents)
+            .filter(|torrent| torrent.daemon == self.daemon_name(daemon))
+            .map(|torrent| torrent.download_dir.clone())
+            .collect();
+        download_dirs.sort();
+        download_dirs.dedup();
+        cmdline::Context {
+            profiles,
+            labels,
+            selected_labels: self
+                .current_torrent()
+                .map(|torrent| torrent.labels.clone())
+                .unwrap_or_default(),
+            views: self.views.iter().map(|view| view.name.clone()).collect(),
+            download_dirs,
+            local_paths: self
+                .daemons
+                .get(daemon)
+                .is_some_and(|state| self.profiles[state.profile].local),
+        }
+    }
+
+    fn run_command(&mut self, input: String, router: &RpcRouter) -> Result<bool> {
+        if input.is_empty() {
+            return Ok(false);
+        }
+        if self.command_history.last() != Some(&input) {
+            self.command_history.push(input.clone());
+            if self.command_history.len() > COMMAND_HISTORY_LIMIT {
+                self.command_history.remove(0);
+            }
+        }
+        let command = match cmdline::parse(&input, &self.command_context()) {
+            Ok(command) => command,
+            Err(err) => {
+                self.set_status(StatusUpdate::error(format!("{err:#}")));
+                return Ok(false);
+            }
+        };
+        match command {
+            Command::Pause => self.pause_selected_torrent(router),
+            Command::Resume => self.resume_selected_torrent(router),
+            Command::Reannounce => self.reannounce_selected_torrent(router),
+            Command::Refresh => self.queue_refresh(router),
+            Command::Quit => {
+                self.should_quit = true;
+                return Ok(true);
+            }
+            Command::Filter(text) => self.apply_filter_text(text),
+            Command::View(name) => {
+                if let Some(index) = self.views.iter().position(|view| view.name == name) {
+                    self.apply_view(index);
                 }
             }
-        }
-        if self.delete_armed {
-            if let Some(deadline) = self.delete_armed_until {
-                if Instant::now() >= deadline {
-                    self.disarm_delete();
+            Command::SaveView(name) => self.save_view(name),
+            Command::Sort(sort) => {
+                self.sort = sort;
+                self.rebuild_indices();
+                let message = match sort {
+                    Some((column, SortOrder::Ascending)) => format!("Sorted by {}", column.title()),
+                    Some((column, SortOrder::Descending)) => {
+                        format!("Sorted by {}, descending", column.title())
+                    }
+                    None => "Sorting off".to_string(),
+                };
+                self.set_status(StatusUpdate::info(message));
+            }
+            Command::Profile(name) => {
+                let target = if name == "all" && self.profiles.len() > 1 {
+                    Some(ProfileTarget::All)
+                } else {
+                    self.profiles
+                        .iter()
+                        .position(|profile| profile.name == name)
+                        .map(ProfileTarget::Single)
+                };
+                match target {
+                    Some(target) if target == self.view => {
+                        self.set_status(StatusUpdate::info(format!("Already showing {name}")));
+                    }
+                    Some(target) => self.pending_profile_switch = Some(target),
+                    None => {}
                 }
             }
-        }
-        if self.trash_armed {
-            if let Some(deadline) = self.trash_armed_until {
-                if Instant::now() >= deadline {
-                    self.disarm_delete();
+            Command::Move(location) => {
+                self.send_for_selected("move", router, |id, name| RpcCommand::MoveTorrent {
+                    id,
+                    name,
+                    location,
+                });
+            }
+            Command::Limit { direction, limit } => {
+                self.send_for_selected("limit", router, |id, name| RpcCommand::SetSpeedLimit {
+                    id,
+                    name,
+                    direction,
+                    limit,
+                });
+            }
+            Command::Label(op, labels) => {
+                if !self.supports(|caps| caps.labels) {
+                    self.set_status(StatusUpdate::warning(
+                        "Labels are not supported by this daemon",
+                    ));
+                    return Ok(false);
                 }
+                let mut current = self
+                    .current_torrent()
+                    .map(|torrent| torrent.labels.clone())
+                    .unwrap_or_default();
+                match op {
+                    LabelOp::Add => {
+                        for label in labels {
+                            if !current.contains(&label) {
+                                current.push(label);
+                            }
+                        }
+                    }
+                    LabelOp::Remove => current.retain(|label| !labels.contains(label)),
+                    LabelOp::Set => current = labels,
+                    LabelOp::Clear => current.clear(),
+                }
+                self.send_for_selected("label", router, |id, name| RpcCommand::SetLabels {
+                    id,
+                    name,
+                    labels: current,
+                });
             }
         }
+        Ok(false)
     }
 
-    fn set_status(&mut self, update: StatusUpdate) {
-        let message = StatusMessage::from_update(update.clone());
-        if matches!(update.level, StatusLevel::Warning | StatusLevel::Error) {
-            self.toast = Some(message.clone());
+    /// Queues the command `build` makes from the selected torrent's id and
+    /// name on that torrent's daemon.
+    fn send_for_selected(
+        &mut self,
+        verb: &str,
+        router: &RpcRouter,
+        build: impl FnOnce(i64, String) -> RpcCommand,
+    ) {
+        let Some(torrent) = self.current_torrent() else {
+            self.set_status(StatusUpdate::warning(format!(
+                "No torrent selected; cannot {verb}"
+            )));
+            return;
+        };
+        let daemon = self.daemon_index(&torrent.daemon).unwrap_or(0);
+        let command = build(torrent.torrent_id, torrent.name.clone());
+        if router.send(daemon, command).is_err() {
+            self.set_status(StatusUpdate::error(format!("Failed to queue {verb}")));
         }
-        self.status = Some(message);
-    }
-
-    fn disarm_delete(&mut self) {
-        self.delete_armed = false;
-        self.delete_armed_until = None;
-        self.trash_armed = false;
-        self.trash_armed_until = None;
     }
 
-    fn queue_refresh(&mut self, rpc_tx: &Sender<RpcCommand>) {
+    fn queue_refresh(&mut self, router: &RpcRouter) {
         self.pending_manual_refresh = true;
         self.set_status(StatusUpdate::info("Refreshing…"));
-        if rpc_tx.send(RpcCommand::Refresh).is_err() {
+        if !router.refresh_all() {
             self.set_status(StatusUpdate::error("Failed to queue refresh"));
         }
     }
 
-    fn arm_delete(&mut self) {
-        self.delete_armed = true;
-        self.delete_armed_until = Some(Instant::now() + Duration::from_secs(2));
-        self.set_status(StatusUpdate::info(
-            "Press d again to delete the selected torrent",
-        ));
-    }
-
-    fn arm_trash(&mut self) {
-        self.trash_armed = true;
-        self.trash_armed_until = Some(Instant::now() + Duration::from_secs(2));
-        self.set_status(StatusUpdate::info(
-            "Press D again to trash data and remove the selected torrent",
-        ));
-    }
-
     fn prompt_delete_current(&mut self, delete_data: bool) {
         if let Some(torrent) = self.current_torrent().cloned() {
+            let daemon = self.daemon_index(&torrent.daemon).unwrap_or(0);
             self.mode = InputMode::Confirm(ConfirmState::remove_torrent(
                 torrent.name.clone(),
                 torrent.torrent_id,
+                daemon,
                 delete_data,
             ));
         } else {
@@ -1443,12 +3960,16 @@ impl App {
         }
     }
 
-    fn resume_selected_torrent(&mut self, rpc_tx: &Sender<RpcCommand>) {
+    fn resume_selected_torrent(&mut self, router: &RpcRouter) {
         if let Some(torrent) = self.current_torrent().cloned() {
             let id = torrent.torrent_id;
             let name = torrent.name.clone();
+            let daemon = self.daemon_index(&torrent.daemon).unwrap_or(0);
             self.set_status(StatusUpdate::info(format!("Resuming {name}…")));
-            if rpc_tx.send(RpcCommand::ResumeTorrent { id, name }).is_err() {
+            if router
+                .send(daemon, RpcCommand::ResumeTorrent { id, name })
+                .is_err()
+            {
                 self.set_status(StatusUpdate::error("Failed to queue resume"));
             }
         } else {
@@ -1456,12 +3977,16 @@ impl App {
         }
     }
 
-    fn pause_selected_torrent(&mut self, rpc_tx: &Sender<RpcCommand>) {
+    fn pause_selected_torrent(&mut self, router: &RpcRouter) {
         if let Some(torrent) = self.current_torrent().cloned() {
             let id = torrent.torrent_id;
             let name = torrent.name.clone();
+            let daemon = self.daemon_index(&torrent.daemon).unwrap_or(0);
             self.set_status(StatusUpdate::info(format!("Pausing {name}…")));
-            if rpc_tx.send(RpcCommand::PauseTorrent { id, name }).is_err() {
+            if router
+                .send(daemon, RpcCommand::PauseTorrent { id, name })
+                .is_err()
+            {
                 self.set_status(StatusUpdate::error("Failed to queue pause"));
             }
         } else {
@@ -1469,17 +3994,54 @@ impl App {
         }
     }
 
-    fn apply_snapshot(&mut self, result: RpcResult<Snapshot>) {
+    fn reannounce_selected_torrent(&mut self, router: &RpcRouter) {
+        if !self.supports(|caps| caps.torrent_reannounce) {
+            self.set_status(StatusUpdate::warning(
+                "Reannounce is not supported by this daemon",
+            ));
+            return;
+        }
+        if let Some(torrent) = self.current_torrent().cloned() {
+            let id = torrent.torrent_id;
+            let name = torrent.name.clone();
+            let daemon = self.daemon_index(&torrent.daemon).unwrap_or(0);
+            self.set_status(StatusUpdate::info(format!("Reannouncing {name}…")));
+            if router
+                .send(daemon, RpcCommand::ReannounceTorrent { id, name })
+                .is_err()
+            {
+                self.set_status(StatusUpdate::error("Failed to queue reannounce"));
+            }
+        } else {
+            self.set_status(StatusUpdate::warning(
+                "No torrent selected; cannot reannounce",
+            ));
+        }
+    }
+
+    fn apply_snapshot(&mut self, daemon: usize, result: RpcResult<Snapshot>) {
         match result {
-            Ok(snapshot) => {
-                let focus = self.pending_focus.take().or(self.selected_id);
-                self.snapshot = Some(snapshot);
+            Ok(mut snapshot) => {
+                let name = self.daemon_name(daemon).to_string();
+                for torrent in &mut snapshot.torrents {
+                    torrent.daemon = name.clone();
+                }
+                if let Some(previous) = &self.daemons[daemon].snapshot {
+                    let changes =
+                        transitions::diff(previous, &snapshot, &self.hooks.ratio_targets());
+                    self.hooks.dispatch(&name, &changes);
+                    self.notifier.notify(&name, &changes);
+                }
+                self.daemons[daemon].snapshot = Some(snapshot);
+                let focus = self.pending_focus.take().or(self.selected_id.take());
+                self.snapshot =
+                    merge_snapshots(self.daemons.iter().filter_map(|d| d.snapshot.as_ref()));
                 self.selected_id = focus;
                 if self.selected_id.is_none() {
                     self.selected_id = self
                         .snapshot
                         .as_ref()
-                        .and_then(|snap| snap.torrents.first().map(|t| t.torrent_id));
+                        .and_then(|snap| snap.torrents.first().map(TorrentSummary::key));
                 }
                 self.rebuild_indices();
                 if self.pending_manual_refresh || self.status.is_none() {
@@ -1493,13 +4055,135 @@ impl App {
                 self.pending_manual_refresh = false;
             }
             Err(err) => {
-                self.set_status(StatusUpdate::error(format!("RPC error: {err}")));
+                // Poll failures are reported through the connection banner;
+                // only surface errors that arrive while the link looks healthy.
+                if matches!(
+                    self.daemons[daemon].connection.state,
+                    ConnectionState::Online
+                ) || self.pending_manual_refresh
+                {
+                    let update =
+                        self.tag_status(daemon, StatusUpdate::error(format!("RPC error: {err}")));
+                    self.set_status(update);
+                }
                 self.pending_manual_refresh = false;
             }
         }
     }
 }
 
+/// The `:` command line.
+struct CommandLine {
+    buffer: String,
+    /// History entry being shown while browsing with Up / Down.
+    history_index: Option<usize>,
+    /// What was typed before browsing the history.
+    draft: String,
+    tab: Option<TabCycle>,
+}
+
+/// Tab completion in progress: the candidates found on the first Tab and
+/// the one currently filled in.
+struct TabCycle {
+    start: usize,
+    candidates: Vec<cmdline::Candidate>,
+    index: usize,
+}
+
+impl CommandLine {
+    fn new() -> Self {
+        Self {
+            buffer: String::new(),
+            history_index: None,
+            draft: String::new(),
+            tab: None,
+        }
+    }
+
+    fn handle_key(
+        &mut self,
+        key: KeyEvent,
+        history: &[String],
+        context: &cmdline::Context,
+    ) -> PromptAction {
+        if !matches!(key.code, KeyCode::Tab | KeyCode::BackTab) {
+            self.tab = None;
+        }
+        match key.code {
+            KeyCode::Enter => return PromptAction::Submit(self.buffer.trim().to_string()),
+            KeyCode::Esc => return PromptAction::Cancel,
+            KeyCode::Backspace if self.buffer.is_empty() => return PromptAction::Cancel,
+            KeyCode::Backspace => {
+                self.buffer.pop();
+            }
+            KeyCode::Tab => self.cycle(true, context),
+            KeyCode::BackTab => self.cycle(false, context),
+            KeyCode::Up => self.browse(history, true),
+            KeyCode::Down => self.browse(history, false),
+            KeyCode::Char(c) => self.buffer.push(c),
+            _ => {}
+        }
+        PromptAction::None
+    }
+
+    /// Fills in the next (or previous) completion of the last word. A sole
+    /// candidate that is not a directory is followed by a space.
+    fn cycle(&mut self, forward: bool, context: &cmdline::Context) {
+        let tab = match &mut self.tab {
+            Some(tab) => {
+                let len = tab.candidates.len();
+                tab.index = if forward {
+                    (tab.index + 1) % len
+                } else {
+                    (tab.index + len - 1) % len
+                };
+                tab
+            }
+            None => {
+                let completion = cmdline::complete(&self.buffer, context);
+                if completion.candidates.is_empty() {
+                    return;
+                }
+                let index = if forward {
+                    0
+                } else {
+                    completion.candidates.len() - 1
+                };
+                self.tab.insert(TabCycle {
+                    start: completion.start,
+                    candidates: completion.candidates,
+                    index,
+                })
+            }
+        };
+        let chosen = &tab.candidates[tab.index].text;
+        self.buffer.truncate(tab.start);
+        self.buffer.push_str(chosen);
+        if tab.candidates.len() == 1 && !chosen.ends_with('/') {
+            self.buffer.push(' ');
+        }
+    }
+
+    fn browse(&mut self, history: &[String], older: bool) {
+        let index = match (self.history_index, older) {
+            (None, true) if !history.is_empty() => {
+                self.draft = self.buffer.clone();
+                history.len() - 1
+            }
+            (None, _) => return,
+            (Some(index), true) => index.saturating_sub(1),
+            (Some(index), false) if index + 1 < history.len() => index + 1,
+            (Some(_), false) => {
+                self.history_index = None;
+                self.buffer = std::mem::take(&mut self.draft);
+                return;
+            }
+        };
+        self.history_index = Some(index);
+        self.buffer = history[index].clone();
+    }
+}
+
 #[derive(Clone)]
 struct PromptState {
     title: &'static str,
@@ -1521,11 +4205,12 @@ struct ConfirmState {
     message: String,
     target_id: i64,
     target_name: String,
+    target_daemon: usize,
     delete_data: bool,
 }
 
 impl ConfirmState {
-    fn remove_torrent(name: String, id: i64, delete_data: bool) -> Self {
+    fn remove_torrent(name: String, id: i64, daemon: usize, delete_data: bool) -> Self {
         let (title, message) = if delete_data {
             (
                 "Trash data & remove",
@@ -1544,6 +4229,7 @@ impl ConfirmState {
             message,
             target_id: id,
             target_name: name,
+            target_daemon: daemon,
             delete_data,
         }
     }
@@ -1733,105 +4419,75 @@ impl PreferencesState {
         }
     }
 
-    fn handle_key(&mut self, key: KeyEvent) -> PreferenceInputResult {
+    fn is_editing(&self) -> bool {
+        matches!(&self.view, PreferencesView::Ready(form) if form.editing.is_some())
+    }
+
+    /// Keys typed into the value being edited; these stay fixed.
+    fn handle_edit_key(&mut self, key: KeyEvent) {
+        let PreferencesView::Ready(form) = &mut self.view else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                let _ = form.finish_edit();
+            }
+            KeyCode::Esc => form.cancel_edit(),
+            KeyCode::Backspace => form.pop_char(),
+            KeyCode::Char(c) => form.push_char(c),
+            _ => {}
+        }
+    }
+
+    fn handle_action(&mut self, action: Action) -> PreferenceInputResult {
+        let mut result = PreferenceInputResult {
+            close: action == Action::Close,
+            command: None,
+        };
         match &mut self.view {
-            PreferencesView::Loading => match key.code {
-                KeyCode::Char('r') | KeyCode::Char('R') => PreferenceInputResult {
-                    close: false,
-                    command: Some(RpcCommand::FetchPreferences),
-                },
-                KeyCode::Esc | KeyCode::Char('q') => PreferenceInputResult {
-                    close: true,
-                    command: None,
-                },
-                _ => PreferenceInputResult {
-                    close: false,
-                    command: None,
-                },
-            },
-            PreferencesView::Error(_) => match key.code {
-                KeyCode::Char('r') | KeyCode::Char('R') => {
+            PreferencesView::Loading => {
+                if action == Action::Refresh {
+                    result.command = Some(RpcCommand::FetchPreferences);
+                }
+            }
+            PreferencesView::Error(_) => {
+                if action == Action::Refresh {
                     self.view = PreferencesView::Loading;
-                    PreferenceInputResult {
-                        close: false,
-                        command: Some(RpcCommand::FetchPreferences),
-                    }
+                    result.command = Some(RpcCommand::FetchPreferences);
                 }
-                KeyCode::Esc | KeyCode::Char('q') => PreferenceInputResult {
-                    close: true,
-                    command: None,
-                },
-                _ => PreferenceInputResult {
-                    close: false,
-                    command: None,
-                },
-            },
-            PreferencesView::Ready(form) => {
-                if form.editing.is_some() {
-                    match key.code {
-                        KeyCode::Enter => {
-                            let _ = form.finish_edit();
-                        }
-                        KeyCode::Esc => form.cancel_edit(),
-                        KeyCode::Backspace => form.pop_char(),
-                        KeyCode::Char(c) => form.push_char(c),
-                        _ => {}
-                    }
-                    return PreferenceInputResult {
-                        close: false,
-                        command: None,
-                    };
+            }
+            PreferencesView::Ready(form) => match action {
+                Action::Down => form.move_selection(1),
+                Action::Up => form.move_selection(-1),
+                Action::Toggle => {
+                    form.toggle_selected();
                 }
-                match key.code {
-                    KeyCode::Char('j') | KeyCode::Down => form.move_selection(1),
-                    KeyCode::Char('k') | KeyCode::Up => form.move_selection(-1),
-                    KeyCode::Char(' ') => {
-                        form.toggle_selected();
-                    }
-                    KeyCode::Left => {
-                        form.cycle_encryption(-1);
-                    }
-                    KeyCode::Right => {
+                Action::Previous => {
+                    form.cycle_encryption(-1);
+                }
+                Action::Next => {
+                    form.cycle_encryption(1);
+                }
+                // Keep the side effects out of a match guard.
+                #[allow(clippy::collapsible_match)]
+                Action::Select => {
+                    if !form.start_editor() && !form.toggle_selected() {
                         form.cycle_encryption(1);
                     }
-                    KeyCode::Enter => {
-                        if !form.start_editor() && !form.toggle_selected() {
-                            form.cycle_encryption(1);
-                        }
-                    }
-                    KeyCode::Char('s') => {
-                        if let Some(cmd) = form.queue_save() {
-                            return PreferenceInputResult {
-                                close: false,
-                                command: Some(cmd),
-                            };
-                        }
-                    }
-                    KeyCode::Char('r') | KeyCode::Char('R') => {
-                        if form.dirty {
-                            form.message = Some("Save or cancel changes before refreshing".into());
-                        } else {
-                            self.view = PreferencesView::Loading;
-                            return PreferenceInputResult {
-                                close: false,
-                                command: Some(RpcCommand::FetchPreferences),
-                            };
-                        }
-                    }
-                    KeyCode::Esc | KeyCode::Char('q') => {
-                        return PreferenceInputResult {
-                            close: true,
-                            command: None,
-                        }
-                    }
-                    _ => {}
                 }
-                PreferenceInputResult {
-                    close: false,
-                    command: None,
+                Action::Save => result.command = form.queue_save(),
+                Action::Refresh => {
+                    if form.dirty {
+                        form.message = Some("Save or cancel changes before refreshing".into());
+                    } else {
+                        self.view = PreferencesView::Loading;
+                        result.command = Some(RpcCommand::FetchPreferences);
+                    }
                 }
-            }
+                _ => {}
+            },
         }
+        result
     }
 }
 
@@ -2179,17 +4835,217 @@ fn parse_positive(input: &str, label: &str) -> Result<u32, String> {
 
 enum InputMode {
     Normal,
-    Filter { buffer: String },
+    /// `previous` is restored by Esc; the list follows `buffer` as it is typed.
+    Filter {
+        buffer: String,
+        previous: String,
+    },
     Prompt(PromptState),
     Confirm(ConfirmState),
     Help,
     Preferences(PreferencesState),
+    Profiles(ProfilePicker),
+    Export(ExportState),
+    Hooks {
+        scroll: usize,
+    },
+    Rules {
+        scroll: usize,
+    },
+    Columns(ColumnPicker),
+    Views(ViewPicker),
+    Command(CommandLine),
+}
+
+/// Export dialog: row 0 is the format, row 1 the path, then one row per
+/// entry in `EXPORT_COLUMNS`.
+#[derive(Clone)]
+struct ExportState {
+    format: ExportFormat,
+    path: String,
+    columns: Vec<bool>,
+    cursor: usize,
+    message: Option<String>,
+}
+
+enum ExportAction {
+    None,
+    Submit,
+    Cancel,
+}
+
+impl ExportState {
+    fn new() -> Self {
+        let defaults = export::default_columns();
+        let format = ExportFormat::default();
+        Self {
+            format,
+            path: format!("torrents.{}", format.extension()),
+            columns: EXPORT_COLUMNS
+                .iter()
+                .map(|column| defaults.iter().any(|d| d == column))
+                .collect(),
+            cursor: 0,
+            message: None,
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> ExportAction {
+        let rows = EXPORT_COLUMNS.len() + 2;
+        let on_path = self.cursor == 1;
+        match key.code {
+            KeyCode::Esc => return ExportAction::Cancel,
+            KeyCode::Enter => return ExportAction::Submit,
+            KeyCode::Up | KeyCode::BackTab => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Down | KeyCode::Tab => self.cursor = (self.cursor + 1).min(rows - 1),
+            KeyCode::Char('k') if !on_path => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Char('j') if !on_path => self.cursor = (self.cursor + 1).min(rows - 1),
+            KeyCode::Backspace if on_path => {
+                self.path.pop();
+            }
+            KeyCode::Char(c) if on_path => self.path.push(c),
+            KeyCode::Left | KeyCode::Right | KeyCode::Char(' ') if self.cursor == 0 => {
+                self.cycle_format(key.code == KeyCode::Left);
+            }
+            KeyCode::Char(' ') => {
+                if let Some(enabled) = self.columns.get_mut(self.cursor - 2) {
+                    *enabled = !*enabled;
+                }
+            }
+            KeyCode::Char('a') => {
+                let all = self.columns.iter().all(|enabled| *enabled);
+                self.columns.iter_mut().for_each(|enabled| *enabled = !all);
+            }
+            _ => {}
+        }
+        ExportAction::None
+    }
+
+    /// Switches format, updating the file extension if it still matches the
+    /// old format's.
+    fn cycle_format(&mut self, backwards: bool) {
+        let old = self.format;
+        self.format = if backwards {
+            old.next().next()
+        } else {
+            old.next()
+        };
+        let suffix = format!(".{}", old.extension());
+        if let Some(stem) = self.path.strip_suffix(&suffix) {
+            self.path = format!("{stem}.{}", self.format.extension());
+        }
+    }
+}
+
+/// Renders the export in memory first so a failed export leaves no partial
+/// file behind.
+fn write_export(
+    path: &str,
+    torrents: &[&TorrentSummary],
+    format: ExportFormat,
+    columns: &[String],
+) -> Result<()> {
+    let mut buffer = Vec::new();
+    export::write_torrents(&mut buffer, torrents, format, columns)?;
+    fs::write(path, buffer).with_context(|| format!("failed to write {path}"))
+}
+
+/// What a screen region does when clicked or scrolled over.
+#[derive(Clone, PartialEq)]
+enum MouseTarget {
+    List,
+    /// Row of the torrent list, by position in the filtered list.
+    Torrent(usize),
+    Details,
+    Peer(String),
+    /// Background of the open dialog; keeps clicks off the panes below.
+    Overlay,
+    /// Dialog row: a profile, column, preference field or export row.
+    Item(usize),
+    /// Checkbox of a dialog row.
+    Check(usize),
+    Action(KeyMode, Action),
+    /// Button of a dialog that reads keys directly.
+    Key(KeyCode),
+}
+
+struct Hit {
+    area: Rect,
+    target: MouseTarget,
+}
+
+impl Hit {
+    fn new(area: Rect, target: MouseTarget) -> Self {
+        Self { area, target }
+    }
+
+    fn contains(&self, column: u16, row: u16) -> bool {
+        (self.area.left()..self.area.right()).contains(&column)
+            && (self.area.top()..self.area.bottom()).contains(&row)
+    }
+}
+
+/// Peer table state for the torrent shown in the details pane.
+struct PeerFocus {
+    torrent: TorrentKey,
+    selected: Option<String>,
+    offset: usize,
+}
+
+struct ProfilePicker {
+    targets: Vec<ProfileTarget>,
+    selected: usize,
+}
+
+/// Saved views picker; rows are `App::views`.
+struct ViewPicker {
+    selected: usize,
+}
+
+/// Column picker: the current columns in order, then the hidden ones.
+struct ColumnPicker {
+    entries: Vec<(Column, bool)>,
+    cursor: usize,
+}
+
+impl ColumnPicker {
+    fn new(current: &[Column]) -> Self {
+        let mut entries: Vec<(Column, bool)> =
+            current.iter().map(|&column| (column, true)).collect();
+        entries.extend(
+            Column::ALL
+                .into_iter()
+                .filter(|column| !current.contains(column))
+                .map(|column| (column, false)),
+        );
+        Self { entries, cursor: 0 }
+    }
+
+    fn shift(&mut self, offset: isize) {
+        let target = self.cursor.saturating_add_signed(offset);
+        if target < self.entries.len() && target != self.cursor {
+            self.entries.swap(self.cursor, target);
+            self.cursor = target;
+        }
+    }
+
+    fn chosen(&self) -> Vec<Column> {
+        self.entries
+            .iter()
+            .filter(|(_, shown)| *shown)
+            .map(|&(column, _)| column)
+            .collect()
+    }
 }
 
 enum FilterAction {
     None,
     Apply(String),
-    Cancel,
+    /// Live update while typing.
+    Preview(String),
+    /// Back to the filter from before FILTER mode.
+    Cancel(String),
+    ToggleFuzzy,
 }
 
 enum PromptAction {
@@ -2198,10 +5054,22 @@ enum PromptAction {
     Cancel,
 }
 
-enum ConfirmAction {
-    None,
-    Accept,
-    Cancel,
+impl RpcCommand {
+    fn label(&self) -> String {
+        match self {
+            RpcCommand::Refresh => "refresh".to_string(),
+            RpcCommand::AddMagnet(_) => "magnet add".to_string(),
+            RpcCommand::RemoveTorrent { name, .. } => format!("remove of {name}"),
+            RpcCommand::ResumeTorrent { name, .. } => format!("resume of {name}"),
+            RpcCommand::PauseTorrent { name, .. } => format!("pause of {name}"),
+            RpcCommand::ReannounceTorrent { name, .. } => format!("reannounce of {name}"),
+            RpcCommand::FetchPreferences => "preferences fetch".to_string(),
+            RpcCommand::UpdatePreferences(_) => "preferences update".to_string(),
+            RpcCommand::MoveTorrent { name, .. } => format!("move of {name}"),
+            RpcCommand::SetSpeedLimit { name, .. } => format!("speed limit of {name}"),
+            RpcCommand::SetLabels { name, .. } => format!("label change of {name}"),
+        }
+    }
 }
 
 enum RpcCommand {
@@ -2220,29 +5088,138 @@ enum RpcCommand {
         id: i64,
         name: String,
     },
+    ReannounceTorrent {
+        id: i64,
+        name: String,
+    },
     FetchPreferences,
     UpdatePreferences(DaemonPreferences),
+    MoveTorrent {
+        id: i64,
+        name: String,
+        location: String,
+    },
+    SetSpeedLimit {
+        id: i64,
+        name: String,
+        direction: LimitDirection,
+        limit: Option<i64>,
+    },
+    SetLabels {
+        id: i64,
+        name: String,
+        labels: Vec<String>,
+    },
 }
 
-fn torrent_row(summary: &TorrentSummary) -> Row<'static> {
-    Row::new(vec![
-        Cell::from(summary.name.clone()),
-        Cell::from(summary.status.clone()),
-        Cell::from(format!("{:>12}", format_speed(summary.rate_download))),
-        Cell::from(format!("{:>12}", format_speed(summary.rate_upload))),
-        Cell::from(format!("{:>9}", format_progress(summary.percent_done))),
-        Cell::from(format!("{:>10}", format_eta(summary.eta))),
-        Cell::from(format!("{:>8.2}", summary.upload_ratio)),
-    ])
+/// Whether `name` passes a name filter: a fuzzy match of its words, or a
+/// case-insensitive substring match on `filter_lower` with no positions.
+fn name_filter_match(
+    filter: &str,
+    filter_lower: &str,
+    fuzzy: bool,
+    name: &str,
+) -> Option<fuzzy::Match> {
+    if fuzzy && !filter_lower.trim().is_empty() {
+        return fuzzy::match_words(filter, name);
+    }
+    name.to_lowercase()
+        .contains(filter_lower)
+        .then(|| fuzzy::Match {
+            score: 0,
+            positions: Vec::new(),
+        })
+}
+
+/// `text` in `base`, with the chars at `positions` patched with `matched`.
+fn highlight_spans(
+    text: &str,
+    positions: &[usize],
+    base: Style,
+    matched: Style,
+) -> Vec<Span<'static>> {
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (idx, c) in text.chars().enumerate() {
+        let is_matched = positions.contains(&idx);
+        if is_matched != run_matched && !run.is_empty() {
+            let style = if run_matched {
+                base.patch(matched)
+            } else {
+                base
+            };
+            spans.push(Span::styled(std::mem::take(&mut run), style));
+        }
+        run_matched = is_matched;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        let style = if run_matched {
+            base.patch(matched)
+        } else {
+            base
+        };
+        spans.push(Span::styled(run, style));
+    }
+    spans
+}
+
+/// `name_matches` are the chars of the name to highlight.
+fn torrent_row(
+    summary: &TorrentSummary,
+    layout: &[(Column, u16)],
+    theme: &Theme,
+    name_matches: &[usize],
+) -> Row<'static> {
+    let cells = layout.iter().map(|&(column, width)| {
+        let style = match column {
+            Column::Status => theme.torrent_status(&summary.status, summary.error.is_some()),
+            Column::Download => theme.download,
+            Column::Upload => theme.upload,
+            Column::Error => theme.errored,
+            _ => Style::default(),
+        };
+        if column == Column::Name && !name_matches.is_empty() {
+            return Cell::from(Line::from(highlight_spans(
+                &summary.name,
+                name_matches,
+                style,
+                theme.matched,
+            )));
+        }
+        Cell::from(pad_cell(column, &column.text(summary), width)).style(style)
+    });
+    Row::new(cells.collect::<Vec<_>>())
 }
 
-fn peer_row(peer: &PeerSummary) -> Row<'static> {
+/// Right-aligns numeric columns within their width.
+fn pad_cell(column: Column, text: &str, width: u16) -> String {
+    if column.is_numeric() {
+        format!("{:>width$}", text.trim_start(), width = width as usize)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Peers fastest first, the order the details pane lists them in.
+fn sorted_peers(torrent: &TorrentSummary) -> Vec<&PeerSummary> {
+    let mut peers = torrent.peers.iter().collect::<Vec<_>>();
+    peers.sort_by(|a, b| {
+        b.rate_down
+            .cmp(&a.rate_down)
+            .then_with(|| b.rate_up.cmp(&a.rate_up))
+    });
+    peers
+}
+
+fn peer_row(peer: &PeerSummary, theme: &Theme) -> Row<'static> {
     Row::new(vec![
         Cell::from(peer.address.clone()),
         Cell::from(peer.client.clone()),
         Cell::from(format!("{:>9}", format_progress(peer.progress))),
-        Cell::from(format!("{:>12}", format_speed(peer.rate_down))),
-        Cell::from(format!("{:>12}", format_speed(peer.rate_up))),
+        Cell::from(format!("{:>12}", format_speed(peer.rate_down))).style(theme.download),
+        Cell::from(format!("{:>12}", format_speed(peer.rate_up))).style(theme.upload),
         Cell::from(format!(
             "{:>4}",
             if peer.is_encrypted { "Yes" } else { "No" }
@@ -2250,13 +5227,47 @@ fn peer_row(peer: &PeerSummary) -> Row<'static> {
     ])
 }
 
-fn status_style(level: StatusLevel) -> Style {
+fn status_style(theme: &Theme, level: StatusLevel) -> Style {
     match level {
-        StatusLevel::Info => Style::default().fg(Color::Blue),
-        StatusLevel::Success => Style::default().fg(Color::Green),
-        StatusLevel::Warning => Style::default().fg(Color::Yellow),
-        StatusLevel::Error => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        StatusLevel::Info => theme.info,
+        StatusLevel::Success => theme.success,
+        StatusLevel::Warning => theme.warning,
+        StatusLevel::Error => theme.error,
+    }
+}
+
+fn connection_banner(status: &ConnectionStatus, retry_key: Option<String>) -> String {
+    let now = Instant::now();
+    let last_success = match status.last_success {
+        Some(at) => format!(
+            "last success {} ago",
+            format_eta(Some(now.saturating_duration_since(at).as_secs() as i64))
+        ),
+        None => "never connected".to_string(),
+    };
+    let retry = match status.next_retry {
+        Some(at) if at > now => format!(
+            "retrying in {}",
+            format_eta(Some(at.saturating_duration_since(now).as_secs() as i64 + 1))
+        ),
+        Some(_) => "retrying now…".to_string(),
+        None => match retry_key {
+            Some(key) => format!("press {key} to retry"),
+            None => "not retrying".to_string(),
+        },
+    };
+    let mut banner = format!(
+        "{} · {} · {} (attempt {})",
+        status.state.label(),
+        last_success,
+        retry,
+        status.failures
+    );
+    if let Some(error) = &status.last_error {
+        banner.push_str(" · ");
+        banner.push_str(error);
     }
+    banner
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
@@ -2279,7 +5290,12 @@ fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     vertical[1]
 }
 
-fn help_rows() -> Vec<Row<'static>> {
+/// Key binding table for the help overlay, built from the active keymap.
+fn help_rows(
+    capabilities: Option<&Capabilities>,
+    keymap: &Keymap,
+    theme: &Theme,
+) -> Vec<Row<'static>> {
     let heading_style = Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
     let heading = |text: &'static str| {
         Row::new(vec![
@@ -2287,30 +5303,86 @@ fn help_rows() -> Vec<Row<'static>> {
             Cell::from(Span::raw("")),
         ])
     };
-    let entry =
-        |keys: &'static str, desc: &'static str| Row::new(vec![Cell::from(keys), Cell::from(desc)]);
+    let entry = |keys: String, desc: String| Row::new(vec![Cell::from(keys), Cell::from(desc)]);
     let spacer = || Row::new(vec![Cell::from(""), Cell::from("")]);
-    vec![
-        heading("Navigation"),
-        entry("j / k", "Move selection"),
-        entry("g / G", "Jump to first / last"),
-        entry("Ctrl+d / Ctrl+u", "Half-page down / up"),
-        spacer(),
-        heading("Actions"),
-        entry("r", "Resume selected torrent"),
-        entry("R", "Refresh now"),
-        entry("p", "Pause selected torrent"),
-        entry("a", "Add magnet"),
-        entry("o", "Edit daemon preferences"),
-        entry("dd", "Delete highlighted torrent"),
-        entry("DD", "Trash data + remove highlighted torrent"),
-        entry("/", "Filter list"),
-        entry("Esc", "Clear filter / cancel dialog"),
-        entry("?", "Toggle this help"),
-        entry("q or Ctrl+c", "Quit"),
-        spacer(),
-        heading("Dialogs"),
-        entry("Prompt", "Enter to submit, Esc to cancel"),
-        entry("Confirm", "y to accept, n/Esc to cancel"),
-    ]
+    let supported = |action: Action| match action {
+        Action::Reannounce => capabilities.is_none_or(|caps| caps.torrent_reannounce),
+        _ => true,
+    };
+    let normal = keymap.entries(KeyMode::Normal);
+    let mut rows = vec![heading("Navigation")];
+    let (navigation, actions): (Vec<_>, Vec<_>) = normal
+        .into_iter()
+        .partition(|(action, _)| action.is_navigation());
+    // Opposite moves share a row when both are bound.
+    let paired = [
+        (Action::Down, Action::Up, "Move selection"),
+        (Action::Top, Action::Bottom, "Jump to first / last"),
+        (Action::PageDown, Action::PageUp, "Half-page down / up"),
+    ];
+    let bound = |wanted: Action| {
+        navigation
+            .iter()
+            .find(|(action, _)| *action == wanted)
+            .map(|(_, keys)| keys.join(", "))
+    };
+    for (action, keys) in &navigation {
+        let pair = paired
+            .iter()
+            .find(|(first, second, _)| first == action || second == action);
+        match pair.map(|&(first, second, desc)| (bound(first), bound(second), first, desc)) {
+            Some((Some(first), Some(second), leader, desc)) => {
+                if leader == *action {
+                    rows.push(entry(format!("{first} / {second}"), desc.to_string()));
+                }
+            }
+            _ => rows.push(entry(keys.join(", "), action.description().to_string())),
+        }
+    }
+    rows.push(spacer());
+    rows.push(heading("Actions"));
+    for (action, mut keys) in actions {
+        if action == Action::Quit {
+            keys.push("Ctrl+c".to_string());
+        }
+        let keys = keys.join(", ");
+        if supported(action) {
+            rows.push(entry(keys, action.description().to_string()));
+        } else {
+            rows.push(
+                entry(
+                    keys,
+                    format!("{} (not supported by daemon)", action.description()),
+                )
+                .style(theme.muted),
+            );
+        }
+    }
+    rows.push(entry(
+        "1 … 9".to_string(),
+        "Apply saved view 1 to 9 (unless bound above)".to_string(),
+    ));
+    rows.push(spacer());
+    rows.push(heading("Dialogs"));
+    for mode in KeyMode::ALL.into_iter().skip(1) {
+        let summary: Vec<String> = keymap
+            .entries(mode)
+            .into_iter()
+            .map(|(action, keys)| {
+                format!("{} {}", keys.join("/"), action.description().to_lowercase())
+            })
+            .collect();
+        let mut label = mode.label().to_string();
+        label[..1].make_ascii_uppercase();
+        rows.push(entry(label, summary.join(", ")));
+    }
+    rows.push(entry(
+        "Prompt".to_string(),
+        "Enter to submit, Esc to cancel".to_string(),
+    ));
+    rows.push(entry(
+        "Export".to_string(),
+        "Space toggles a column, Enter writes the file".to_string(),
+    ));
+    rows
 }
@@ -0,0 +1,292 @@
+use std::{
+    io::Read,
+    net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    process::{Child, Command, Stdio},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crossbeam_channel::{bounded, RecvTimeoutError, Sender};
+
+const FIRST_RESTART_DELAY: Duration = Duration::from_millis(500);
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(30);
+/// A child that stayed up this long counts as healthy again, so the next
+/// failure restarts it quickly.
+const HEALTHY_AFTER: Duration = Duration::from_secs(30);
+const CHECK_INTERVAL: Duration = Duration::from_millis(200);
+/// After this long without exiting, an earlier ssh error is considered stale.
+const STALE_ERROR_AFTER: Duration = Duration::from_secs(5);
+
+/// `ssh` settings for a profile whose daemon is only reachable through a
+/// tunnel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshConfig {
+    /// `user@host` (or any destination `ssh` accepts).
+    pub destination: String,
+    /// Program to run instead of `ssh`, e.g. a wrapper script.
+    pub program: String,
+    /// What the remote end forwards to, as seen from the SSH host.
+    pub remote: RemoteTarget,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteTarget {
+    Tcp { host: String, port: u16 },
+    Unix(String),
+}
+
+impl SshConfig {
+    fn args(&self, local_port: u16) -> Vec<String> {
+        let remote = match &self.remote {
+            RemoteTarget::Tcp { host, port } => format!("{host}:{port}"),
+            RemoteTarget::Unix(path) => path.clone(),
+        };
+        vec![
+            "-N".to_string(),
+            "-L".to_string(),
+            format!("127.0.0.1:{local_port}:{remote}"),
+            // The TUI owns the terminal, so ssh must never prompt.
+            "-o".to_string(),
+            "BatchMode=yes".to_string(),
+            "-o".to_string(),
+            "ExitOnForwardFailure=yes".to_string(),
+            "-o".to_string(),
+            "ServerAliveInterval=15".to_string(),
+            self.destination.clone(),
+        ]
+    }
+}
+
+/// Asks the OS for a currently unused loopback port.
+pub fn free_local_port() -> std::io::Result<u16> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    Ok(listener.local_addr()?.port())
+}
+
+/// A supervised `ssh -N -L` child. A background thread restarts it with
+/// backoff whenever it exits; dropping the tunnel kills the child.
+pub struct SshTunnel {
+    local_port: u16,
+    last_error: Arc<Mutex<Option<String>>>,
+    stop: Option<Sender<()>>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl SshTunnel {
+    pub fn start(config: SshConfig, local_port: u16) -> Self {
+        let last_error = Arc::new(Mutex::new(None));
+        let (stop, stopped) = bounded::<()>(0);
+        let errors = Arc::clone(&last_error);
+        let handle = thread::spawn(move || {
+            let mut delay = FIRST_RESTART_DELAY;
+            loop {
+                let started = Instant::now();
+                let mut child = match spawn_ssh(&config, local_port) {
+                    Ok(child) => child,
+                    Err(err) => {
+                        set_error(&errors, format!("failed to run {}: {err}", config.program));
+                        match stopped.recv_timeout(delay) {
+                            Err(RecvTimeoutError::Timeout) => {
+                                delay = (delay * 2).min(MAX_RESTART_DELAY);
+                                continue;
+                            }
+                            _ => return,
+                        }
+                    }
+                };
+                let exit = loop {
+                    match stopped.recv_timeout(CHECK_INTERVAL) {
+                        Err(RecvTimeoutError::Timeout) => {}
+                        _ => {
+                            let _ = child.kill();
+                            let _ = child.wait();
+                            return;
+                        }
+                    }
+                    match child.try_wait() {
+                        Ok(Some(status)) => break status.to_string(),
+                        Ok(None) if started.elapsed() >= STALE_ERROR_AFTER => clear_error(&errors),
+                        Ok(None) => {}
+                        Err(err) => break err.to_string(),
+                    }
+                };
+                set_error(&errors, describe_exit(&mut child, &config.program, &exit));
+                if started.elapsed() >= HEALTHY_AFTER {
+                    delay = FIRST_RESTART_DELAY;
+                }
+                match stopped.recv_timeout(delay) {
+                    Err(RecvTimeoutError::Timeout) => delay = (delay * 2).min(MAX_RESTART_DELAY),
+                    _ => return,
+                }
+            }
+        });
+        Self {
+            local_port,
+            last_error,
+            stop: Some(stop),
+            handle: Some(handle),
+        }
+    }
+
+    /// Blocks until the forwarded port accepts connections, up to `timeout`.
+    pub fn wait_ready(&self, timeout: Duration) -> bool {
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, self.local_port));
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            if TcpStream::connect_timeout(&addr, CHECK_INTERVAL).is_ok() {
+                return true;
+            }
+            thread::sleep(CHECK_INTERVAL);
+        }
+        false
+    }
+
+    /// Why the most recent `ssh` run ended, unless a later run has been up
+    /// for a while since.
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().ok()?.clone()
+    }
+}
+
+impl Drop for SshTunnel {
+    fn drop(&mut self) {
+        self.stop.take();
+        if let Some(handle) = self.handle.take() {
+            handle.join().ok();
+        }
+    }
+}
+
+fn spawn_ssh(config: &SshConfig, local_port: u16) -> std::io::Result<Child> {
+    Command::new(&config.program)
+        .args(config.args(local_port))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+}
+
+/// Exit status plus the last line ssh printed, which usually names the cause
+/// (`Permission denied`, `Could not resolve hostname`, …).
+fn describe_exit(child: &mut Child, program: &str, status: &str) -> String {
+    let mut stderr = String::new();
+    if let Some(mut pipe) = child.stderr.take() {
+        let _ = pipe.read_to_string(&mut stderr);
+    }
+    match stderr.lines().rev().find(|line| !line.trim().is_empty()) {
+        Some(line) => format!("{program} {status}: {}", line.trim()),
+        None => format!("{program} {status}"),
+    }
+}
+
+fn set_error(slot: &Mutex<Option<String>>, message: String) {
+    if let Ok(mut guard) = slot.lock() {
+        *guard = Some(message);
+    }
+}
+
+fn clear_error(slot: &Mutex<Option<String>>) {
+    if let Ok(mut guard) = slot.lock() {
+        guard.take();
+    }
+}
+
+// Every test runs a shell script standing in for `ssh`.
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{fs, os::unix::fs::PermissionsExt, path::Path};
+
+    use super::*;
+
+    /// Writes an executable stand-in for `ssh` and returns its path.
+    fn fake_ssh(dir: &Path, body: &str) -> String {
+        let path = dir.join("ssh");
+        fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn config(program: String) -> SshConfig {
+        SshConfig {
+            destination: "user@box".to_string(),
+            program,
+            remote: RemoteTarget::Tcp {
+                host: "localhost".to_string(),
+                port: 9091,
+            },
+        }
+    }
+
+    fn wait_for(mut check: impl FnMut() -> bool) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            if check() {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        false
+    }
+
+    #[test]
+    fn passes_forward_spec_and_kills_child_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("args");
+        let pid = dir.path().join("pid");
+        let program = fake_ssh(
+            dir.path(),
+            &format!(
+                "echo \"$@\" > {}\necho $$ > {}\nexec sleep 60",
+                log.display(),
+                pid.display()
+            ),
+        );
+        let tunnel = SshTunnel::start(config(program), 40123);
+        assert!(wait_for(
+            || fs::read_to_string(&pid).is_ok_and(|p| !p.is_empty())
+        ));
+        let args = fs::read_to_string(&log).unwrap();
+        assert!(args.starts_with("-N -L 127.0.0.1:40123:localhost:9091 "));
+        assert!(args.trim_end().ends_with("user@box"));
+
+        let pid = fs::read_to_string(&pid).unwrap().trim().to_string();
+        drop(tunnel);
+        let alive = Command::new("kill")
+            .args(["-0", &pid])
+            .stderr(Stdio::null())
+            .status()
+            .unwrap()
+            .success();
+        assert!(!alive, "ssh child {pid} survived the tunnel");
+    }
+
+    #[test]
+    fn restarts_failed_ssh_and_reports_its_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let runs = dir.path().join("runs");
+        let program = fake_ssh(
+            dir.path(),
+            &format!(
+                "echo run >> {}\necho 'ssh: connect to host box port 22: Connection refused' >&2\nexit 255",
+                runs.display()
+            ),
+        );
+        let tunnel = SshTunnel::start(config(program), 40124);
+        assert!(wait_for(
+            || fs::read_to_string(&runs).is_ok_and(|runs| runs.lines().count() >= 2)
+        ));
+        let error = tunnel.last_error().unwrap();
+        assert!(error.contains("Connection refused"), "{error}");
+    }
+
+    #[test]
+    fn ready_once_the_forwarded_port_listens() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let dir = tempfile::tempdir().unwrap();
+        let program = fake_ssh(dir.path(), "exec sleep 60");
+        let tunnel = SshTunnel::start(config(program), port);
+        assert!(tunnel.wait_ready(Duration::from_secs(2)));
+    }
+}
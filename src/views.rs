@@ -0,0 +1,155 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+};
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+use crate::{cmdline::SortOrder, columns::Column};
+
+/// A saved view: a name filter with a sort order and column set, applied
+/// together from the views picker or a number key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct View {
+    pub name: String,
+    pub filter: String,
+    pub fuzzy: bool,
+    /// `None` keeps the daemon's order (or the fuzzy ranking).
+    pub sort: Option<(Column, SortOrder)>,
+    /// `None` leaves the columns as they are.
+    pub columns: Option<Vec<Column>>,
+}
+
+/// How a view is written to the config file.
+#[derive(Serialize)]
+struct FileView<'a> {
+    name: &'a str,
+    filter: &'a str,
+    fuzzy: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sort: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    columns: Option<Vec<&'static str>>,
+}
+
+/// Parses a `sort` setting: a column name with an optional `asc` or
+/// `desc`, or `off`.
+pub fn parse_sort(text: &str) -> Result<Option<(Column, SortOrder)>> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let (column, order) = match words[..] {
+        ["off"] => return Ok(None),
+        [column] => (column, SortOrder::Ascending),
+        [column, "asc"] => (column, SortOrder::Ascending),
+        [column, "desc"] => (column, SortOrder::Descending),
+        _ => bail!("invalid sort `{text}` (expected `<column> [asc|desc]` or `off`)"),
+    };
+    let column = Column::ALL
+        .into_iter()
+        .find(|candidate| candidate.name() == column)
+        .with_context(|| format!("unknown sort column `{column}`"))?;
+    Ok(Some((column, order)))
+}
+
+pub fn format_sort(sort: Option<(Column, SortOrder)>) -> String {
+    match sort {
+        Some((column, SortOrder::Ascending)) => column.name().to_string(),
+        Some((column, SortOrder::Descending)) => format!("{} desc", column.name()),
+        None => "off".to_string(),
+    }
+}
+
+/// Appends `view` to the config file at `path` as a `[[views]]` table,
+/// leaving the rest of the file untouched. The file is created if missing,
+/// and left alone if the result would no longer parse.
+pub fn append(path: &Path, view: &View) -> Result<()> {
+    let existing = if path.exists() {
+        fs::read_to_string(path)
+            .with_context(|| format!("failed to read config file {}", path.display()))?
+    } else {
+        String::new()
+    };
+    let entry = FileView {
+        name: &view.name,
+        filter: &view.filter,
+        fuzzy: view.fuzzy,
+        sort: view.sort.map(|sort| format_sort(Some(sort))),
+        columns: view
+            .columns
+            .as_ref()
+            .map(|columns| columns.iter().map(|column| column.name()).collect()),
+    };
+    let mut text = String::new();
+    if !existing.is_empty() {
+        if !existing.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push('\n');
+    }
+    text.push_str("[[views]]\n");
+    text.push_str(&toml::to_string(&entry).context("failed to encode view")?);
+    if let Err(err) = toml::from_str::<toml::Table>(&format!("{existing}{text}")) {
+        bail!(
+            "can't add a [[views]] table to {}: {}",
+            path.display(),
+            err.message()
+        );
+    }
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(text.as_bytes()))
+        .with_context(|| format!("failed to write config file {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sort_settings() {
+        assert_eq!(
+            parse_sort("ratio desc").unwrap(),
+            Some((Column::Ratio, SortOrder::Descending))
+        );
+        assert_eq!(
+            parse_sort("download_dir").unwrap(),
+            Some((Column::DownloadDir, SortOrder::Ascending))
+        );
+        assert_eq!(parse_sort("off").unwrap(), None);
+        assert!(parse_sort("rat").is_err());
+        assert!(parse_sort("ratio down").is_err());
+        assert!(parse_sort("").is_err());
+    }
+
+    #[test]
+    fn appends_a_views_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "# mine\n[keys.normal]\nquit = \"Q\"").unwrap();
+        let view = View {
+            name: "big \"ones\"".to_string(),
+            filter: "iso".to_string(),
+            fuzzy: true,
+            sort: Some((Column::Size, SortOrder::Descending)),
+            columns: Some(vec![Column::Name, Column::Size]),
+        };
+        append(&path, &view).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("# mine\n[keys.normal]\nquit = \"Q\"\n\n[[views]]\n"));
+        let table: toml::Table = toml::from_str(&text).unwrap();
+        let saved = &table["views"].as_array().unwrap()[0];
+        assert_eq!(saved["name"].as_str(), Some("big \"ones\""));
+        assert_eq!(saved["sort"].as_str(), Some("size desc"));
+        assert_eq!(saved["columns"].as_array().unwrap().len(), 2);
+
+        fs::write(&path, "views = []\n").unwrap();
+        assert!(append(&path, &view).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "views = []\n");
+    }
+}
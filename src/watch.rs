@@ -0,0 +1,557 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{bail, Context, Result};
+use serde_json::json;
+
+use crate::{
+    commands,
+    config::AppConfig,
+    rpc::{AddOptions, AddTorrentOutcome, RpcResult, TransmissionClient},
+    tunnel::SshTunnel,
+};
+
+/// Files modified more recently than this may still be being written and
+/// are left for the next scan.
+const SETTLE_TIME: Duration = Duration::from_secs(2);
+
+/// A directory whose `.torrent` and `.magnet` files are added to a profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchFolder {
+    pub dir: PathBuf,
+    /// Name of the profile the torrents are added to.
+    pub profile: String,
+    pub options: AddOptions,
+    /// Where files go once everything in them was added (or already present).
+    pub processed_dir: PathBuf,
+    /// Where files go when the daemon rejected them or they were unreadable.
+    pub failed_dir: PathBuf,
+}
+
+/// What to hand to `torrent_add`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddSource<'a> {
+    Metainfo(&'a [u8]),
+    Magnet(&'a str),
+}
+
+/// Result of ingesting one watched file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchOutcome {
+    /// Added (or already present) and moved to the processed folder.
+    Added { file: PathBuf, names: Vec<String> },
+    /// Moved to the failed folder.
+    Failed { file: PathBuf, reason: String },
+    /// Left in place because the daemon is unreachable; retried next scan.
+    Deferred { file: PathBuf, reason: String },
+}
+
+/// What one scan of a folder did, plus anything that kept files from being
+/// looked at (an unreadable folder or entry, a broken symlink).
+#[derive(Debug, Default)]
+pub struct WatchScan {
+    pub outcomes: Vec<WatchOutcome>,
+    pub problems: Vec<String>,
+}
+
+/// The problems each folder reported on its previous scan, so that one that
+/// persists (an unmounted folder, say) is reported once rather than on every
+/// poll.
+#[derive(Debug, Default)]
+pub struct WatchProblems {
+    last: BTreeMap<PathBuf, Vec<String>>,
+}
+
+impl WatchProblems {
+    /// The problems in `scan` that `folder` did not report last time.
+    pub fn fresh(&mut self, folder: &WatchFolder, scan: &WatchScan) -> Vec<String> {
+        let previous = self
+            .last
+            .insert(folder.dir.clone(), scan.problems.clone())
+            .unwrap_or_default();
+        scan.problems
+            .iter()
+            .filter(|problem| !previous.contains(problem))
+            .cloned()
+            .collect()
+    }
+}
+
+impl WatchOutcome {
+    pub fn message(&self) -> String {
+        match self {
+            WatchOutcome::Added { file, names } if names == &[file_name(file)] => {
+                format!("Watch: added {}", file_name(file))
+            }
+            WatchOutcome::Added { file, names } => {
+                format!("Watch: added {} from {}", names.join(", "), file_name(file))
+            }
+            WatchOutcome::Failed { file, reason } => {
+                format!("Watch: {} failed: {reason}", file_name(file))
+            }
+            WatchOutcome::Deferred { file, reason } => {
+                format!("Watch: {} deferred: {reason}", file_name(file))
+            }
+        }
+    }
+}
+
+/// Ingests the settled `.torrent` and `.magnet` files in `folder` through
+/// the daemon behind `client`.
+pub fn scan_with_client(folder: &WatchFolder, client: &TransmissionClient) -> WatchScan {
+    scan(folder, |source| match source {
+        AddSource::Metainfo(bytes) => client.add_torrent_file(bytes, &folder.options),
+        AddSource::Magnet(link) => client.add_magnet(link, &folder.options),
+    })
+}
+
+/// Runs `add` for every settled file in `folder`, in name order, and moves
+/// each file to the processed or failed folder. Scanning stops at the first
+/// connection failure so that an offline daemon never fails a file.
+pub fn scan(
+    folder: &WatchFolder,
+    mut add: impl FnMut(AddSource) -> RpcResult<AddTorrentOutcome>,
+) -> WatchScan {
+    let mut problems = Vec::new();
+    let files = match pending_files(&folder.dir, &mut problems) {
+        Ok(files) => files,
+        Err(err) => {
+            problems.push(format!(
+                "cannot scan watch folder {}: {err}",
+                folder.dir.display()
+            ));
+            return WatchScan {
+                outcomes: Vec::new(),
+                problems,
+            };
+        }
+    };
+    let mut outcomes = Vec::new();
+    for file in files {
+        let result = match ingest(&file, &mut add) {
+            Ok(names) => Ok(names),
+            Err(Ingest::Unreachable(reason)) => {
+                outcomes.push(WatchOutcome::Deferred { file, reason });
+                break;
+            }
+            Err(Ingest::Failed(reason)) => Err(reason),
+        };
+        let target = match &result {
+            Ok(_) => &folder.processed_dir,
+            Err(_) => &folder.failed_dir,
+        };
+        let moved = move_into(&file, target).map_err(|err| {
+            format!(
+                "could not move to {}: {err}",
+                target.file_name().unwrap_or_default().to_string_lossy()
+            )
+        });
+        outcomes.push(match (result, moved) {
+            (Ok(names), Ok(_)) => WatchOutcome::Added { file, names },
+            (Ok(_), Err(reason)) => WatchOutcome::Failed { file, reason },
+            (Err(reason), Ok(_)) => WatchOutcome::Failed { file, reason },
+            (Err(reason), Err(move_error)) => WatchOutcome::Failed {
+                file,
+                reason: format!("{reason}; {move_error}"),
+            },
+        });
+    }
+    WatchScan { outcomes, problems }
+}
+
+enum Ingest {
+    Failed(String),
+    Unreachable(String),
+}
+
+fn ingest(
+    file: &Path,
+    add: &mut impl FnMut(AddSource) -> RpcResult<AddTorrentOutcome>,
+) -> Result<Vec<String>, Ingest> {
+    let contents = fs::read(file).map_err(|err| Ingest::Failed(err.to_string()))?;
+    let mut run = |source: AddSource| match add(source) {
+        Ok(outcome) => Ok(outcome.name.unwrap_or_else(|| file_name(file))),
+        Err(err) if err.is_connection_failure() => Err(Ingest::Unreachable(err.to_string())),
+        Err(err) => Err(Ingest::Failed(err.to_string())),
+    };
+    if file.extension().is_some_and(|ext| ext == "torrent") {
+        return run(AddSource::Metainfo(&contents)).map(|name| vec![name]);
+    }
+    let text = String::from_utf8_lossy(&contents);
+    let links: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+    if links.is_empty() {
+        return Err(Ingest::Failed("no magnet links".to_string()));
+    }
+    let mut names = Vec::new();
+    let mut errors = Vec::new();
+    for link in &links {
+        if !link.starts_with("magnet:") {
+            errors.push(format!("not a magnet link: {link}"));
+            continue;
+        }
+        match run(AddSource::Magnet(link)) {
+            Ok(name) => names.push(name),
+            Err(Ingest::Failed(reason)) => errors.push(reason),
+            // Links added before the daemon went away are duplicates on retry.
+            Err(unreachable) => return Err(unreachable),
+        }
+    }
+    match errors.first() {
+        None => Ok(names),
+        Some(first) if links.len() == 1 => Err(Ingest::Failed(first.clone())),
+        Some(first) => Err(Ingest::Failed(format!(
+            "{} of {} links failed: {first}",
+            errors.len(),
+            links.len()
+        ))),
+    }
+}
+
+/// Regular `.torrent`/`.magnet` files (or symlinks to one) that have not
+/// been touched for `SETTLE_TIME`, sorted by name. Entries that vanish or
+/// can't be read are skipped, and noted in `problems`, so one bad file
+/// doesn't stall the folder.
+fn pending_files(dir: &Path, problems: &mut Vec<String>) -> io::Result<Vec<PathBuf>> {
+    let now = SystemTime::now();
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                problems.push(format!("cannot read an entry of {}: {err}", dir.display()));
+                continue;
+            }
+        };
+        let path = entry.path();
+        let watched = path
+            .extension()
+            .is_some_and(|ext| ext == "torrent" || ext == "magnet");
+        if !watched {
+            continue;
+        }
+        let metadata = entry.metadata().and_then(|metadata| {
+            if metadata.is_symlink() {
+                fs::metadata(&path)
+            } else {
+                Ok(metadata)
+            }
+        });
+        let metadata = match metadata {
+            Ok(metadata) if metadata.is_file() => metadata,
+            Ok(_) => continue,
+            Err(err) => {
+                problems.push(format!("skipping {}: {err}", path.display()));
+                continue;
+            }
+        };
+        let settled = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age >= SETTLE_TIME);
+        if settled {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Moves `file` into `dir`, adding `-1`, `-2`, … to the stem if the name is
+/// taken.
+fn move_into(file: &Path, dir: &Path) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = file
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    let mut target = dir.join(format!("{stem}{extension}"));
+    let mut n = 1;
+    while target.exists() {
+        target = dir.join(format!("{stem}-{n}{extension}"));
+        n += 1;
+    }
+    fs::rename(file, &target)?;
+    Ok(target)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// `watch` subcommand: scans every configured folder at the poll interval,
+/// printing one line per file, until killed (or once with `once`).
+pub fn run(config: &AppConfig, once: bool, json: bool) -> Result<()> {
+    if config.watch.is_empty() {
+        bail!("no [[watch]] folders configured");
+    }
+    let mut clients: BTreeMap<&str, (TransmissionClient, Option<SshTunnel>)> = BTreeMap::new();
+    for folder in &config.watch {
+        if clients.contains_key(folder.profile.as_str()) {
+            continue;
+        }
+        let profile = config
+            .profiles
+            .iter()
+            .find(|profile| profile.name == folder.profile)
+            .with_context(|| format!("unknown profile '{}'", folder.profile))?;
+        let connection = commands::connect(&profile.rpc)
+            .with_context(|| format!("cannot reach profile '{}'", profile.name))?;
+        clients.insert(&profile.name, connection);
+    }
+    let interval = config.poll_interval.max(Duration::from_secs(1));
+    let mut problems = WatchProblems::default();
+    // Last handshake error per profile, so a daemon that stays down is
+    // reported once.
+    let mut unreachable: BTreeMap<&str, String> = BTreeMap::new();
+    loop {
+        let mut failed = 0;
+        for folder in &config.watch {
+            let (client, _tunnel) = &clients[folder.profile.as_str()];
+            if client.capabilities().is_none() {
+                if let Err(err) = client.handshake() {
+                    if once {
+                        return Err(err)
+                            .with_context(|| format!("cannot reach profile '{}'", folder.profile));
+                    }
+                    let message = err.to_string();
+                    if unreachable.get(folder.profile.as_str()) != Some(&message) {
+                        log::warn!("profile '{}' unreachable: {message}", folder.profile);
+                        unreachable.insert(&folder.profile, message);
+                    }
+                    continue;
+                }
+                unreachable.remove(folder.profile.as_str());
+            }
+            let scan = scan_with_client(folder, client);
+            for problem in problems.fresh(folder, &scan) {
+                log::warn!("{problem}");
+            }
+            for outcome in scan.outcomes {
+                if matches!(outcome, WatchOutcome::Failed { .. }) {
+                    failed += 1;
+                }
+                print_outcome(&outcome, json)?;
+            }
+        }
+        if once {
+            if failed > 0 {
+                bail!("{failed} watched files could not be added");
+            }
+            return Ok(());
+        }
+        thread::sleep(interval);
+    }
+}
+
+fn print_outcome(outcome: &WatchOutcome, json: bool) -> Result<()> {
+    let mut out = io::stdout().lock();
+    if !json {
+        writeln!(out, "{}", outcome.message().trim_start_matches("Watch: "))?;
+        return Ok(out.flush()?);
+    }
+    let value = match outcome {
+        WatchOutcome::Added { file, names } => {
+            json!({ "file": file, "status": "added", "names": names })
+        }
+        WatchOutcome::Failed { file, reason } => {
+            json!({ "file": file, "status": "failed", "error": reason })
+        }
+        WatchOutcome::Deferred { file, reason } => {
+            json!({ "file": file, "status": "deferred", "error": reason })
+        }
+    };
+    writeln!(out, "{value}")?;
+    Ok(out.flush()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+    use crate::rpc::TransmissionError;
+
+    fn folder(dir: &Path) -> WatchFolder {
+        WatchFolder {
+            dir: dir.to_path_buf(),
+            profile: "default".to_string(),
+            options: AddOptions::default(),
+            processed_dir: dir.join("processed"),
+            failed_dir: dir.join("failed"),
+        }
+    }
+
+    /// Writes `contents` with an mtime old enough to count as settled.
+    fn settled_file(dir: &Path, name: &str, contents: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, contents).unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(60))
+            .unwrap();
+        path
+    }
+
+    fn added(name: &str) -> RpcResult<AddTorrentOutcome> {
+        Ok(AddTorrentOutcome {
+            torrent_id: Some(1),
+            name: Some(name.to_string()),
+            added: true,
+            duplicate: false,
+        })
+    }
+
+    fn rejected() -> RpcResult<AddTorrentOutcome> {
+        Err(TransmissionError::Rpc {
+            code: 0,
+            message: "invalid or corrupt torrent file".to_string(),
+            context: String::new(),
+        })
+    }
+
+    #[test]
+    fn adds_torrent_and_magnet_files_and_moves_them_to_processed() {
+        let dir = tempfile::tempdir().unwrap();
+        settled_file(dir.path(), "a.torrent", "d8:announce0:e");
+        settled_file(
+            dir.path(),
+            "b.magnet",
+            "# weekly\nmagnet:?xt=urn:btih:one\n\nmagnet:?xt=urn:btih:two\n",
+        );
+        let mut seen = Vec::new();
+        let outcomes = scan(&folder(dir.path()), |source| {
+            let name = match source {
+                AddSource::Metainfo(bytes) => String::from_utf8_lossy(bytes).into_owned(),
+                AddSource::Magnet(link) => link.to_string(),
+            };
+            seen.push(name.clone());
+            added(&name)
+        })
+        .outcomes;
+        assert_eq!(
+            seen,
+            [
+                "d8:announce0:e",
+                "magnet:?xt=urn:btih:one",
+                "magnet:?xt=urn:btih:two"
+            ]
+        );
+        assert!(
+            matches!(&outcomes[..], [WatchOutcome::Added { .. }, WatchOutcome::Added { names, .. }] if names.len() == 2)
+        );
+        assert!(dir.path().join("processed/a.torrent").exists());
+        assert!(dir.path().join("processed/b.magnet").exists());
+        assert!(!dir.path().join("a.torrent").exists());
+    }
+
+    #[test]
+    fn rejected_files_move_to_failed() {
+        let dir = tempfile::tempdir().unwrap();
+        settled_file(dir.path(), "bad.torrent", "garbage");
+        settled_file(dir.path(), "empty.magnet", "# nothing here\n");
+        let outcomes = scan(&folder(dir.path()), |_| rejected()).outcomes;
+        match &outcomes[..] {
+            [WatchOutcome::Failed { reason: first, .. }, WatchOutcome::Failed { reason: second, .. }] =>
+            {
+                assert!(first.contains("invalid or corrupt"), "{first}");
+                assert_eq!(second, "no magnet links");
+            }
+            other => panic!("unexpected outcomes {other:?}"),
+        }
+        assert!(dir.path().join("failed/bad.torrent").exists());
+        assert!(dir.path().join("failed/empty.magnet").exists());
+    }
+
+    #[test]
+    fn unreachable_daemon_leaves_files_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        settled_file(dir.path(), "a.torrent", "x");
+        settled_file(dir.path(), "b.torrent", "y");
+        let mut calls = 0;
+        let outcomes = scan(&folder(dir.path()), |_| {
+            calls += 1;
+            Err(TransmissionError::Offline)
+        })
+        .outcomes;
+        assert_eq!(calls, 1);
+        assert!(matches!(&outcomes[..], [WatchOutcome::Deferred { .. }]));
+        assert!(dir.path().join("a.torrent").exists());
+        assert!(dir.path().join("b.torrent").exists());
+        assert!(!dir.path().join("failed").exists());
+    }
+
+    #[test]
+    fn skips_unsettled_and_unrelated_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("fresh.torrent"), "x").unwrap();
+        settled_file(dir.path(), "notes.txt", "magnet:?xt=urn:btih:one");
+        fs::create_dir(dir.path().join("sub.torrent")).unwrap();
+        let outcomes = scan(&folder(dir.path()), |_| panic!("nothing should be added")).outcomes;
+        assert!(outcomes.is_empty());
+        assert!(dir.path().join("fresh.torrent").exists());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn broken_symlinks_do_not_stop_the_scan() {
+        let dir = tempfile::tempdir().unwrap();
+        std::os::unix::fs::symlink(dir.path().join("gone"), dir.path().join("dangling.torrent"))
+            .unwrap();
+        settled_file(dir.path(), "a.torrent", "x");
+        let scan = scan(&folder(dir.path()), |_| added("a"));
+        assert!(matches!(&scan.outcomes[..], [WatchOutcome::Added { .. }]));
+        assert!(matches!(&scan.problems[..], [problem] if problem.contains("dangling.torrent")));
+        assert!(dir.path().join("processed/a.torrent").exists());
+    }
+
+    #[test]
+    fn a_missing_folder_is_reported_once_until_it_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = folder(&dir.path().join("unmounted"));
+        let mut problems = WatchProblems::default();
+        let first = scan(&missing, |_| panic!("nothing should be added"));
+        assert!(first.outcomes.is_empty());
+        let fresh = problems.fresh(&missing, &first);
+        assert!(matches!(&fresh[..], [problem] if problem.starts_with("cannot scan watch folder")));
+        let again = scan(&missing, |_| panic!("nothing should be added"));
+        assert!(problems.fresh(&missing, &again).is_empty());
+
+        fs::create_dir(&missing.dir).unwrap();
+        let mounted = scan(&missing, |_| panic!("nothing should be added"));
+        assert!(problems.fresh(&missing, &mounted).is_empty());
+        fs::remove_dir(&missing.dir).unwrap();
+        let gone = scan(&missing, |_| panic!("nothing should be added"));
+        assert_eq!(problems.fresh(&missing, &gone).len(), 1);
+    }
+
+    #[test]
+    fn name_clashes_in_processed_get_a_suffix() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("processed")).unwrap();
+        fs::write(dir.path().join("processed/a.torrent"), "old").unwrap();
+        settled_file(dir.path(), "a.torrent", "new");
+        let outcomes = scan(&folder(dir.path()), |_| added("a")).outcomes;
+        assert!(matches!(&outcomes[..], [WatchOutcome::Added { .. }]));
+        assert_eq!(
+            fs::read_to_string(dir.path().join("processed/a-1.torrent")).unwrap(),
+            "new"
+        );
+    }
+}